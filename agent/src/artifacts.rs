@@ -0,0 +1,252 @@
+//! # Proof Artifact Sweeper
+//!
+//! EZKL writes a witness, proof and calldata file per detection into
+//! `assets/prove` (`<kind>_<tx_hash>.<ext>`). Crashes and aborted proofs
+//! leave them behind, so `PROVE_DIR_MAX_MB` caps the directory: a background
+//! sweep evicts whole sets, least recently written first, until it fits.
+//!
+//! With `PUBLISH_LATEST_PROOF`, each successful proof's set is also copied to
+//! `latest.{json,proof,bytes}` (plus `latest.txt` holding the tx hash) by
+//! atomic rename. Those names carry no hash, so the sweeper never evicts them.
+//!
+//! In warm standby proofs go to the `standby` subdirectory instead, which is
+//! swept to the same cap and never published.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tracing::{info, warn};
+
+/// Where `run_ezkl_pipeline` writes its artifacts
+pub const PROVE_DIR: &str = "assets/prove";
+
+/// Scratch directory for proofs made in warm standby, kept apart from the
+/// live set so rehearsal proofs are never published as `latest.*`
+pub const STANDBY_DIR: &str = "assets/prove/standby";
+
+/// How often the sweeper checks the directory size
+pub const SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Sets written this recently may belong to a proof still in flight
+const MIN_EVICTION_AGE: Duration = Duration::from_secs(120);
+
+/// Artifact kinds and extensions of one proof set
+const ARTIFACTS: [(&str, &str); 3] = [
+    ("witness", "json"),
+    ("vanguard", "proof"),
+    ("calldata", "bytes"),
+];
+
+/// One publish at a time, so concurrent proofs never interleave their copies
+static PUBLISH_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+/// The most recently published proof set
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LatestProof {
+    pub tx_hash: String,
+    pub witness: String,
+    pub proof: String,
+    pub calldata: Vec<u8>,
+}
+
+/// One tx hash's artifacts
+#[derive(Debug, Default)]
+struct ArtifactSet {
+    files: Vec<PathBuf>,
+    bytes: u64,
+    newest: Option<SystemTime>,
+}
+
+/// Evict the oldest artifact sets in `dir` until it holds at most
+/// `max_bytes`. Returns the evicted tx hashes and the bytes each freed.
+pub fn enforce_limit(dir: &Path, max_bytes: u64) -> std::io::Result<Vec<(String, u64)>> {
+    let mut sets: HashMap<String, ArtifactSet> = HashMap::new();
+    let mut total = 0;
+    for entry in std::fs::read_dir(dir)?.flatten() {
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        if !metadata.is_file() {
+            continue;
+        }
+        total += metadata.len();
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let Some(hash) = artifact_hash(&name) else {
+            continue;
+        };
+        let set = sets.entry(hash.to_string()).or_default();
+        set.files.push(entry.path());
+        set.bytes += metadata.len();
+        set.newest = set.newest.max(metadata.modified().ok());
+    }
+
+    let mut sets: Vec<(String, ArtifactSet)> = sets.into_iter().collect();
+    sets.sort_by_key(|(_, set)| set.newest);
+
+    let now = SystemTime::now();
+    let mut evicted = Vec::new();
+    for (hash, set) in sets {
+        if total <= max_bytes {
+            break;
+        }
+        let recent = set
+            .newest
+            .and_then(|t| now.duration_since(t).ok())
+            .is_none_or(|age| age < MIN_EVICTION_AGE);
+        if recent {
+            break;
+        }
+        for file in &set.files {
+            std::fs::remove_file(file)?;
+        }
+        total = total.saturating_sub(set.bytes);
+        evicted.push((hash, set.bytes));
+    }
+    Ok(evicted)
+}
+
+/// `witness_0xab.json` -> `0xab`
+fn artifact_hash(name: &str) -> Option<&str> {
+    let (_, rest) = name.split_once('_')?;
+    let (hash, _) = rest.rsplit_once('.')?;
+    (!hash.is_empty()).then_some(hash)
+}
+
+/// Copy `tx_hash`'s artifacts in `dir` to the stable `latest.*` names. All
+/// copies go to temporary names first and are then renamed, so a missing
+/// artifact publishes nothing and readers never see a partial file; the hash
+/// is renamed last.
+pub fn publish_latest(dir: &Path, tx_hash: &str) -> std::io::Result<()> {
+    let _guard = PUBLISH_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut staged = Vec::new();
+    for (kind, ext) in ARTIFACTS {
+        let tmp = dir.join(format!("latest.{}.tmp", ext));
+        std::fs::copy(dir.join(format!("{}_{}.{}", kind, tx_hash, ext)), &tmp)?;
+        staged.push((tmp, dir.join(format!("latest.{}", ext))));
+    }
+    let tmp = dir.join("latest.txt.tmp");
+    std::fs::write(&tmp, tx_hash)?;
+    staged.push((tmp, dir.join("latest.txt")));
+    for (tmp, path) in staged {
+        std::fs::rename(tmp, path)?;
+    }
+    Ok(())
+}
+
+/// Read the set `publish_latest` last wrote to `dir`
+pub fn read_latest(dir: &Path) -> std::io::Result<LatestProof> {
+    let _guard = PUBLISH_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    Ok(LatestProof {
+        tx_hash: std::fs::read_to_string(dir.join("latest.txt"))?,
+        witness: std::fs::read_to_string(dir.join("latest.json"))?,
+        proof: std::fs::read_to_string(dir.join("latest.proof"))?,
+        calldata: std::fs::read(dir.join("latest.bytes"))?,
+    })
+}
+
+/// Sweep `PROVE_DIR` and `STANDBY_DIR` every `SWEEP_INTERVAL`, keeping
+/// each under `max_bytes`
+pub fn spawn_sweeper(max_bytes: u64) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(SWEEP_INTERVAL);
+        loop {
+            interval.tick().await;
+            for dir in [PROVE_DIR, STANDBY_DIR] {
+                let sweep =
+                    tokio::task::spawn_blocking(move || enforce_limit(Path::new(dir), max_bytes))
+                        .await;
+                match sweep {
+                    Ok(Ok(evicted)) => {
+                        for (hash, bytes) in evicted {
+                            info!(
+                                "Evicted proof artifacts for {} from {} ({} bytes)",
+                                hash, dir, bytes
+                            );
+                        }
+                    }
+                    // Nothing proven yet
+                    Ok(Err(e)) if e.kind() == std::io::ErrorKind::NotFound => {}
+                    Ok(Err(e)) => warn!("Proof artifact sweep of {} failed: {}", dir, e),
+                    Err(e) => warn!("Proof artifact sweep panicked: {}", e),
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+
+    fn write(dir: &Path, name: &str, bytes: usize, age_secs: u64) {
+        let path = dir.join(name);
+        std::fs::write(&path, vec![0u8; bytes]).unwrap();
+        File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(SystemTime::now() - Duration::from_secs(age_secs))
+            .unwrap();
+    }
+
+    #[test]
+    fn evicts_oldest_sets_but_keeps_recent_ones() {
+        let dir = std::env::temp_dir().join(format!("beetrap-prove-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        write(&dir, "witness_0xold.json", 100, 3_600);
+        write(&dir, "calldata_0xold.bytes", 100, 3_500);
+        write(&dir, "witness_0xmid.json", 100, 600);
+        write(&dir, "vanguard_0xmid.proof", 100, 600);
+        write(&dir, "witness_0xnew.json", 100, 10);
+
+        // 500 bytes, limit 300: evicting the oldest set is enough
+        let evicted = enforce_limit(&dir, 300).unwrap();
+        assert_eq!(evicted, vec![("0xold".to_string(), 200)]);
+        assert!(!dir.join("calldata_0xold.bytes").exists());
+        assert!(dir.join("witness_0xmid.json").exists());
+
+        // An in-flight set is never evicted, even over the limit
+        let evicted = enforce_limit(&dir, 0).unwrap();
+        assert_eq!(evicted, vec![("0xmid".to_string(), 200)]);
+        assert!(dir.join("witness_0xnew.json").exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn latest_proof_survives_sweeps() {
+        let dir = std::env::temp_dir().join(format!("beetrap-latest-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        // A set without all three artifacts is not published
+        write(&dir, "witness_0xab.json", 10, 600);
+        assert!(publish_latest(&dir, "0xab").is_err());
+        assert!(!dir.join("latest.json").exists());
+
+        let proof = dir.join("vanguard_0xab.proof");
+        std::fs::write(&proof, "{\"proof\":[]}").unwrap();
+        File::options()
+            .write(true)
+            .open(&proof)
+            .unwrap()
+            .set_modified(SystemTime::now() - Duration::from_secs(600))
+            .unwrap();
+        write(&dir, "calldata_0xab.bytes", 4, 600);
+        publish_latest(&dir, "0xab").unwrap();
+
+        // Evicting the hashed set leaves the published copy
+        let evicted = enforce_limit(&dir, 0).unwrap();
+        assert_eq!(evicted.len(), 1);
+        let latest = read_latest(&dir).unwrap();
+        assert_eq!(latest.tx_hash, "0xab");
+        assert_eq!(latest.proof, "{\"proof\":[]}");
+        assert_eq!(latest.calldata, vec![0u8; 4]);
+        assert!(!dir.join("latest.json.tmp").exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
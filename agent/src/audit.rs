@@ -0,0 +1,208 @@
+//! # Detection Audit Log
+//!
+//! Append-only JSON Lines record of every on-chain trap, kept apart from the
+//! rotating tracing log. Each record is fsync'd before returning.
+//!
+//! Records are hash-chained: each carries `prev_hash`, the Keccak-256 of the
+//! previous line (zero for the first), so editing, dropping or reordering a
+//! line breaks the chain at the line after it. `verify` walks the chain. The
+//! last line has nothing after it; compare its hash with a copy kept
+//! elsewhere to cover it too.
+
+use chrono::{DateTime, Utc};
+use eyre::{Result, WrapErr};
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Keccak256};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::sync::Mutex;
+
+/// One append at a time, so two records never chain off the same line
+static APPEND_LOCK: Mutex<()> = Mutex::new(());
+
+/// Bytes read per step when looking for the last line
+const TAIL_CHUNK: u64 = 4096;
+
+/// One audit record per successful trap submission
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditEntry {
+    pub timestamp: DateTime<Utc>,
+    pub bot: String,
+    pub confidence: f32,
+    /// Keccak-256 of the ONNX model file (0x-prefixed hex)
+    pub model_hash: String,
+    /// Keccak-256 of the submitted proof bytes (0x-prefixed hex)
+    pub proof_hash: String,
+    pub chain_tx: String,
+    /// Follow-up for the trap with the same `chain_tx`: a reorg replaced
+    /// the block it was detected at, so it needs re-verifying
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub reorged: bool,
+}
+
+/// An entry as written: chained to the line before it
+#[derive(Serialize)]
+struct Chained<'a> {
+    #[serde(flatten)]
+    entry: &'a AuditEntry,
+    prev_hash: String,
+}
+
+/// Append `entry` as a single line, chained to the last one, and fsync.
+/// The file is never truncated.
+pub fn append(path: &str, entry: &AuditEntry) -> Result<()> {
+    let _guard = APPEND_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .read(true)
+        .append(true)
+        .open(path)
+        .wrap_err_with(|| format!("Failed to open audit log {}", path))?;
+    let prev = last_line(&mut file)
+        .wrap_err_with(|| format!("Failed to read the last record of {}", path))?;
+
+    let mut line = serde_json::to_string(&Chained {
+        entry,
+        prev_hash: line_hash(prev.as_deref()),
+    })?;
+    line.push('\n');
+    file.write_all(line.as_bytes())?;
+    file.sync_all().wrap_err("Failed to fsync audit log")?;
+    Ok(())
+}
+
+/// Check every record's `prev_hash` in `path`. Returns the number of
+/// records, or an error naming the first line whose link is broken.
+pub fn verify(path: &str) -> Result<usize> {
+    #[derive(Deserialize)]
+    struct Link {
+        prev_hash: String,
+    }
+
+    let file = File::open(path).wrap_err_with(|| format!("Failed to open audit log {}", path))?;
+    let mut prev: Option<String> = None;
+    let mut count = 0;
+    for (i, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        let link: Link = serde_json::from_str(&line)
+            .wrap_err_with(|| format!("Audit log line {} is not a chained record", i + 1))?;
+        if link.prev_hash != line_hash(prev.as_deref()) {
+            return Err(eyre::eyre!(
+                "Audit log chain broken at line {}: line {} was edited, removed or reordered",
+                i + 1,
+                i
+            ));
+        }
+        prev = Some(line);
+        count += 1;
+    }
+    Ok(count)
+}
+
+/// `prev_hash` for a record following `line` (zero at the start)
+fn line_hash(line: Option<&str>) -> String {
+    format!(
+        "0x{}",
+        hex::encode(line.map_or([0; 32], |l| keccak(l.as_bytes())))
+    )
+}
+
+/// The last complete line of `file`, without its newline
+fn last_line(file: &mut File) -> std::io::Result<Option<String>> {
+    let len = file.metadata()?.len();
+    // Skip the trailing newline, then read back until the one before it
+    let end = len.saturating_sub(1);
+    let mut start = end;
+    let mut tail = Vec::new();
+    while start > 0 {
+        let step = TAIL_CHUNK.min(start);
+        start -= step;
+        let mut chunk = vec![0; step as usize];
+        file.seek(SeekFrom::Start(start))?;
+        file.read_exact(&mut chunk)?;
+        chunk.append(&mut tail);
+        tail = chunk;
+        if let Some(newline) = tail.iter().rposition(|b| *b == b'\n') {
+            tail.drain(..=newline);
+            break;
+        }
+    }
+    Ok((!tail.is_empty()).then(|| String::from_utf8_lossy(&tail).into_owned()))
+}
+
+/// Keccak-256 of `data`
+pub fn keccak(data: &[u8]) -> [u8; 32] {
+    Keccak256::digest(data).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(n: u8) -> AuditEntry {
+        AuditEntry {
+            timestamp: Utc::now(),
+            bot: format!("0x{:040x}", n),
+            confidence: 0.9,
+            model_hash: format!("0x{:064x}", 1),
+            proof_hash: format!("0x{:064x}", n),
+            chain_tx: format!("0x{:064x}", n),
+            reorged: false,
+        }
+    }
+
+    #[test]
+    fn edited_records_break_the_chain() {
+        let dir = std::env::temp_dir().join("beetrap-audit-tests");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(format!("audit-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let path = path.to_str().unwrap();
+
+        for n in 1..=3 {
+            append(path, &entry(n)).unwrap();
+        }
+        assert_eq!(verify(path).unwrap(), 3);
+
+        let content = std::fs::read_to_string(path).unwrap();
+        let first: serde_json::Value =
+            serde_json::from_str(content.lines().next().unwrap()).unwrap();
+        assert_eq!(first["prev_hash"], line_hash(None));
+
+        // Lower the confidence of the second trap after the fact
+        let mut lines: Vec<String> = content.lines().map(String::from).collect();
+        lines[1] = lines[1].replace("\"confidence\":0.9", "\"confidence\":0.5");
+        std::fs::write(path, lines.join("\n") + "\n").unwrap();
+        let err = verify(path).unwrap_err().to_string();
+        assert!(err.contains("line 3"), "{}", err);
+
+        // Dropping a record breaks it too
+        let dropped: Vec<&str> = content.lines().skip(1).collect();
+        std::fs::write(path, dropped.join("\n") + "\n").unwrap();
+        assert!(verify(path).unwrap_err().to_string().contains("line 1"));
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn appends_chain_off_the_last_line() {
+        let dir = std::env::temp_dir().join("beetrap-audit-tests");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(format!("audit-tail-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        // A long record spans several tail chunks
+        let mut long = entry(1);
+        long.bot = "0x".to_string() + &"a".repeat(TAIL_CHUNK as usize * 2);
+        append(path.to_str().unwrap(), &long).unwrap();
+        append(path.to_str().unwrap(), &entry(2)).unwrap();
+
+        let mut file = File::open(&path).unwrap();
+        let last = last_line(&mut file).unwrap().unwrap();
+        assert!(last.contains(&format!("0x{:064x}", 2)));
+        assert_eq!(verify(path.to_str().unwrap()).unwrap(), 2);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,135 @@
+//! # Chain Metadata
+//!
+//! Native currency and display scale per chain id, so savings and balances
+//! are labelled in the token actually spent. Gas on L2s costs fractions of a
+//! Gwei; whole-Gwei figures would read as zero there, so each chain also
+//! sets how many decimals its Gwei amounts get.
+
+/// Display metadata for one chain
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Chain {
+    pub id: u64,
+    pub name: &'static str,
+    /// Native currency symbol (gas and value are paid in it)
+    pub symbol: &'static str,
+    /// Decimals shown for Gwei amounts
+    pub gas_decimals: usize,
+}
+
+const KNOWN: &[Chain] = &[
+    chain(1, "Ethereum", "ETH", 2),
+    chain(10, "Optimism", "ETH", 4),
+    chain(56, "BNB Chain", "BNB", 2),
+    chain(100, "Gnosis", "xDAI", 2),
+    chain(130, "Unichain", "ETH", 4),
+    chain(137, "Polygon", "POL", 1),
+    chain(1301, "Unichain Sepolia", "ETH", 4),
+    chain(8453, "Base", "ETH", 4),
+    chain(31337, "Anvil", "ETH", 2),
+    chain(42161, "Arbitrum One", "ETH", 4),
+    chain(43114, "Avalanche", "AVAX", 2),
+    chain(84532, "Base Sepolia", "ETH", 4),
+    chain(421614, "Arbitrum Sepolia", "ETH", 4),
+    chain(11155111, "Sepolia", "ETH", 2),
+];
+
+const fn chain(id: u64, name: &'static str, symbol: &'static str, gas_decimals: usize) -> Chain {
+    Chain {
+        id,
+        name,
+        symbol,
+        gas_decimals,
+    }
+}
+
+impl Chain {
+    /// Metadata for `id`; unknown chains are assumed to be ETH-denominated
+    pub fn by_id(id: u64) -> Self {
+        KNOWN
+            .iter()
+            .find(|c| c.id == id)
+            .copied()
+            .unwrap_or(chain(id, "Unknown", "ETH", 2))
+    }
+}
+
+impl Default for Chain {
+    fn default() -> Self {
+        Self::by_id(1)
+    }
+}
+
+/// How amounts are shown: transaction values and savings on the monitored
+/// chain, balances and gas prices on the chain traps are submitted to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Units {
+    pub monitored: Chain,
+    pub execution: Chain,
+    /// Decimals for native amounts (`ETH_DECIMALS`)
+    pub precision: usize,
+}
+
+impl Default for Units {
+    fn default() -> Self {
+        Self {
+            monitored: Chain::default(),
+            execution: Chain::default(),
+            precision: 4,
+        }
+    }
+}
+
+impl Units {
+    /// Native amount on the monitored chain, e.g. `0.2500 ETH`
+    pub fn monitored_amount(&self, amount: f64) -> String {
+        format!("{:.*} {}", self.precision, amount, self.monitored.symbol)
+    }
+
+    /// Native amount on the execution chain
+    pub fn execution_amount(&self, amount: f64) -> String {
+        format!("{:.*} {}", self.precision, amount, self.execution.symbol)
+    }
+
+    /// Wei as Gwei on the monitored chain
+    pub fn monitored_gas(&self, wei: u128) -> String {
+        gwei(wei, self.monitored.gas_decimals)
+    }
+
+    /// Wei as Gwei on the execution chain
+    pub fn execution_gas(&self, wei: u128) -> String {
+        gwei(wei, self.execution.gas_decimals)
+    }
+}
+
+fn gwei(wei: u128, decimals: usize) -> String {
+    format!("{:.*} Gwei", decimals, wei as f64 / 1e9)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn labels_amounts_in_each_chains_currency() {
+        let units = Units {
+            monitored: Chain::by_id(137),
+            execution: Chain::by_id(8453),
+            precision: 2,
+        };
+        assert_eq!(units.monitored_amount(1.5), "1.50 POL");
+        assert_eq!(units.execution_amount(0.25), "0.25 ETH");
+        // 0.0012 Gwei of L2 gas must not round to zero
+        assert_eq!(units.execution_gas(1_200_000), "0.0012 Gwei");
+        assert_eq!(
+            Units::default().monitored_gas(21_000_000_000_000),
+            "21000.00 Gwei"
+        );
+    }
+
+    #[test]
+    fn unknown_chains_fall_back_to_eth() {
+        let unknown = Chain::by_id(999_999);
+        assert_eq!(unknown.id, 999_999);
+        assert_eq!(unknown.symbol, "ETH");
+    }
+}
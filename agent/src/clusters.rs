@@ -0,0 +1,240 @@
+//! # Address Clusters
+//!
+//! Some bots split a sandwich across addresses they control, so each leg
+//! scores as an ordinary swap from an unrelated sender. With
+//! `CLUSTER_WINDOW_SECS` set, plain transfers seen in the mempool become
+//! funding links between sender and recipient. Addresses connected by links
+//! inside the window form a cluster, e.g. a funder and every address it paid.
+//! Swaps by two members of one cluster in the same block are treated as one
+//! actor: the threshold is lowered and the cluster is shown in the insight
+//! panel. Hubs such as an exchange hot wallet pay many unrelated users, so
+//! an address with too many links is not a link at all.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::{Duration, Instant};
+
+/// An address linked to more than this many others is a hub (an exchange
+/// or a router), not one actor's funder
+const MAX_LINK_DEGREE: usize = 16;
+
+/// A cluster larger than this is not one actor; no cluster is reported
+const MAX_CLUSTER_SIZE: usize = 64;
+
+/// A swap whose sender shares a cluster with another swap in its block
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Coordinated {
+    /// Every cluster member found, the sender first
+    pub members: Vec<String>,
+    /// Members that also swapped in this block
+    pub partners: Vec<String>,
+}
+
+/// Funding links and same-block swaps, bounded by age and link count
+#[derive(Debug)]
+pub struct AddressClusters {
+    window: Duration,
+    max_links: usize,
+    /// (from, to, seen at), oldest first
+    links: VecDeque<(String, String, Instant)>,
+    /// address -> linked address -> number of live links between them
+    neighbours: HashMap<String, HashMap<String, usize>>,
+    /// sender -> block of its latest swap
+    swaps: HashMap<String, u64>,
+}
+
+impl AddressClusters {
+    pub fn new(window: Duration, max_links: usize) -> Self {
+        Self {
+            window,
+            max_links: max_links.max(1),
+            links: VecDeque::new(),
+            neighbours: HashMap::new(),
+            swaps: HashMap::new(),
+        }
+    }
+
+    /// Remember that `from` sent funds to `to` at `at`
+    pub fn link(&mut self, from: &str, to: &str, at: Instant) {
+        let (from, to) = (from.to_lowercase(), to.to_lowercase());
+        if from == to {
+            return;
+        }
+        self.expire(at);
+        while self.links.len() >= self.max_links {
+            self.drop_oldest();
+        }
+        *self.neighbour_count(&from, &to) += 1;
+        *self.neighbour_count(&to, &from) += 1;
+        self.links.push_back((from, to, at));
+    }
+
+    /// Record `sender`'s swap in `block` and report cluster members that
+    /// swapped in the same block
+    pub fn swap(&mut self, sender: &str, block: u64, at: Instant) -> Option<Coordinated> {
+        let sender = sender.to_lowercase();
+        self.expire(at);
+        // Only the current block can coordinate
+        self.swaps.retain(|_, b| *b >= block);
+        self.swaps.insert(sender.clone(), block);
+
+        let members = self.cluster(&sender);
+        let partners: Vec<String> = members
+            .iter()
+            .skip(1)
+            .filter(|m| self.swaps.get(*m) == Some(&block))
+            .cloned()
+            .collect();
+        (!partners.is_empty()).then_some(Coordinated { members, partners })
+    }
+
+    /// `address` and everything linked to it through non-hub addresses,
+    /// breadth first; just `address` when that is more than one actor could be
+    fn cluster(&self, address: &str) -> Vec<String> {
+        let alone = vec![address.to_string()];
+        if self.is_hub(address) {
+            return alone;
+        }
+        let mut members = alone.clone();
+        let mut seen: HashSet<&str> = HashSet::from([address]);
+        let mut next = 0;
+        while next < members.len() {
+            let Some(linked) = self.neighbours.get(&members[next]) else {
+                next += 1;
+                continue;
+            };
+            let mut found: Vec<&String> = linked
+                .keys()
+                .filter(|a| !seen.contains(a.as_str()) && !self.is_hub(a))
+                .collect();
+            found.sort();
+            for address in found {
+                seen.insert(address);
+                members.push(address.clone());
+            }
+            if members.len() > MAX_CLUSTER_SIZE {
+                return alone;
+            }
+            next += 1;
+        }
+        members
+    }
+
+    fn is_hub(&self, address: &str) -> bool {
+        self.neighbours
+            .get(address)
+            .is_some_and(|linked| linked.len() > MAX_LINK_DEGREE)
+    }
+
+    fn neighbour_count(&mut self, a: &str, b: &str) -> &mut usize {
+        self.neighbours
+            .entry(a.to_string())
+            .or_default()
+            .entry(b.to_string())
+            .or_insert(0)
+    }
+
+    fn expire(&mut self, now: Instant) {
+        while self
+            .links
+            .front()
+            .is_some_and(|(_, _, at)| now.saturating_duration_since(*at) > self.window)
+        {
+            self.drop_oldest();
+        }
+    }
+
+    fn drop_oldest(&mut self) {
+        let Some((from, to, _)) = self.links.pop_front() else {
+            return;
+        };
+        self.unlink(&from, &to);
+        self.unlink(&to, &from);
+    }
+
+    fn unlink(&mut self, a: &str, b: &str) {
+        if let Some(linked) = self.neighbours.get_mut(a) {
+            if let Some(count) = linked.get_mut(b) {
+                *count -= 1;
+                if *count == 0 {
+                    linked.remove(b);
+                }
+            }
+            if linked.is_empty() {
+                self.neighbours.remove(a);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn siblings_funded_by_one_source_swap_as_one_actor() {
+        let start = Instant::now();
+        let mut clusters = AddressClusters::new(Duration::from_secs(60), 100);
+        clusters.link("0xFUNDER", "0xa", start);
+        clusters.link("0xfunder", "0xb", start);
+
+        assert_eq!(clusters.swap("0xa", 10, start), None);
+        // Unrelated sender in the same block
+        assert_eq!(clusters.swap("0xc", 10, start), None);
+        let coordinated = clusters.swap("0xB", 10, start).unwrap();
+        assert_eq!(coordinated.members, vec!["0xb", "0xfunder", "0xa"]);
+        assert_eq!(coordinated.partners, vec!["0xa"]);
+
+        // A new block starts over
+        assert_eq!(clusters.swap("0xa", 11, start), None);
+    }
+
+    #[test]
+    fn links_expire_and_are_capped() {
+        let start = Instant::now();
+        let mut clusters = AddressClusters::new(Duration::from_secs(60), 2);
+        clusters.link("0xf", "0xa", start);
+        clusters.link("0xf", "0xb", start);
+        clusters.swap("0xa", 1, start);
+        assert!(clusters.swap("0xb", 1, start).is_some());
+
+        // Over the cap, the oldest link (to 0xa) goes
+        clusters.link("0xf", "0xc", start);
+        assert_eq!(clusters.swap("0xa", 2, start), None);
+        assert!(clusters.swap("0xc", 2, start).is_none());
+        assert!(clusters.swap("0xb", 2, start).is_some());
+
+        // Past the window nothing is linked
+        let later = start + Duration::from_secs(61);
+        clusters.link("0xx", "0xy", later);
+        assert_eq!(clusters.cluster("0xb"), vec!["0xb"]);
+        assert!(!clusters.neighbours.contains_key("0xf"));
+    }
+
+    #[test]
+    fn hubs_do_not_link_their_recipients() {
+        let start = Instant::now();
+        let mut clusters = AddressClusters::new(Duration::from_secs(60), 1_000);
+        for i in 0..=MAX_LINK_DEGREE {
+            clusters.link("0xexchange", &format!("0xuser{}", i), start);
+        }
+        clusters.swap("0xuser0", 1, start);
+        assert_eq!(clusters.swap("0xuser1", 1, start), None);
+        assert_eq!(clusters.cluster("0xexchange"), vec!["0xexchange"]);
+
+        // A real funder next to the hub still clusters
+        clusters.link("0xuser1", "0xsibling", start);
+        let coordinated = clusters.swap("0xsibling", 1, start).unwrap();
+        assert_eq!(coordinated.members, vec!["0xsibling", "0xuser1"]);
+    }
+
+    #[test]
+    fn oversized_clusters_are_not_one_actor() {
+        let start = Instant::now();
+        let mut clusters = AddressClusters::new(Duration::from_secs(60), 1_000);
+        // A chain of links: no hub, but far more members than one actor has
+        for i in 0..=MAX_CLUSTER_SIZE {
+            clusters.link(&format!("0x{}", i), &format!("0x{}", i + 1), start);
+        }
+        assert_eq!(clusters.cluster("0x0"), vec!["0x0"]);
+    }
+}
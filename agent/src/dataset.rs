@@ -0,0 +1,222 @@
+//! # Retraining Dataset
+//!
+//! With `DATASET_CSV` set, every scored transaction is appended as one CSV
+//! row: raw features (in `FEATURE_NAMES` order, before normalization), the
+//! model score and how the pipeline ended. Analysts review rows from the TUI
+//! (`t` confirms the model's verdict, `n` refutes it); the resulting
+//! true/false positive/negative label is appended as its own row carrying
+//! only the hash, sender and label. The latest label for a hash wins, and
+//! labels are read back at startup so they survive a restart.
+
+use crate::processor::FEATURE_NAMES;
+use crate::types::{ReviewLabel, SkipReason, TrapAction};
+use chrono::{DateTime, Utc};
+use eyre::{Result, WrapErr};
+use std::collections::HashMap;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::sync::Mutex;
+
+/// Serializes appends so the header is written once and rows never interleave
+static WRITE_LOCK: Mutex<()> = Mutex::new(());
+
+/// A scored transaction waiting for its outcome
+#[derive(Debug, Clone)]
+pub struct Sample {
+    pub scored_at: DateTime<Utc>,
+    pub sender: String,
+    pub raw: [f32; 6],
+    pub score: f32,
+    /// Set once the detection goes to the chain
+    pub action: Option<TrapAction>,
+}
+
+/// Scored transactions waiting for their outcome, and the file they go to
+#[derive(Debug)]
+pub struct Recorder {
+    path: String,
+    pending: tokio::sync::Mutex<HashMap<String, Sample>>,
+}
+
+impl Recorder {
+    pub fn new(path: String) -> Self {
+        Self {
+            path,
+            pending: Default::default(),
+        }
+    }
+
+    /// Remember `sample` until `tx_hash` finishes
+    pub async fn scored(&self, tx_hash: &str, sample: Sample) {
+        self.pending
+            .lock()
+            .await
+            .insert(tx_hash.to_string(), sample);
+    }
+
+    /// Note the on-chain action `tx_hash` is submitted with
+    pub async fn submitting(&self, tx_hash: &str, action: TrapAction) {
+        if let Some(sample) = self.pending.lock().await.get_mut(tx_hash) {
+            sample.action = Some(action);
+        }
+    }
+
+    /// Write the row for `tx_hash` given how `process_transaction` ended.
+    /// Transactions that were never scored have no sample and no row.
+    pub async fn finish(&self, tx_hash: &str, outcome: &Result<Option<SkipReason>>) {
+        let Some(sample) = self.pending.lock().await.remove(tx_hash) else {
+            return;
+        };
+        let action = sample.action.unwrap_or_default();
+        let (outcome, trapped) = match outcome {
+            Ok(None) => (action.to_string(), action == TrapAction::Trap),
+            Ok(Some(reason)) => (reason.to_string(), false),
+            Err(_) => ("Error".to_string(), false),
+        };
+        let path = self.path.clone();
+        let tx_hash = tx_hash.to_string();
+        match tokio::task::spawn_blocking(move || {
+            append_sample(&path, &tx_hash, &sample, &outcome, trapped)
+        })
+        .await
+        {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => tracing::error!("Failed to write dataset row: {:?}", e),
+            Err(e) => tracing::error!("Dataset task panicked: {}", e),
+        }
+    }
+}
+
+fn header() -> String {
+    format!(
+        "timestamp,tx_hash,sender,{},score,outcome,trapped,label",
+        FEATURE_NAMES.join(",")
+    )
+}
+
+/// Append a scored row; `outcome` is the skip reason or the on-chain action
+fn append_sample(
+    path: &str,
+    tx_hash: &str,
+    sample: &Sample,
+    outcome: &str,
+    trapped: bool,
+) -> Result<()> {
+    let features: Vec<String> = sample.raw.iter().map(|v| v.to_string()).collect();
+    append_row(
+        path,
+        &format!(
+            "{},{},{},{},{},{},{},",
+            sample.scored_at.to_rfc3339(),
+            tx_hash,
+            sample.sender,
+            features.join(","),
+            sample.score,
+            outcome,
+            trapped
+        ),
+    )
+}
+
+/// Append an analyst label for `tx_hash`
+pub fn append_label(path: &str, tx_hash: &str, sender: &str, label: ReviewLabel) -> Result<()> {
+    append_row(
+        path,
+        &format!(
+            "{},{},{},{},{}",
+            Utc::now().to_rfc3339(),
+            tx_hash,
+            sender,
+            ",".repeat(FEATURE_NAMES.len() + 2),
+            label
+        ),
+    )
+}
+
+/// Latest label per tx hash in `path`; a missing file has none
+pub fn load_labels(path: &str) -> Result<HashMap<String, ReviewLabel>> {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(HashMap::new()),
+        Err(e) => return Err(e).wrap_err_with(|| format!("Failed to read dataset {}", path)),
+    };
+    let mut labels = HashMap::new();
+    for line in contents.lines().skip(1) {
+        let tx_hash = line.split(',').nth(1).unwrap_or("");
+        let label = line.rsplit(',').next().unwrap_or("");
+        if let Ok(label) = label.parse() {
+            labels.insert(tx_hash.to_string(), label);
+        }
+    }
+    Ok(labels)
+}
+
+fn append_row(path: &str, row: &str) -> Result<()> {
+    let _guard = WRITE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .wrap_err_with(|| format!("Failed to open dataset {}", path))?;
+    if file.metadata()?.len() == 0 {
+        writeln!(file, "{}", header())?;
+    }
+    writeln!(file, "{}", row)?;
+    file.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn rows_and_labels_share_one_schema() {
+        let path = std::env::temp_dir().join(format!("beetrap-dataset-{}.csv", std::process::id()));
+        let path = path.to_str().unwrap();
+        let _ = std::fs::remove_file(path);
+
+        let sample = Sample {
+            scored_at: Utc::now(),
+            sender: "0xbot".to_string(),
+            raw: [30.0, 2.0, 0.5, 140_000.0, 1.5, 3.0],
+            score: 0.93,
+            action: None,
+        };
+        let recorder = Recorder::new(path.to_string());
+        for hash in ["0x01", "0x02"] {
+            recorder.scored(hash, sample.clone()).await;
+        }
+        recorder.submitting("0x01", TrapAction::Trap).await;
+        recorder.finish("0x01", &Ok(None)).await;
+        recorder
+            .finish("0x02", &Ok(Some(SkipReason::BelowValueFloor)))
+            .await;
+        // Never scored: no row
+        recorder
+            .finish("0x03", &Ok(Some(SkipReason::PlainTransfer)))
+            .await;
+        append_label(path, "0x02", "0xbot", ReviewLabel::FalseNegative).unwrap();
+
+        let contents = std::fs::read_to_string(path).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].starts_with("timestamp,tx_hash,sender,gas_price_gwei,"));
+        let columns = lines[0].split(',').count();
+        assert!(lines.iter().all(|l| l.split(',').count() == columns));
+
+        let trapped: Vec<&str> = lines[1].split(',').collect();
+        assert_eq!(&trapped[3..5], ["30", "2"]);
+        assert_eq!(&trapped[columns - 3..], ["trap", "true", ""]);
+        assert!(lines[2].contains(",Below Value Floor,false,"));
+        assert!(lines[3].ends_with(",false_negative"));
+
+        // Labels come back after a restart; the latest one wins
+        append_label(path, "0x02", "0xbot", ReviewLabel::TrueNegative).unwrap();
+        let labels = load_labels(path).unwrap();
+        assert_eq!(labels.len(), 1);
+        assert_eq!(labels["0x02"], ReviewLabel::TrueNegative);
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
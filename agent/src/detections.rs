@@ -0,0 +1,356 @@
+//! # Detection Stream
+//!
+//! Continuous JSON Lines record of every detection as it happens, for
+//! unattended runs feeding downstream analytics. Unlike the audit log this
+//! includes detections that never reached the chain, and it rotates by size.
+//!
+//! The same records can go to a named pipe (`FIFO_PATH`) for shell
+//! integrations, e.g. `cat detections.fifo | jq .bot`. Without a reader
+//! they are dropped, so the agent never waits on a consumer. Named pipes
+//! are unix only.
+
+use crate::processor::FEATURE_NAMES;
+use crate::types::Detection;
+use chrono::{DateTime, Utc};
+use eyre::{Result, WrapErr};
+use serde::Serialize;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::sync::Mutex;
+use std::time::Duration;
+#[cfg(unix)]
+use tokio::io::AsyncWriteExt;
+#[cfg(unix)]
+use tokio::net::unix::pipe;
+use tracing::{debug, warn};
+
+/// Serializes appends so a rotation never interleaves with another write
+static WRITE_LOCK: Mutex<()> = Mutex::new(());
+
+/// Lines waiting for the FIFO writer before new ones are dropped
+const FIFO_QUEUE: usize = 256;
+
+/// A reader that stops reading loses lines after this long
+const FIFO_WRITE_TIMEOUT: Duration = Duration::from_millis(200);
+
+/// One line per detection
+#[derive(Debug, Clone, Serialize)]
+pub struct DetectionRecord {
+    pub detected_at: DateTime<Utc>,
+    pub bot: String,
+    pub tx_hash: String,
+    pub confidence: f32,
+    pub reason: String,
+    /// Time from mempool receipt to detection
+    pub latency_ms: u64,
+    /// Chain head when detected
+    pub block: u64,
+    /// Signed transaction, for re-verification after it left the mempool
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub raw_tx: Option<String>,
+    /// Normalized model input by feature name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub features: Option<serde_json::Value>,
+    /// Follow-up for the detection with the same `tx_hash`: a reorg
+    /// replaced `block` or an earlier one since
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub reorged: bool,
+}
+
+impl From<&Detection> for DetectionRecord {
+    fn from(d: &Detection) -> Self {
+        Self {
+            detected_at: d.detected_at,
+            bot: d.bot_address.clone(),
+            tx_hash: d.tx_hash.clone(),
+            confidence: d.confidence,
+            reason: d.reason.to_string(),
+            latency_ms: d.latency.as_millis() as u64,
+            block: d.block,
+            raw_tx: d.raw_tx.clone(),
+            features: d.features.as_ref().map(features_json),
+            reorged: d.reorged,
+        }
+    }
+}
+
+/// `{"priority_fee_gwei": 1.2, ...}` in model input order
+pub fn features_json(features: &[f32; 6]) -> serde_json::Value {
+    FEATURE_NAMES
+        .iter()
+        .zip(features)
+        .map(|(name, value)| (name.to_string(), serde_json::json!(value)))
+        .collect::<serde_json::Map<_, _>>()
+        .into()
+}
+
+/// Append `detection` to `path` and flush.
+///
+/// Once the file reaches `max_bytes` it is renamed to `<path>.<timestamp>`
+/// and a fresh file is started; `0` disables rotation.
+pub fn append(path: &str, max_bytes: u64, detection: &Detection) -> Result<()> {
+    let mut line = serde_json::to_string(&DetectionRecord::from(detection))?;
+    line.push('\n');
+
+    let _guard = WRITE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+
+    if max_bytes > 0 {
+        let size = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+        if size >= max_bytes {
+            let rotated = format!("{}.{}", path, Utc::now().format("%Y%m%dT%H%M%S"));
+            std::fs::rename(path, &rotated)
+                .wrap_err_with(|| format!("Failed to rotate {} to {}", path, rotated))?;
+        }
+    }
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .wrap_err_with(|| format!("Failed to open detections stream {}", path))?;
+    file.write_all(line.as_bytes())?;
+    file.flush()?;
+    Ok(())
+}
+
+/// Detection records written to a named pipe by a background task
+#[derive(Debug, Clone)]
+pub struct DetectionFifo {
+    lines: tokio::sync::mpsc::Sender<String>,
+}
+
+impl DetectionFifo {
+    /// Start writing to the FIFO at `path`, which must already exist (`mkfifo`)
+    #[cfg(unix)]
+    pub fn spawn(path: &str) -> Result<Self> {
+        use std::os::unix::fs::FileTypeExt;
+        let metadata = std::fs::metadata(path)
+            .wrap_err_with(|| format!("FIFO_PATH {} not found (create it with mkfifo)", path))?;
+        if !metadata.file_type().is_fifo() {
+            return Err(eyre::eyre!("FIFO_PATH {} is not a named pipe", path));
+        }
+        let (lines, rx) = tokio::sync::mpsc::channel(FIFO_QUEUE);
+        tokio::spawn(write_fifo(path.to_string(), rx));
+        Ok(Self { lines })
+    }
+
+    #[cfg(not(unix))]
+    pub fn spawn(path: &str) -> Result<Self> {
+        Err(eyre::eyre!(
+            "FIFO_PATH {} needs named pipes, which this platform lacks",
+            path
+        ))
+    }
+
+    /// Queue `detection` for the pipe; dropped if the writer is backed up
+    pub fn send(&self, detection: &Detection) {
+        let line = match serde_json::to_string(&DetectionRecord::from(detection)) {
+            Ok(line) => line + "\n",
+            Err(e) => {
+                warn!("Failed to serialize detection for FIFO: {}", e);
+                return;
+            }
+        };
+        if self.lines.try_send(line).is_err() {
+            debug!("FIFO queue full, dropped {}", detection.tx_hash);
+        }
+    }
+}
+
+/// Write queued lines, opening the pipe whenever a reader is attached. The
+/// pipe stays open between lines so `cat` doesn't see end-of-file.
+///
+/// A write that times out may have left part of its line in the pipe (a
+/// record over PIPE_BUF is not written atomically). The pipe is then closed,
+/// so the stalled reader sees end-of-file after the partial line, and nothing
+/// more is written until that reader has gone; otherwise the next line would
+/// be glued onto half a record.
+#[cfg(unix)]
+async fn write_fifo(path: String, mut rx: tokio::sync::mpsc::Receiver<String>) {
+    let mut pipe: Option<pipe::Sender> = None;
+    let mut stalled = false;
+    while let Some(line) = rx.recv().await {
+        if pipe.is_none() {
+            // Non-blocking open: fails at once (ENXIO) while nobody reads
+            match pipe::OpenOptions::new().open_sender(&path) {
+                Ok(_) if stalled => {
+                    debug!(
+                        "FIFO reader on {} is still stalled, dropped a detection",
+                        path
+                    );
+                    continue;
+                }
+                Ok(sender) => {
+                    tracing::info!("FIFO reader attached to {}", path);
+                    pipe = Some(sender);
+                }
+                Err(e) => {
+                    stalled = false;
+                    debug!("No FIFO reader on {} ({}), dropped a detection", path, e);
+                    continue;
+                }
+            }
+        }
+        let Some(sender) = pipe.as_mut() else {
+            continue;
+        };
+        match tokio::time::timeout(FIFO_WRITE_TIMEOUT, sender.write_all(line.as_bytes())).await {
+            Ok(Ok(())) => {}
+            // EPIPE: the reader went away; reopen for the next line
+            Ok(Err(e)) => {
+                tracing::info!("FIFO reader on {} detached ({})", path, e);
+                pipe = None;
+            }
+            Err(_) => {
+                warn!(
+                    "FIFO reader on {} is not reading, closed the pipe and dropped a detection",
+                    path
+                );
+                pipe = None;
+                stalled = true;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::DetectionReason;
+
+    fn detection(n: u8) -> Detection {
+        Detection {
+            bot_address: format!("0x{:040x}", n),
+            tx_hash: format!("0x{:064x}", n),
+            confidence: 0.97,
+            detected_at: Utc::now(),
+            latency: Duration::from_millis(42),
+            reason: DetectionReason::HighGasFrontrun,
+            raw_tx: (n == 2).then(|| "0x02f87001".to_string()),
+            block: 100,
+            reorged: false,
+            features: (n == 2).then_some([0.5, -1.0, 0.0, 0.25, 2.0, 0.0]),
+        }
+    }
+
+    #[test]
+    fn appends_lines_and_rotates_by_size() {
+        let dir = std::env::temp_dir().join("beetrap-detections-tests");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("detections.jsonl");
+        let path = path.to_str().unwrap();
+
+        append(path, 0, &detection(1)).unwrap();
+        append(path, 0, &detection(2)).unwrap();
+        let content = std::fs::read_to_string(path).unwrap();
+        let lines: Vec<serde_json::Value> = content
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[1]["latency_ms"], 42);
+        assert_eq!(lines[1]["reason"], "High Gas Frontrun");
+        // Raw bytes only when captured
+        assert!(lines[0].get("raw_tx").is_none());
+        assert_eq!(lines[1]["raw_tx"], "0x02f87001");
+        assert!(lines[0].get("features").is_none());
+        assert_eq!(lines[1]["features"]["gas_price_gwei"], 0.5);
+        assert_eq!(lines[1]["features"]["priority_fee_gwei"], -1.0);
+        assert_eq!(lines[1]["features"]["tx_index"], 0.0);
+        assert!(lines[0].get("reorged").is_none());
+
+        // A reorg follow-up repeats the detection with the flag set
+        let mut reorged = detection(1);
+        reorged.reorged = true;
+        append(path, 0, &reorged).unwrap();
+        let last = std::fs::read_to_string(path).unwrap();
+        let last: serde_json::Value = serde_json::from_str(last.lines().last().unwrap()).unwrap();
+        assert_eq!(last["tx_hash"], lines[0]["tx_hash"]);
+        assert_eq!(last["reorged"], true);
+
+        // Already past 1 byte: the next append starts a new file
+        append(path, 1, &detection(3)).unwrap();
+        assert_eq!(std::fs::read_to_string(path).unwrap().lines().count(), 1);
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 2);
+    }
+
+    #[cfg(unix)]
+    fn make_fifo(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join("beetrap-fifo-tests");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        let _ = std::fs::remove_file(&path);
+        let status = std::process::Command::new("mkfifo")
+            .arg(&path)
+            .status()
+            .unwrap();
+        assert!(status.success());
+        path
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn fifo_drops_lines_without_a_reader() {
+        use tokio::io::AsyncBufReadExt;
+
+        let dir = std::env::temp_dir().join("beetrap-fifo-tests");
+        std::fs::create_dir_all(&dir).unwrap();
+        let plain = dir.join("plain.jsonl");
+        std::fs::write(&plain, "").unwrap();
+        assert!(DetectionFifo::spawn(plain.to_str().unwrap()).is_err());
+
+        let path = make_fifo("detections.fifo");
+        let fifo = DetectionFifo::spawn(path.to_str().unwrap()).unwrap();
+
+        // Nobody reading: returns at once
+        fifo.send(&detection(1));
+
+        let reader = pipe::OpenOptions::new().open_receiver(&path).unwrap();
+        let mut lines = tokio::io::BufReader::new(reader).lines();
+        fifo.send(&detection(2));
+        let wanted = detection(2).tx_hash;
+        let found = tokio::time::timeout(Duration::from_secs(5), async {
+            while let Some(line) = lines.next_line().await.unwrap() {
+                let record: serde_json::Value = serde_json::from_str(&line).unwrap();
+                if record["tx_hash"] == wanted.as_str() {
+                    return true;
+                }
+            }
+            false
+        })
+        .await;
+        assert_eq!(found, Ok(true));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn stalled_reader_gets_end_of_file_not_spliced_records() {
+        use tokio::io::AsyncReadExt;
+
+        let path = make_fifo("stalled.fifo");
+        let fifo = DetectionFifo::spawn(path.to_str().unwrap()).unwrap();
+        let mut reader = pipe::OpenOptions::new().open_receiver(&path).unwrap();
+
+        // Fill the pipe buffer while nobody reads, until a write times out
+        for _ in 0..600 {
+            fifo.send(&detection(2));
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+        tokio::time::sleep(FIFO_WRITE_TIMEOUT * 2).await;
+
+        // The writer closed the pipe: the stream ends, and every complete
+        // line in it is a whole record
+        let mut out = Vec::new();
+        tokio::time::timeout(Duration::from_secs(5), reader.read_to_end(&mut out))
+            .await
+            .expect("the stalled pipe is closed")
+            .unwrap();
+        let out = String::from_utf8(out).unwrap();
+        let complete = out.rsplit_once('\n').map_or("", |(lines, _)| lines);
+        assert!(complete.lines().count() > 0);
+        for line in complete.lines() {
+            serde_json::from_str::<serde_json::Value>(line).unwrap();
+        }
+    }
+}
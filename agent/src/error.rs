@@ -0,0 +1,111 @@
+//! # Error Kinds
+//!
+//! Typed failures for the processor and network modules, so callers can tell
+//! a dropped RPC connection from a bad model or a failed proof without
+//! string-matching. Entry points still return `eyre::Result`: these convert
+//! with `?` and can be recovered with `report.downcast_ref::<SentinelError>()`.
+
+use alloy::transports::{RpcError, TransportError};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum SentinelError {
+    /// Endpoint unreachable, timed out, or the connection dropped
+    #[error("RPC disconnected: {0}")]
+    RpcDisconnected(String),
+    /// The node answered, but with an error
+    #[error("RPC request failed: {0}")]
+    Rpc(String),
+    #[error("Failed to load ONNX model from {path}: {reason}")]
+    ModelLoad { path: String, reason: String },
+    #[error("Inference failed: {0}")]
+    Inference(String),
+    /// The `ezkl` binary could not be launched at all
+    #[error("Failed to execute ezkl {command}: {reason}")]
+    ProverUnavailable { command: String, reason: String },
+    /// An EZKL stage ran and failed (after any retries)
+    #[error("{stage} failed: {reason}")]
+    ProofFailed { stage: &'static str, reason: String },
+    #[error("Proof is {size} bytes, exceeding MAX_PROOF_BYTES ({max}); refusing to submit")]
+    ProofTooLarge { size: usize, max: usize },
+    #[error(
+        "Proof has {count} public inputs, exceeding MAX_PUBLIC_INPUTS ({max}); refusing to submit"
+    )]
+    TooManyPublicInputs { count: usize, max: usize },
+    /// The trap transaction was rejected, reverted or never confirmed
+    #[error("Submission failed: {0}")]
+    Submission(String),
+    /// `SUBMIT_QUEUE_MAX` submissions were already waiting for pacing
+    #[error("Submission queue full ({queued} waiting); dropped")]
+    SubmissionQueueFull { queued: usize },
+    /// Warm standby: everything up to submission ran, but the agent is not armed
+    #[error("Agent is not armed; submission withheld")]
+    Disarmed,
+}
+
+impl SentinelError {
+    /// Stable short name for logs and counters
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::RpcDisconnected(_) => "rpc_disconnected",
+            Self::Rpc(_) => "rpc",
+            Self::ModelLoad { .. } => "model_load",
+            Self::Inference(_) => "inference",
+            Self::ProverUnavailable { .. } => "prover_unavailable",
+            Self::ProofFailed { .. } => "proof_failed",
+            Self::ProofTooLarge { .. } => "proof_too_large",
+            Self::TooManyPublicInputs { .. } => "too_many_public_inputs",
+            Self::Submission(_) => "submission",
+            Self::SubmissionQueueFull { .. } => "submission_queue_full",
+            Self::Disarmed => "disarmed",
+        }
+    }
+}
+
+impl From<TransportError> for SentinelError {
+    fn from(e: TransportError) -> Self {
+        match e {
+            RpcError::Transport(_) | RpcError::NullResp | RpcError::LocalUsageError(_) => {
+                Self::RpcDisconnected(e.to_string())
+            }
+            _ => Self::Rpc(e.to_string()),
+        }
+    }
+}
+
+impl From<alloy::contract::Error> for SentinelError {
+    fn from(e: alloy::contract::Error) -> Self {
+        match e {
+            alloy::contract::Error::TransportError(e) => e.into(),
+            other => Self::Rpc(other.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::transports::TransportErrorKind;
+
+    #[test]
+    fn transport_errors_split_into_disconnects_and_node_errors() {
+        let dropped: TransportError = TransportErrorKind::backend_gone();
+        assert_eq!(SentinelError::from(dropped).kind(), "rpc_disconnected");
+
+        let node: TransportError = RpcError::UnsupportedFeature("eth_foo");
+        assert_eq!(SentinelError::from(node).kind(), "rpc");
+    }
+
+    #[test]
+    fn converts_into_eyre_and_back() {
+        let report = eyre::Report::from(SentinelError::ProofFailed {
+            stage: "Proof generation",
+            reason: "bad witness".to_string(),
+        });
+        assert!(matches!(
+            report.downcast_ref::<SentinelError>(),
+            Some(SentinelError::ProofFailed { .. })
+        ));
+        assert_eq!(report.to_string(), "Proof generation failed: bad witness");
+    }
+}
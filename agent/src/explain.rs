@@ -0,0 +1,256 @@
+//! # Explain Subcommand
+//!
+//! `beetrap-sentinel explain <tx_hash>` fetches one transaction, runs feature
+//! extraction and inference on it once, and prints every step up to the
+//! decision. Nothing is proven or submitted and the TUI never starts, so it
+//! can be scripted while tuning thresholds or debugging a model.
+
+use crate::processor::{explain_transaction, Explanation, ProcessorSettings, FEATURE_NAMES};
+use crate::types::{Config, FeeModel, ModelTask, PendingTransaction, SkipReason};
+use alloy::primitives::TxHash;
+use alloy::providers::{Provider, ProviderBuilder, WsConnect};
+use alloy::rpc::types::BlockTransactionsKind;
+use eyre::{Result, WrapErr};
+use std::str::FromStr;
+use std::time::Instant;
+
+/// Run `explain` for `hash` and print the report to stdout
+pub async fn run(config: &Config, hash: &str) -> Result<()> {
+    let tx_hash =
+        TxHash::from_str(hash).wrap_err_with(|| format!("Invalid transaction hash: {}", hash))?;
+    let provider = ProviderBuilder::new()
+        .on_ws(WsConnect::new(&config.rpc_url))
+        .await
+        .wrap_err("Failed to connect to the mempool RPC")?;
+    let tx = provider
+        .get_transaction_by_hash(tx_hash)
+        .await?
+        .ok_or_else(|| eyre::eyre!("Transaction {} not found", tx_hash))?;
+
+    let targets = crate::indexer::Targets::parse(
+        &config.pool_manager_addresses,
+        &config.universal_router_addresses,
+    )?;
+
+    // Mined transactions have a real position; pending ones count as first
+    let tx_index = tx.transaction_index.unwrap_or(0) as usize;
+    let block_number = match tx.block_number {
+        Some(block) => block,
+        None => provider.get_block_number().await?,
+    };
+
+    let legacy_fees = match config.fee_model {
+        FeeModel::Legacy => true,
+        FeeModel::Eip1559 => false,
+        FeeModel::Auto => provider
+            .get_block_by_number(block_number.into(), BlockTransactionsKind::Hashes)
+            .await?
+            .is_some_and(|block| block.header.base_fee_per_gas.is_none()),
+    };
+    let pending = crate::indexer::pending_transaction(
+        tx_hash.to_string(),
+        &tx,
+        &targets,
+        Instant::now(),
+        None,
+        legacy_fees,
+    );
+
+    // Gas is estimated on the execution chain, as in the live pipeline
+    let estimator = if config.scoring_only {
+        None
+    } else {
+        Some(
+            ProviderBuilder::new()
+                .on_ws(WsConnect::new(&config.execution_rpc_url))
+                .await
+                .wrap_err("Failed to connect to the execution RPC")?,
+        )
+    };
+
+    let settings = ProcessorSettings::from(config);
+    let extractor = crate::features::by_name(&config.feature_extractor)?;
+    let explanation = explain_transaction(
+        &pending,
+        tx_index,
+        block_number,
+        estimator.as_ref(),
+        &config.model_path,
+        &settings,
+        extractor.as_ref(),
+    )
+    .await?;
+
+    for line in report(&pending, tx_index, block_number, &explanation, &settings) {
+        println!("{}", line);
+    }
+    Ok(())
+}
+
+/// Human-readable report, one line per entry
+fn report(
+    tx: &PendingTransaction,
+    tx_index: usize,
+    block_number: u64,
+    explanation: &Explanation,
+    settings: &ProcessorSettings,
+) -> Vec<String> {
+    let decision = &explanation.decision;
+    let mut lines = vec![
+        format!("Transaction: {}", tx.hash),
+        format!(
+            "From:        {} -> {} ({})",
+            tx.from,
+            tx.to.as_deref().unwrap_or("contract creation"),
+            tx.target
+        ),
+        format!("Block:       #{} (index {})", block_number, tx_index),
+        format!("Model hash:  0x{}", hex::encode(explanation.model_hash)),
+        String::new(),
+        format!("{:<20} {:>14} {:>12}", "Feature", "Raw", "Normalized"),
+    ];
+    for (i, name) in FEATURE_NAMES.iter().enumerate() {
+        lines.push(format!(
+            "{:<20} {:>14.4} {:>12.4}",
+            name, explanation.raw[i], explanation.normalized[i]
+        ));
+    }
+    if !explanation.clamped.is_empty() {
+        lines.push(format!(
+            "Clamped:       {} (±{}σ)",
+            explanation.clamped.join(", "),
+            settings.feature_clamp_sigma
+        ));
+    }
+    lines.push(format!(
+        "Gas estimate:  {}",
+        if explanation.estimate_reverted {
+            "reverted"
+        } else {
+            "ok"
+        }
+    ));
+    lines.push(String::new());
+    lines.push(match settings.model_task {
+        ModelTask::Classification => format!("Probability:   {:.4}", decision.score),
+        ModelTask::Regression => format!("Profit (est.): {:.4}", decision.score),
+    });
+    lines.push(format!(
+        "Threshold:     {:.4} ({}, base {:.4})",
+        decision.threshold, decision.decided_by, decision.base_threshold
+    ));
+    lines.push(format!("Reason:        {}", decision.reason));
+
+    let verdict = match explanation.unscored {
+        Some(reason) => format!("not scored live ({})", reason),
+        None if !decision.trap => "SAFE".to_string(),
+        None if tx.value < settings.min_trap_value_wei => {
+            format!("DETECTED, not trapped ({})", SkipReason::BelowValueFloor)
+        }
+        None => "TRAP".to_string(),
+    };
+    lines.push(format!("Decision:      {}", verdict));
+
+    lines.push(String::new());
+    lines.push("Contributions (per +1σ):".to_string());
+    for contribution in &explanation.contributions {
+        lines.push(format!(
+            "  {:<20} {:+.4}",
+            contribution.feature, contribution.delta
+        ));
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{
+        DecisionModifier, DecisionTrace, DetectionReason, FeatureContribution, TxTarget,
+    };
+
+    fn explanation(score: f32, unscored: Option<SkipReason>) -> Explanation {
+        Explanation {
+            raw: [30.0, 2.0, 0.7, 140_000.0, 1.5, 3.0],
+            normalized: [0.1, 0.2, 0.3, 0.4, 0.5, 0.6],
+            clamped: Vec::new(),
+            estimate_reverted: true,
+            model_hash: [0xab; 32],
+            unscored,
+            decision: DecisionTrace {
+                score,
+                smoothed: None,
+                reason: DetectionReason::GenericMEV,
+                base_threshold: 0.8,
+                threshold: 0.8,
+                decided_by: DecisionModifier::GlobalThreshold,
+                trap: score >= 0.8,
+            },
+            contributions: vec![FeatureContribution {
+                feature: "priority_fee_gwei",
+                delta: 0.12,
+            }],
+        }
+    }
+
+    fn tx(value: u128) -> PendingTransaction {
+        PendingTransaction {
+            hash: "0x01".to_string(),
+            from: "0x02".to_string(),
+            to: Some("0x03".to_string()),
+            value,
+            gas_price: None,
+            max_fee_per_gas: None,
+            max_priority_fee_per_gas: None,
+            priority_fee: 0,
+            gas_limit: 200_000,
+            input: vec![0x01],
+            received_at: Instant::now(),
+            chain_id: 1,
+            pool_key: None,
+            max_fee_per_blob_gas: None,
+            blob_count: 0,
+            approves: None,
+            target_address: None,
+            nonce: 0,
+            gas_escalation: None,
+            raw: None,
+            target: TxTarget::Router,
+        }
+    }
+
+    fn verdict(lines: &[String]) -> &str {
+        lines
+            .iter()
+            .find_map(|l| l.strip_prefix("Decision:"))
+            .unwrap()
+            .trim()
+    }
+
+    #[test]
+    fn reports_the_would_be_decision() {
+        let settings = ProcessorSettings {
+            min_trap_value_wei: 1_000,
+            ..Default::default()
+        };
+        let trap = report(&tx(5_000), 3, 100, &explanation(0.93, None), &settings);
+        assert_eq!(verdict(&trap), "TRAP");
+        assert!(trap.iter().any(|l| l.starts_with("priority_fee_gwei")));
+        assert!(trap.iter().any(|l| l == "Gas estimate:  reverted"));
+
+        let safe = report(&tx(5_000), 3, 100, &explanation(0.2, None), &settings);
+        assert_eq!(verdict(&safe), "SAFE");
+
+        let low_value = report(&tx(10), 3, 100, &explanation(0.93, None), &settings);
+        assert!(verdict(&low_value).starts_with("DETECTED, not trapped"));
+
+        let unscored = report(
+            &tx(5_000),
+            3,
+            100,
+            &explanation(0.93, Some(SkipReason::PlainTransfer)),
+            &settings,
+        );
+        assert_eq!(verdict(&unscored), "not scored live (Plain Transfer)");
+    }
+}
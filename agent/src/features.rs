@@ -0,0 +1,107 @@
+//! # Feature Extraction
+//!
+//! Turns a pending transaction into the model's `FeatureVector`. Deployments
+//! with a different feature definition implement `FeatureExtractor` and
+//! register it in `by_name`; `FEATURE_EXTRACTOR` selects one at startup.
+
+use crate::types::{FeatureVector, PendingTransaction};
+use eyre::Result;
+use std::sync::Arc;
+
+/// Chain-side inputs gathered by the processor before extraction
+#[derive(Debug, Clone, Copy)]
+pub struct ChainContext {
+    /// Estimated position of the transaction in the block
+    pub tx_index: usize,
+    /// `eth_estimateGas` result, or a fallback when estimation failed
+    pub estimated_gas_used: f32,
+    /// The estimate reverted (node simulated the tx and it failed)
+    pub estimate_reverted: bool,
+    /// Latest block seen by the indexer
+    pub block_number: u64,
+}
+
+/// Maps a transaction to model inputs. Must match the features the ONNX
+/// model was trained on.
+pub trait FeatureExtractor: Send + Sync + std::fmt::Debug {
+    fn extract(&self, tx: &PendingTransaction, ctx: &ChainContext) -> FeatureVector;
+}
+
+/// Default feature set used to train `assets/network.onnx`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StandardExtractor;
+
+impl FeatureExtractor for StandardExtractor {
+    fn extract(&self, tx: &PendingTransaction, ctx: &ChainContext) -> FeatureVector {
+        FeatureVector {
+            tx_index: ctx.tx_index as f32,
+            gas_price_gwei: (tx.gas_price.unwrap_or(0) as f32) / 1e9,
+            priority_fee_gwei: (tx.priority_fee as f32) / 1e9,
+            gas_used: ctx.estimated_gas_used,
+            native_value: (tx.value as f32) / 1e18,
+            gas_usage_ratio: ctx.estimated_gas_used / (tx.gas_limit as f32 + 1.0), // Simple ratio
+        }
+    }
+}
+
+/// Resolve an extractor from its config name
+pub fn by_name(name: &str) -> Result<Arc<dyn FeatureExtractor>> {
+    match name {
+        "" | "standard" => Ok(Arc::new(StandardExtractor)),
+        other => Err(eyre::eyre!("Unknown feature extractor: {}", other)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::TxTarget;
+    use std::time::Instant;
+
+    #[test]
+    fn standard_extractor_matches_training_units() {
+        let tx = PendingTransaction {
+            hash: "0x01".to_string(),
+            from: "0x02".to_string(),
+            to: None,
+            value: 2_000_000_000_000_000_000,
+            gas_price: Some(30_000_000_000),
+            max_fee_per_gas: None,
+            max_priority_fee_per_gas: None,
+            priority_fee: 2_000_000_000,
+            gas_limit: 199_999,
+            input: Vec::new(),
+            received_at: Instant::now(),
+            chain_id: 1,
+            pool_key: None,
+            max_fee_per_blob_gas: None,
+            blob_count: 0,
+            approves: None,
+            target_address: None,
+            nonce: 0,
+            gas_escalation: None,
+            raw: None,
+            target: TxTarget::Other,
+        };
+        let ctx = ChainContext {
+            tx_index: 7,
+            estimated_gas_used: 100_000.0,
+            estimate_reverted: false,
+            block_number: 1,
+        };
+
+        let features = by_name("standard").unwrap().extract(&tx, &ctx);
+
+        let close = |a: f32, b: f32| (a - b).abs() < 1e-4;
+        assert_eq!(features.tx_index, 7.0);
+        assert!(close(features.gas_price_gwei, 30.0));
+        assert!(close(features.priority_fee_gwei, 2.0));
+        assert!(close(features.native_value, 2.0));
+        assert!(close(features.gas_usage_ratio, 0.5));
+    }
+
+    #[test]
+    fn unknown_extractor_is_rejected() {
+        assert!(by_name("uniswap-v5").is_err());
+    }
+}
@@ -0,0 +1,242 @@
+//! # Sender History
+//!
+//! With `DETECTION_SENDER_HISTORY` on, the sender of each detection has its
+//! transaction count (nonce) fetched from the mempool RPC and shown in the
+//! insight panel (the TUI has no separate detections panel). A handful of
+//! transactions points at a fresh address; tens of thousands at a
+//! long-running bot. Counts are cached per address for `CACHE_TTL`, and
+//! detections arriving while a lookup is in flight share it, so a busy bot
+//! costs one call per `CACHE_TTL`.
+
+use crate::types::{UiMessage, UiSender};
+use alloy::primitives::Address;
+use alloy::providers::{Provider, RootProvider};
+use alloy::pubsub::PubSubFrontend;
+use eyre::{Result, WrapErr};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// Addresses whose counts are kept; the oldest go first
+const MAX_CACHED: usize = 4096;
+
+/// How long a count stays fresh; an active bot's nonce keeps climbing
+const CACHE_TTL: Duration = Duration::from_secs(600);
+
+/// Below this many transactions an address reads as fresh
+const FRESH_BELOW: u64 = 10;
+
+/// From this many transactions an address reads as long-running
+const LONG_RUNNING_FROM: u64 = 1_000;
+
+/// `fresh`, `active` or `long-running`
+pub fn activity_label(tx_count: u64) -> &'static str {
+    match tx_count {
+        n if n < FRESH_BELOW => "fresh",
+        n if n < LONG_RUNNING_FROM => "active",
+        _ => "long-running",
+    }
+}
+
+/// Transaction counts by lowercase address with when they were fetched,
+/// capped at `capacity` and trusted for `ttl`
+#[derive(Debug)]
+struct TxCounts {
+    capacity: usize,
+    ttl: Duration,
+    counts: HashMap<String, (u64, Instant)>,
+    order: VecDeque<String>,
+}
+
+impl TxCounts {
+    fn new(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            ttl,
+            counts: HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    /// The count for `address`, unless it is older than `ttl` at `now`
+    fn get(&self, address: &str, now: Instant) -> Option<u64> {
+        self.counts
+            .get(address)
+            .filter(|(_, fetched)| now.saturating_duration_since(*fetched) <= self.ttl)
+            .map(|(count, _)| *count)
+    }
+
+    fn insert(&mut self, address: &str, count: u64, now: Instant) {
+        if !self.counts.contains_key(address) {
+            while self.order.len() >= self.capacity {
+                if let Some(oldest) = self.order.pop_front() {
+                    self.counts.remove(&oldest);
+                }
+            }
+            self.order.push_back(address.to_string());
+        }
+        self.counts.insert(address.to_string(), (count, now));
+    }
+}
+
+/// Cached `eth_getTransactionCount` lookups on the mempool chain
+pub struct SenderHistory {
+    provider: RootProvider<PubSubFrontend>,
+    counts: Mutex<TxCounts>,
+    /// Lowercase addresses with a lookup running
+    in_flight: Mutex<HashSet<String>>,
+}
+
+impl SenderHistory {
+    /// Look up counts through `provider`, a connection to the mempool chain
+    pub fn new(provider: RootProvider<PubSubFrontend>) -> Self {
+        Self {
+            provider,
+            counts: Mutex::new(TxCounts::new(MAX_CACHED, CACHE_TTL)),
+            in_flight: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// `address`'s transaction count, from the cache when it was fetched
+    /// within `CACHE_TTL`
+    pub async fn tx_count(&self, address: &str) -> Result<u64> {
+        let key = address.to_lowercase();
+        if let Some(count) = self.cached(&key) {
+            return Ok(count);
+        }
+        let parsed = Address::from_str(address)
+            .wrap_err_with(|| format!("Invalid sender address: {}", address))?;
+        let count = self.provider.get_transaction_count(parsed).await?;
+        self.counts
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(&key, count, Instant::now());
+        Ok(count)
+    }
+
+    fn cached(&self, key: &str) -> Option<u64> {
+        self.counts
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(key, Instant::now())
+    }
+
+    /// Look up `address` in the background and report it to the UI. The
+    /// report is keyed by address, so a lookup already running for it
+    /// answers this detection too.
+    pub fn spawn_lookup(self: &Arc<Self>, address: String, ui_sender: UiSender) {
+        let key = address.to_lowercase();
+        if !self
+            .in_flight
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(key.clone())
+        {
+            return;
+        }
+        let history = self.clone();
+        tokio::spawn(async move {
+            let result = history.tx_count(&address).await;
+            history
+                .in_flight
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .remove(&key);
+            match result {
+                Ok(count) => {
+                    info!(
+                        "Sender {} has sent {} transactions ({})",
+                        address,
+                        count,
+                        activity_label(count)
+                    );
+                    let _ = ui_sender.send(UiMessage::SenderHistory(address, count));
+                }
+                Err(e) => warn!("Sender history lookup failed for {}: {}", address, e),
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{mock_provider, MockNode};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn cache_keeps_the_newest_addresses() {
+        let now = Instant::now();
+        let mut counts = TxCounts::new(2, CACHE_TTL);
+        counts.insert("0xa", 1, now);
+        counts.insert("0xb", 2, now);
+        // Updating an address keeps its slot
+        counts.insert("0xa", 3, now);
+        counts.insert("0xc", 4, now);
+        assert_eq!(counts.get("0xa", now), None);
+        assert_eq!(counts.get("0xb", now), Some(2));
+        assert_eq!(counts.get("0xc", now), Some(4));
+    }
+
+    #[test]
+    fn counts_expire_after_the_ttl() {
+        let fetched = Instant::now();
+        let mut counts = TxCounts::new(2, Duration::from_secs(60));
+        counts.insert("0xa", 7, fetched);
+
+        assert_eq!(
+            counts.get("0xa", fetched + Duration::from_secs(60)),
+            Some(7)
+        );
+        assert_eq!(counts.get("0xa", fetched + Duration::from_secs(61)), None);
+
+        // A refetch starts the clock again
+        counts.insert("0xa", 9, fetched + Duration::from_secs(61));
+        assert_eq!(
+            counts.get("0xa", fetched + Duration::from_secs(90)),
+            Some(9)
+        );
+    }
+
+    #[test]
+    fn labels_activity_by_count() {
+        assert_eq!(activity_label(0), "fresh");
+        assert_eq!(activity_label(9), "fresh");
+        assert_eq!(activity_label(10), "active");
+        assert_eq!(activity_label(1_000), "long-running");
+    }
+
+    #[tokio::test]
+    async fn sender_lookups_in_flight_share_one_call() {
+        static CALLS: AtomicUsize = AtomicUsize::new(0);
+        let node = mock_provider(MockNode(|method| {
+            (method == "eth_getTransactionCount").then(|| {
+                CALLS.fetch_add(1, Ordering::SeqCst);
+                serde_json::json!("0x2a")
+            })
+        }))
+        .await;
+        let history = Arc::new(SenderHistory::new(node));
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+        let ui_sender = UiSender::new(sender);
+        let bot = format!("0x{:040x}", 7);
+
+        history.spawn_lookup(bot.clone(), ui_sender.clone());
+        history.spawn_lookup(bot.clone(), ui_sender.clone());
+        let Some(UiMessage::SenderHistory(address, 42)) = receiver.recv().await else {
+            panic!("expected the sender's count");
+        };
+        assert_eq!(address, bot);
+
+        // Answered from the cache once the lookup finished
+        history.spawn_lookup(bot.clone(), ui_sender);
+        assert!(matches!(
+            receiver.recv().await,
+            Some(UiMessage::SenderHistory(_, 42))
+        ));
+        assert_eq!(CALLS.load(Ordering::SeqCst), 1);
+        assert!(receiver.try_recv().is_err());
+    }
+}
@@ -0,0 +1,213 @@
+//! # Hook ABI
+//!
+//! Loads the hook's trap function from a JSON ABI at startup, so a redeployed
+//! hook whose `markAsPredatorWithProof` gained, dropped or reordered
+//! arguments can be called without recompiling. Each parameter is filled by
+//! its Solidity type; an ABI the sentinel cannot fill is rejected at load.
+
+use crate::error::SentinelError;
+use alloy::dyn_abi::{DynSolValue, JsonAbiExt};
+use alloy::json_abi::{Function, JsonAbi};
+use alloy::primitives::{Address, Bytes, U256};
+use eyre::{Result, WrapErr};
+
+/// Trap function name looked up in the ABI
+pub const TRAP_METHOD: &str = "markAsPredatorWithProof";
+
+/// What the sentinel passes for one ABI parameter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TrapArg {
+    Bot,
+    Status,
+    Proof,
+    PublicInputs,
+    TokenId,
+}
+
+impl TrapArg {
+    fn for_type(ty: &str) -> Option<Self> {
+        match ty {
+            "address" => Some(Self::Bot),
+            "bool" => Some(Self::Status),
+            "bytes" => Some(Self::Proof),
+            "uint256[]" => Some(Self::PublicInputs),
+            "uint256" => Some(Self::TokenId),
+            _ => None,
+        }
+    }
+}
+
+/// Values available to fill a trap call
+#[derive(Debug, Clone)]
+pub struct TrapCallArgs {
+    pub bot: Address,
+    /// True to trap, false to record the proof without marking the bot
+    pub status: bool,
+    pub proof: Bytes,
+    pub public_inputs: Vec<U256>,
+    pub token_id: U256,
+}
+
+/// The trap function resolved from a loaded ABI
+#[derive(Debug, Clone)]
+pub struct TrapCall {
+    function: Function,
+    args: Vec<TrapArg>,
+}
+
+impl TrapCall {
+    /// Read the ABI at `path` (a bare ABI array or a forge artifact)
+    pub fn load(path: &str) -> Result<Self> {
+        let json = std::fs::read_to_string(path)
+            .wrap_err_with(|| format!("Failed to read hook ABI {}", path))?;
+        Self::from_json(&json).wrap_err_with(|| format!("Invalid hook ABI {}", path))
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        let mut value: serde_json::Value = serde_json::from_str(json)?;
+        // Forge artifacts wrap the ABI under "abi"
+        if let Some(abi) = value.get_mut("abi") {
+            value = abi.take();
+        }
+        let abi: JsonAbi = serde_json::from_value(value)?;
+
+        let overloads = abi
+            .function(TRAP_METHOD)
+            .ok_or_else(|| eyre::eyre!("ABI has no {} function", TRAP_METHOD))?;
+        let mut last_err = None;
+        for function in overloads {
+            match Self::resolve(function) {
+                Ok(call) => return Ok(call),
+                Err(e) => last_err = Some(e),
+            }
+        }
+        Err(last_err.unwrap_or_else(|| eyre::eyre!("ABI has no {} function", TRAP_METHOD)))
+    }
+
+    fn resolve(function: &Function) -> Result<Self> {
+        let args = function
+            .inputs
+            .iter()
+            .map(|p| {
+                TrapArg::for_type(&p.ty).ok_or_else(|| {
+                    eyre::eyre!(
+                        "{}: unsupported parameter `{} {}`",
+                        function.signature(),
+                        p.ty,
+                        p.name
+                    )
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        for required in [TrapArg::Bot, TrapArg::Proof] {
+            if !args.contains(&required) {
+                return Err(eyre::eyre!(
+                    "{}: missing {:?} parameter",
+                    function.signature(),
+                    required
+                ));
+            }
+        }
+        Ok(Self {
+            function: function.clone(),
+            args,
+        })
+    }
+
+    /// Canonical signature, e.g. `markAsPredatorWithProof(address,bool,bytes,uint256[])`
+    pub fn signature(&self) -> String {
+        self.function.signature()
+    }
+
+    /// Calldata (selector included) for one submission
+    pub fn encode(&self, call: &TrapCallArgs) -> Result<Bytes, SentinelError> {
+        let values: Vec<DynSolValue> = self
+            .args
+            .iter()
+            .map(|arg| match arg {
+                TrapArg::Bot => DynSolValue::Address(call.bot),
+                TrapArg::Status => DynSolValue::Bool(call.status),
+                TrapArg::Proof => DynSolValue::Bytes(call.proof.to_vec()),
+                TrapArg::PublicInputs => DynSolValue::Array(
+                    call.public_inputs
+                        .iter()
+                        .map(|v| DynSolValue::Uint(*v, 256))
+                        .collect(),
+                ),
+                TrapArg::TokenId => DynSolValue::Uint(call.token_id, 256),
+            })
+            .collect();
+        self.function
+            .abi_encode_input(&values)
+            .map(Bytes::from)
+            .map_err(|e| SentinelError::Submission(format!("ABI encoding failed: {}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::BeeTrapHook;
+    use alloy::sol_types::SolCall;
+
+    const STANDARD_ABI: &str = r#"[
+        {"type":"function","name":"isPredator","stateMutability":"view",
+         "inputs":[{"name":"","type":"address"}],
+         "outputs":[{"name":"","type":"bool"}]},
+        {"type":"function","name":"markAsPredatorWithProof","stateMutability":"nonpayable",
+         "inputs":[{"name":"bot","type":"address"},{"name":"status","type":"bool"},
+                   {"name":"proof","type":"bytes"},{"name":"publicInputs","type":"uint256[]"}],
+         "outputs":[]}
+    ]"#;
+
+    fn args() -> TrapCallArgs {
+        TrapCallArgs {
+            bot: Address::repeat_byte(0xbe),
+            status: true,
+            proof: Bytes::from(vec![1, 2, 3]),
+            public_inputs: vec![U256::from(7), U256::from(9)],
+            token_id: U256::from(42),
+        }
+    }
+
+    #[test]
+    fn standard_abi_encodes_like_the_compiled_binding() {
+        let call = TrapCall::from_json(STANDARD_ABI).unwrap();
+        let a = args();
+        let expected = BeeTrapHook::markAsPredatorWithProofCall {
+            bot: a.bot,
+            status: true,
+            proof: a.proof.clone(),
+            publicInputs: a.public_inputs.clone(),
+        }
+        .abi_encode();
+        assert_eq!(call.encode(&a).unwrap().to_vec(), expected);
+    }
+
+    #[test]
+    fn accepts_forge_artifacts_and_reordered_arguments() {
+        let artifact = r#"{"abi":[{"type":"function","name":"markAsPredatorWithProof",
+            "stateMutability":"nonpayable","outputs":[],
+            "inputs":[{"name":"tokenId","type":"uint256"},{"name":"bot","type":"address"},
+                      {"name":"proof","type":"bytes"},{"name":"publicInputs","type":"uint256[]"}]}]}"#;
+        let call = TrapCall::from_json(artifact).unwrap();
+        assert_eq!(
+            call.signature(),
+            "markAsPredatorWithProof(uint256,address,bytes,uint256[])"
+        );
+        assert!(call.encode(&args()).is_ok());
+    }
+
+    #[test]
+    fn rejects_missing_or_unfillable_trap_function() {
+        let missing = r#"[{"type":"function","name":"isPredator","stateMutability":"view",
+            "inputs":[{"name":"","type":"address"}],"outputs":[]}]"#;
+        assert!(TrapCall::from_json(missing).is_err());
+
+        let unfillable = r#"[{"type":"function","name":"markAsPredatorWithProof",
+            "stateMutability":"nonpayable","outputs":[],
+            "inputs":[{"name":"bot","type":"address"},{"name":"proof","type":"bytes"},
+                      {"name":"salt","type":"bytes32"}]}]"#;
+        assert!(TrapCall::from_json(unfillable).is_err());
+    }
+}
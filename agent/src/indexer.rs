@@ -1,40 +1,332 @@
-use crate::types::{PendingTransaction, TransactionSummary, UiMessage};
+use crate::types::{
+    short_address, FeeModel, GasEscalation, PendingTransaction, Reorg, RpcCall, TransactionSummary,
+    TxTarget, UiMessage, UiSender,
+};
 use alloy::{
     consensus::Transaction as TransactionTrait,
-    primitives::Address,
+    eips::eip2718::Encodable2718,
+    primitives::{Address, TxHash, B256},
     providers::{Provider, ProviderBuilder, WsConnect},
-    rpc::types::Transaction,
+    pubsub::PubSubFrontend,
+    rpc::{client::BatchRequest, types::Transaction},
 };
 use eyre::Result;
 use futures::StreamExt;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, VecDeque};
 use std::str::FromStr;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
-use tokio::sync::mpsc::{self, Sender, UnboundedSender};
-use tokio::sync::Semaphore;
+use tokio::sync::mpsc::{self, Sender};
+use tokio::sync::{watch, OwnedSemaphorePermit, Semaphore};
 use tokio::time::sleep;
-use tracing::{error, info, warn};
+use tracing::{error, info, instrument, warn};
 
 /// Uniswap V4 Pool Manager Address
 pub const POOL_MANAGER_ADDRESS: &str = "0x000000000004444c5dc75cB358380D2e3dE08A90";
 
+/// Pending transactions fetched concurrently; hashes beyond this are dropped
+pub const MAX_IN_FLIGHT_FETCHES: usize = 10;
+
+/// Transactions buffered between the indexer and the processor
+pub const TX_QUEUE_CAPACITY: usize = 100;
+
+/// How long a `(sender, nonce)` bid is remembered for replacement detection
+const REPLACEMENT_WINDOW: Duration = Duration::from_secs(300);
+
+/// `(sender, nonce)` bids kept at most; the oldest are evicted first
+const REPLACEMENT_CAPACITY: usize = 50_000;
+
+/// The chain's gas pricing, as configured or as seen in block headers.
+/// Without a base fee (pre-London or non-EIP-1559 chains) the header gas
+/// price comes from transactions, and a legacy gas price is the whole tip.
+#[derive(Debug)]
+pub struct FeeMarket {
+    model: FeeModel,
+    legacy: AtomicBool,
+    /// Latest legacy gas price seen on a fetched transaction (wei)
+    last_gas_price: AtomicU64,
+}
+
+impl FeeMarket {
+    pub fn new(model: FeeModel) -> Self {
+        Self {
+            model,
+            legacy: AtomicBool::new(model == FeeModel::Legacy),
+            last_gas_price: AtomicU64::new(0),
+        }
+    }
+
+    pub fn is_legacy(&self) -> bool {
+        self.legacy.load(Ordering::Relaxed)
+    }
+
+    /// Track a header's base fee and return the gas price to display (wei)
+    fn observe_header(&self, base_fee: Option<u64>) -> u128 {
+        if self.model == FeeModel::Auto {
+            let legacy = base_fee.is_none();
+            if self.legacy.swap(legacy, Ordering::Relaxed) != legacy {
+                if legacy {
+                    info!("Blocks carry no base fee: legacy gas pricing, using transaction gas prices");
+                } else {
+                    info!("Blocks carry a base fee: EIP-1559 gas pricing");
+                }
+            }
+        }
+        if self.is_legacy() {
+            self.last_gas_price.load(Ordering::Relaxed) as u128
+        } else {
+            base_fee.unwrap_or(0) as u128
+        }
+    }
+
+    fn observe_transaction(&self, gas_price: Option<u128>) {
+        if let Some(price) = gas_price {
+            self.last_gas_price.store(price as u64, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Monitored contracts, several of each kind (e.g. a second router or a
+/// PoolManager on a fork)
+#[derive(Debug, Clone)]
+pub struct Targets {
+    pub pool_managers: Vec<Address>,
+    pub routers: Vec<Address>,
+}
+
+impl Targets {
+    pub fn parse(pool_managers: &[String], routers: &[String]) -> Result<Self> {
+        let parse_all = |addrs: &[String]| -> Result<Vec<Address>> {
+            addrs
+                .iter()
+                .map(|a| {
+                    Address::from_str(a)
+                        .map_err(|e| eyre::eyre!("Invalid target address {}: {}", a, e))
+                })
+                .collect()
+        };
+        Ok(Self {
+            pool_managers: parse_all(pool_managers)?,
+            routers: parse_all(routers)?,
+        })
+    }
+
+    /// Which monitored contract `addr` is, if any
+    pub fn matching(&self, addr: Address) -> Option<TxTarget> {
+        if self.pool_managers.contains(&addr) {
+            Some(TxTarget::PoolManager)
+        } else if self.routers.contains(&addr) {
+            Some(TxTarget::Router)
+        } else {
+            None
+        }
+    }
+}
+
+/// Fee bids per `(sender, nonce)`, to spot transactions re-broadcast with
+/// higher gas (replacement / gas escalation). Bids are also queued in the
+/// order they were last seen, so expiry and the size cap only look at the
+/// oldest end instead of scanning every entry per transaction.
+#[derive(Debug)]
+pub struct ReplacementTracker {
+    window: Duration,
+    capacity: usize,
+    /// Bid per key, with the sequence number of the queue slot that owns it
+    seen: HashMap<(String, u64), (u64, GasEscalation)>,
+    order: VecDeque<(Instant, u64, (String, u64))>,
+    next_seq: u64,
+}
+
+impl ReplacementTracker {
+    pub fn new(window: Duration, capacity: usize) -> Self {
+        Self {
+            window,
+            capacity,
+            seen: HashMap::new(),
+            order: VecDeque::new(),
+            next_seq: 0,
+        }
+    }
+
+    /// Drop the oldest queued bid. A queue slot whose bid was raised since
+    /// is stale: the newer slot further back owns the entry.
+    fn evict_oldest(&mut self) {
+        if let Some((_, seq, key)) = self.order.pop_front() {
+            if let Entry::Occupied(entry) = self.seen.entry(key) {
+                if entry.get().0 == seq {
+                    entry.remove();
+                }
+            }
+        }
+    }
+
+    /// Record `from`'s bid for `nonce` at `now`. Returns the escalation when
+    /// it outbids an earlier broadcast of the same nonce; duplicates and
+    /// lower re-broadcasts return `None`. Expired entries are dropped, and
+    /// the oldest ones once `capacity` is reached.
+    pub fn observe(
+        &mut self,
+        from: &str,
+        nonce: u64,
+        bid: u128,
+        now: Instant,
+    ) -> Option<GasEscalation> {
+        while self
+            .order
+            .front()
+            .is_some_and(|(at, _, _)| now.saturating_duration_since(*at) > self.window)
+        {
+            self.evict_oldest();
+        }
+        let key = (from.to_lowercase(), nonce);
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        match self.seen.entry(key.clone()) {
+            Entry::Occupied(mut entry) => {
+                let (owner, escalation) = entry.get_mut();
+                if bid <= escalation.bid {
+                    return None;
+                }
+                *owner = seq;
+                escalation.replacements += 1;
+                escalation.bid = bid;
+                let escalation = *escalation;
+                self.order.push_back((now, seq, key));
+                Some(escalation)
+            }
+            Entry::Vacant(entry) => {
+                entry.insert((
+                    seq,
+                    GasEscalation {
+                        replacements: 0,
+                        first_bid: bid,
+                        bid,
+                    },
+                ));
+                self.order.push_back((now, seq, key));
+                while self.seen.len() > self.capacity {
+                    self.evict_oldest();
+                }
+                None
+            }
+        }
+    }
+}
+
+/// Highest block seen plus recent header hashes, to tell a reorg from an
+/// advancing head. The highest number never regresses, so the UI and the
+/// processor's block-aware heuristics don't jump back on a competing header.
+#[derive(Debug)]
+pub struct ChainHead {
+    window: usize,
+    highest: u64,
+    hashes: VecDeque<(u64, B256)>,
+}
+
+impl ChainHead {
+    /// Remember the last `window` headers (0 = only track the highest)
+    pub fn new(window: usize) -> Self {
+        Self {
+            window,
+            highest: 0,
+            hashes: VecDeque::new(),
+        }
+    }
+
+    pub fn highest(&self) -> u64 {
+        self.highest
+    }
+
+    fn hash_at(&self, number: u64) -> Option<B256> {
+        self.hashes
+            .iter()
+            .find(|(n, _)| *n == number)
+            .map(|(_, hash)| *hash)
+    }
+
+    /// Record a new header. Returns the reorg when it replaces a header
+    /// already seen, or does not build on the one seen at its parent height.
+    pub fn observe(&mut self, number: u64, hash: B256, parent_hash: B256) -> Option<Reorg> {
+        if self.window == 0 {
+            self.highest = self.highest.max(number);
+            return None;
+        }
+        let first_block = match self.hash_at(number) {
+            // Same header delivered again
+            Some(known) if known == hash => return None,
+            Some(_) => Some(number),
+            None => match self.hash_at(number.saturating_sub(1)) {
+                Some(parent) if parent != parent_hash => Some(number - 1),
+                _ => None,
+            },
+        };
+        let reorg = first_block.map(|first_block| {
+            self.hashes.retain(|(n, _)| *n < first_block);
+            Reorg {
+                first_block,
+                depth: self.highest.max(number) - first_block + 1,
+            }
+        });
+        self.hashes.push_back((number, hash));
+        while self.hashes.len() > self.window {
+            self.hashes.pop_front();
+        }
+        self.highest = self.highest.max(number);
+        reorg
+    }
+}
+
 /// Spawns the mempool listener with automatic reconnection logic
+#[allow(clippy::too_many_arguments)]
 pub async fn spawn_mempool_listener(
     p2p_wss_url: String,
-    pool_manager_str: String,
-    router_str: String,
+    targets: Targets,
     tx_sender: Sender<PendingTransaction>,
-    ui_sender: UnboundedSender<UiMessage>,
+    ui_sender: UiSender,
+    block_sender: watch::Sender<u64>,
+    batch_window: Duration,
+    fee_model: FeeModel,
+    capture_raw: bool,
+    reorg_window: usize,
+    reorg_sender: mpsc::UnboundedSender<Reorg>,
+    observed: Arc<AtomicU64>,
 ) -> Result<()> {
-    let pool_manager = Address::from_str(&pool_manager_str)?;
-    let router = Address::from_str(&router_str)?;
-
     info!(target: "sentinel", "Starting Mempool Listener...");
-    info!(target: "sentinel", "Target 1 (PoolManager): {}", pool_manager);
-    info!(target: "sentinel", "Target 2 (Router): {}", router);
+    for pool_manager in &targets.pool_managers {
+        info!(target: "sentinel", "Target (PoolManager): {}", pool_manager);
+    }
+    for router in &targets.routers {
+        info!(target: "sentinel", "Target (Router): {}", router);
+    }
+    if targets.pool_managers.is_empty() && targets.routers.is_empty() {
+        warn!(target: "sentinel", "No target addresses configured; every tx is scored as Other");
+    }
+    let targets = Arc::new(targets);
+    let fees = Arc::new(FeeMarket::new(fee_model));
+    if fee_model != FeeModel::Auto {
+        info!(target: "sentinel", "Fee model: {:?}", fee_model);
+    }
+
+    // Survives reconnects, so a new session can't move the head backwards
+    let mut head = ChainHead::new(reorg_window);
 
     loop {
-        match run_listener_session(&p2p_wss_url, &tx_sender, &ui_sender, pool_manager, router).await
+        match run_listener_session(
+            &p2p_wss_url,
+            &tx_sender,
+            &ui_sender,
+            &block_sender,
+            &targets,
+            &fees,
+            batch_window,
+            capture_raw,
+            &mut head,
+            &reorg_sender,
+            &observed,
+        )
+        .await
         {
             Ok(_) => {
                 warn!("Listener session ended normally. Restarting...");
@@ -43,16 +335,33 @@ pub async fn spawn_mempool_listener(
                 error!("Listener session failed: {}. Retrying in 5s...", e);
             }
         }
+
+        // Keep the last-known head; only the connection state changes
+        let _ = ui_sender.send(UiMessage::NetworkUpdate(crate::types::NetworkStatus {
+            connected: false,
+            reconnecting: true,
+            chain: "Ethereum".to_string(),
+            chain_id: 1,
+            block_number: *block_sender.borrow(),
+            gas_price: 0,
+        }));
         sleep(Duration::from_secs(5)).await;
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn run_listener_session(
     wss_url: &str,
     tx_sender: &Sender<PendingTransaction>,
-    ui_sender: &UnboundedSender<UiMessage>,
-    pool_manager: Address,
-    router: Address,
+    ui_sender: &UiSender,
+    block_sender: &watch::Sender<u64>,
+    targets: &Arc<Targets>,
+    fees: &Arc<FeeMarket>,
+    batch_window: Duration,
+    capture_raw: bool,
+    head: &mut ChainHead,
+    reorg_sender: &mpsc::UnboundedSender<Reorg>,
+    observed: &AtomicU64,
 ) -> Result<()> {
     // 1. Establish WSS Connection
     info!("Attempting to connect to WSS URL: {}", wss_url); // Log the URL!
@@ -64,6 +373,7 @@ async fn run_listener_session(
     // Notify UI of connection
     let _ = ui_sender.send(UiMessage::NetworkUpdate(crate::types::NetworkStatus {
         connected: true,
+        reconnecting: false,
         chain: "Ethereum".to_string(), // Or get from chain_id
         chain_id: 1,                   // Placeholder or fetch
         block_number: 0,               // Will update when block heard
@@ -82,17 +392,40 @@ async fn run_listener_session(
 
     // 4. Process Streams Conditionally
     // We use tokio::select! to handle both streams concurrently
-    let semaphore = Arc::new(Semaphore::new(10)); // Reduced to 10 for safe sampling
+    let semaphore = Arc::new(Semaphore::new(MAX_IN_FLIGHT_FETCHES)); // Kept low for safe sampling
+
+    // Hashes waiting to be fetched in the next JSON-RPC batch (permit held until fetched)
+    let mut pending_hashes: Vec<(TxHash, OwnedSemaphorePermit)> = Vec::new();
+    let mut flush_tick = tokio::time::interval(batch_window.max(Duration::from_millis(1)));
+    // Replacements land within seconds of the original; a reconnect may start fresh
+    let replacements = Arc::new(Mutex::new(ReplacementTracker::new(
+        REPLACEMENT_WINDOW,
+        REPLACEMENT_CAPACITY,
+    )));
+    flush_tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
 
     loop {
         tokio::select! {
             Some(header) = block_stream.next() => {
-                 let block_num = header.number;
-                 let gas_price = header.base_fee_per_gas.unwrap_or(0) as u128;
+                 if let Some(reorg) = head.observe(header.number, header.hash, header.parent_hash) {
+                     warn!(
+                         "Reorg: block #{} replaced by {} (depth {})",
+                         reorg.first_block, header.hash, reorg.depth
+                     );
+                     let _ = ui_sender.send(UiMessage::Reorg(reorg));
+                     let _ = reorg_sender.send(reorg);
+                 }
+                 // A competing lower header never moves the head back
+                 let block_num = head.highest();
+                 let gas_price = fees.observe_header(header.base_fee_per_gas);
+
+                 // Share latest block with the processor for block-aware heuristics
+                 block_sender.send_replace(block_num);
 
                  // Update UI
                  let _ = ui_sender.send(UiMessage::NetworkUpdate(crate::types::NetworkStatus {
                     connected: true,
+                    reconnecting: false,
                     chain: "Ethereum".to_string(),
                     chain_id: 1,
                     block_number: block_num,
@@ -100,11 +433,8 @@ async fn run_listener_session(
                  }));
             }
             Some(tx_hash) = tx_stream.next() => {
-                let provider_clone = provider.clone();
-                let tx_sender_clone = tx_sender.clone();
-                let ui_sender_clone = ui_sender.clone();
-                let tx_hash_str = tx_hash.to_string();
-
+                // Counted before backpressure, for sampling-aware efficiency
+                observed.fetch_add(1, Ordering::Relaxed);
                 let permit = if let Ok(p) = semaphore.clone().try_acquire_owned() {
                     p
                 } else {
@@ -114,26 +444,32 @@ async fn run_listener_session(
                     continue;
                 };
 
-                tokio::spawn(async move {
-                    let _permit = permit; // Drop permit when task finishes
-                    match provider_clone.get_transaction_by_hash(tx_hash).await {
-                        Ok(Some(tx)) => {
-                            process_transaction(
-                                tx_hash_str,
-                                tx,
-                                &tx_sender_clone,
-                                &ui_sender_clone,
-                                pool_manager,
-                                router
-                            )
-                            .await;
-                        }
-                        Ok(None) => {}
-                        Err(e) => {
-                            tracing::debug!("Failed to fetch tx {}: {}", tx_hash_str, e);
-                        }
-                    }
-                });
+                pending_hashes.push((tx_hash, permit));
+                // A zero window disables coalescing: fetch immediately
+                if batch_window.is_zero() {
+                    spawn_batch_fetch(
+                        provider.clone(),
+                        std::mem::take(&mut pending_hashes),
+                        tx_sender.clone(),
+                        ui_sender.clone(),
+                        targets.clone(),
+                        fees.clone(),
+                        replacements.clone(),
+                        capture_raw,
+                    );
+                }
+            }
+            _ = flush_tick.tick(), if !pending_hashes.is_empty() => {
+                spawn_batch_fetch(
+                    provider.clone(),
+                    std::mem::take(&mut pending_hashes),
+                    tx_sender.clone(),
+                    ui_sender.clone(),
+                    targets.clone(),
+                    fees.clone(),
+                    replacements.clone(),
+                    capture_raw,
+                );
             }
             else => break, // Stream ended
         }
@@ -142,49 +478,251 @@ async fn run_listener_session(
     Ok(())
 }
 
-async fn process_transaction(
+/// Fetches a group of coalesced hashes and forwards each transaction found
+#[allow(clippy::too_many_arguments)]
+fn spawn_batch_fetch<P>(
+    provider: P,
+    batch: Vec<(TxHash, OwnedSemaphorePermit)>,
+    tx_sender: Sender<PendingTransaction>,
+    ui_sender: UiSender,
+    targets: Arc<Targets>,
+    fees: Arc<FeeMarket>,
+    replacements: Arc<Mutex<ReplacementTracker>>,
+    capture_raw: bool,
+) where
+    P: Provider<PubSubFrontend> + 'static,
+{
+    tokio::spawn(async move {
+        // Permits drop when the task finishes
+        let (hashes, _permits): (Vec<TxHash>, Vec<OwnedSemaphorePermit>) =
+            batch.into_iter().unzip();
+
+        for (tx_hash, tx) in fetch_transactions(&provider, &hashes, &ui_sender).await {
+            process_transaction(
+                tx_hash.to_string(),
+                tx,
+                &tx_sender,
+                &ui_sender,
+                &targets,
+                &fees,
+                &replacements,
+                capture_raw,
+            )
+            .await;
+        }
+    });
+}
+
+/// Fetches pending transactions with a single JSON-RPC batch request.
+///
+/// Falls back to individual `eth_getTransactionByHash` calls for any hash the
+/// batch could not resolve (e.g. providers that reject batching).
+#[instrument(skip_all, fields(count = hashes.len()))]
+async fn fetch_transactions<P>(
+    provider: &P,
+    hashes: &[TxHash],
+    ui_sender: &UiSender,
+) -> Vec<(TxHash, Transaction)>
+where
+    P: Provider<PubSubFrontend>,
+{
+    let mut batched: Vec<Option<Option<Transaction>>> = vec![None; hashes.len()];
+
+    if hashes.len() > 1 {
+        let mut batch = BatchRequest::new(provider.client());
+        let waiters: Vec<_> = hashes
+            .iter()
+            .map(|hash| {
+                batch
+                    .add_call::<_, Option<Transaction>>("eth_getTransactionByHash", &(hash,))
+                    .ok()
+            })
+            .collect();
+
+        match batch.send().await {
+            Ok(()) => {
+                for (slot, waiter) in batched.iter_mut().zip(waiters) {
+                    if let Some(waiter) = waiter {
+                        *slot = waiter.await.ok();
+                    }
+                }
+            }
+            Err(e) => {
+                tracing::debug!("Batch fetch of {} txs failed: {}", hashes.len(), e);
+                let _ = ui_sender.send(UiMessage::RpcError(RpcCall::Fetch));
+            }
+        }
+    }
+
+    let mut found = Vec::with_capacity(hashes.len());
+    for (hash, result) in hashes.iter().zip(batched) {
+        let tx = match result {
+            Some(tx) => tx,
+            None => match provider.get_transaction_by_hash(*hash).await {
+                Ok(tx) => tx,
+                Err(e) => {
+                    tracing::debug!("Failed to fetch tx {}: {}", hash, e);
+                    let _ = ui_sender.send(UiMessage::RpcError(RpcCall::Fetch));
+                    None
+                }
+            },
+        };
+        if let Some(tx) = tx {
+            found.push((*hash, tx));
+        }
+    }
+    found
+}
+
+/// Convert a fetched transaction into the processor's input
+pub fn pending_transaction(
     tx_hash: String,
-    tx: Transaction,
-    sender: &Sender<PendingTransaction>, // Bounded Sender
-    ui_sender: &UnboundedSender<UiMessage>,
-    _pool_manager: Address,
-    _router: Address,
-) {
-    // Use the inner transaction envelope to access fields
+    tx: &Transaction,
+    targets: &Targets,
+    received_at: Instant,
+    gas_escalation: Option<GasEscalation>,
+    legacy_fees: bool,
+) -> PendingTransaction {
     let tx_inner = &tx.inner;
 
     // Check destination (to)
     let to_addr = tx_inner.to();
+    let target = to_addr
+        .and_then(|to| targets.matching(to))
+        .unwrap_or(TxTarget::Other);
 
-    // SAMPLING MODE: Process ANY transaction that we have capacity for (semaphore logic handled upstream)
-    // We do NOT filter by address here anymore, relying on upstream sampling to keep load low.
+    // V4-native awareness: decode the PoolKey for direct PoolManager calls
+    let pool_key = if target == TxTarget::PoolManager {
+        crate::router::decode_pool_manager_call(tx_inner.input())
+    } else {
+        None
+    };
 
-    // Found a target transaction!
-    let event = PendingTransaction {
-        hash: tx_hash.clone(),
+    // Allowance granted to a monitored contract (bot setup ahead of swaps)
+    let approval = crate::router::decode_approve_spender(tx_inner.input())
+        .and_then(|spender| targets.matching(spender).map(|kind| (spender, kind)));
+    let approves = approval.map(|(_, kind)| kind);
+
+    // Tag the exact contract matched: several of each kind may be monitored
+    let target_address = match target {
+        TxTarget::Other => approval.map(|(spender, _)| spender),
+        _ => to_addr,
+    }
+    .map(|a| a.to_string());
+
+    PendingTransaction {
+        hash: tx_hash,
         from: tx.from.to_string(),
         to: to_addr.map(|t| t.to_string()),
         value: tx_inner.value().to_string().parse().unwrap_or(0),
         gas_price: tx_inner.gas_price(),
         max_priority_fee_per_gas: tx_inner.max_priority_fee_per_gas(),
         max_fee_per_gas: Some(tx_inner.max_fee_per_gas()),
-        priority_fee: tx_inner.max_priority_fee_per_gas().unwrap_or(0),
+        // Without a base fee the whole legacy gas price goes to the producer
+        priority_fee: if legacy_fees {
+            tx_inner.gas_price().unwrap_or(0)
+        } else {
+            tx_inner.max_priority_fee_per_gas().unwrap_or(0)
+        },
         gas_limit: tx_inner.gas_limit(),
         input: tx_inner.input().to_vec(),
-        received_at: Instant::now(),
+        received_at,
         chain_id: tx_inner.chain_id().unwrap_or(1),
-    };
+        pool_key,
+        target,
+        approves,
+        target_address,
+        max_fee_per_blob_gas: tx_inner.max_fee_per_blob_gas(),
+        blob_count: tx_inner.blob_versioned_hashes().map_or(0, |h| h.len()),
+        nonce: tx_inner.nonce(),
+        gas_escalation,
+        raw: None,
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn process_transaction(
+    tx_hash: String,
+    tx: Transaction,
+    sender: &Sender<PendingTransaction>, // Bounded Sender
+    ui_sender: &UiSender,
+    targets: &Targets,
+    fees: &FeeMarket,
+    replacements: &Mutex<ReplacementTracker>,
+    capture_raw: bool,
+) {
+    // Use the inner transaction envelope to access fields
+    let tx_inner = &tx.inner;
+
+    // SAMPLING MODE: Process ANY transaction that we have capacity for (semaphore logic handled upstream)
+    // We do NOT filter by address here anymore, relying on upstream sampling to keep load low.
+
+    // Same nonce re-broadcast with a higher bid: bot escalating a gas war
+    let received_at = Instant::now();
+    let bid = tx_inner
+        .gas_price()
+        .unwrap_or_else(|| tx_inner.max_fee_per_gas());
+    let gas_escalation = replacements
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .observe(&tx.from.to_string(), tx_inner.nonce(), bid, received_at);
+    if let Some(escalation) = gas_escalation {
+        info!(
+            "Gas escalation by {} ({}): {}",
+            tx.from, tx_hash, escalation
+        );
+        let _ = ui_sender.send(UiMessage::Log(format!(
+            "Gas escalation: {} re-sent nonce {} ({})",
+            tx.from,
+            tx_inner.nonce(),
+            escalation
+        )));
+    }
+
+    // Found a target transaction!
+    fees.observe_transaction(tx_inner.gas_price());
+    let mut event = pending_transaction(
+        tx_hash.clone(),
+        &tx,
+        targets,
+        received_at,
+        gas_escalation,
+        fees.is_legacy(),
+    );
+    if capture_raw {
+        event.raw = Some(tx_inner.encoded_2718());
+    }
+    let target = event.target;
+    let target_address = event.target_address.clone();
 
     // Send to UI First to avoid race condition (Processor updating before UI creates entry)
     let summary = TransactionSummary {
         hash: tx_hash.clone(),
         short_hash: format!("{}...", &tx_hash[0..8]),
-        from_short: format!("{}...", &event.from[0..6]),
-        to_short: format!("{}...", &event.to.as_deref().unwrap_or("Creation")[0..6]),
+        from: event.from.clone(),
+        from_short: short_address(&event.from),
+        to_short: event
+            .to
+            .as_deref()
+            .map_or("Creation".to_string(), short_address),
         value_eth: (event.value as f64) / 1e18,
         gas_gwei: (event.gas_price.unwrap_or(0) as f64) / 1e9,
         suspicious: false,
         probability: None, // Init as None
+        pool: event.pool_key.as_ref().map(|k| k.to_string()),
+        target,
+        target_address,
+        received_at: event.received_at,
+        blob_fee_gwei: event.max_fee_per_blob_gas.map(|f| f as f64 / 1e9),
+        context_note: None,
+        decision: None,
+        gas_escalation,
+        inference_failed: false,
+        address_score: None,
+        unscored: None,
+        trap_cost: None,
+        label: None,
+        cluster: None,
     };
     let _ = ui_sender.send(UiMessage::NewTransaction(summary));
 
@@ -193,3 +731,134 @@ async fn process_transaction(
         warn!("Failed to send tx to processor (channel closed?): {}", e);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn header_gas_price_falls_back_to_transactions_without_a_base_fee() {
+        let auto = FeeMarket::new(FeeModel::Auto);
+        auto.observe_transaction(Some(7_000_000_000));
+        assert_eq!(auto.observe_header(Some(3_000_000_000)), 3_000_000_000);
+        assert!(!auto.is_legacy());
+
+        // A chain without EIP-1559 shows the last legacy gas price
+        assert_eq!(auto.observe_header(None), 7_000_000_000);
+        assert!(auto.is_legacy());
+
+        // Forced EIP-1559 keeps the base fee reading
+        let eip1559 = FeeMarket::new(FeeModel::Eip1559);
+        eip1559.observe_transaction(Some(7_000_000_000));
+        assert_eq!(eip1559.observe_header(None), 0);
+        assert!(!eip1559.is_legacy());
+        assert!(FeeMarket::new(FeeModel::Legacy).is_legacy());
+    }
+
+    #[test]
+    fn matches_any_configured_target() {
+        let targets = Targets::parse(
+            &[POOL_MANAGER_ADDRESS.to_string()],
+            &crate::types::parse_address_list(
+                "0x66a9893cC07D91D95644AEDD05D03f95e1dBA8Af, 0x3fC91A3afd70395Cd496C647d5a6CC9D4B2b7FAD",
+            ),
+        )
+        .unwrap();
+        assert_eq!(targets.routers.len(), 2);
+
+        let second_router =
+            Address::from_str("0x3fC91A3afd70395Cd496C647d5a6CC9D4B2b7FAD").unwrap();
+        assert_eq!(targets.matching(second_router), Some(TxTarget::Router));
+        assert_eq!(
+            targets.matching(Address::from_str(POOL_MANAGER_ADDRESS).unwrap()),
+            Some(TxTarget::PoolManager)
+        );
+        assert_eq!(targets.matching(Address::ZERO), None);
+
+        assert!(Targets::parse(&["0xnot-an-address".to_string()], &[]).is_err());
+    }
+
+    #[test]
+    fn flags_only_higher_bids_for_the_same_nonce() {
+        let start = Instant::now();
+        let mut tracker = ReplacementTracker::new(Duration::from_secs(60), 16);
+
+        assert_eq!(tracker.observe("0xAbC", 7, 30_000_000_000, start), None);
+        // Duplicate gossip and other nonces are not replacements
+        assert_eq!(tracker.observe("0xabc", 7, 30_000_000_000, start), None);
+        assert_eq!(tracker.observe("0xabc", 8, 45_000_000_000, start), None);
+
+        let bumped = tracker
+            .observe("0xabc", 7, 45_000_000_000, start + Duration::from_secs(2))
+            .unwrap();
+        assert_eq!(bumped.replacements, 1);
+        assert_eq!(bumped.first_bid, 30_000_000_000);
+        assert!((bumped.bump_pct() - 50.0).abs() < 1e-9);
+
+        let again = tracker
+            .observe("0xabc", 7, 60_000_000_000, start + Duration::from_secs(4))
+            .unwrap();
+        assert_eq!(again.replacements, 2);
+
+        // Forgotten once the window passes
+        assert_eq!(
+            tracker.observe("0xabc", 7, 90_000_000_000, start + Duration::from_secs(120)),
+            None
+        );
+    }
+
+    #[test]
+    fn replacement_tracker_evicts_the_oldest_bids_at_capacity() {
+        let start = Instant::now();
+        let mut tracker = ReplacementTracker::new(Duration::from_secs(60), 2);
+
+        tracker.observe("0xa", 1, 10, start);
+        tracker.observe("0xb", 1, 10, start);
+        // Raising a's bid makes it the most recent
+        assert!(tracker.observe("0xa", 1, 20, start).is_some());
+        tracker.observe("0xc", 1, 10, start);
+
+        assert_eq!(tracker.seen.len(), 2);
+        assert!(tracker.observe("0xa", 1, 30, start).is_some());
+        // b was the oldest and is gone: its next bid starts over
+        assert_eq!(tracker.observe("0xb", 1, 20, start), None);
+    }
+
+    #[test]
+    fn chain_head_spots_reorgs_without_moving_back() {
+        let hash = |n: u8| B256::repeat_byte(n);
+        let mut head = ChainHead::new(8);
+        assert_eq!(head.observe(10, hash(10), hash(9)), None);
+        assert_eq!(head.observe(11, hash(11), hash(10)), None);
+        assert_eq!(head.observe(12, hash(12), hash(11)), None);
+        // Duplicate delivery
+        assert_eq!(head.observe(12, hash(12), hash(11)), None);
+
+        // Competing header for 11: 11 and 12 are replaced
+        assert_eq!(
+            head.observe(11, hash(0xb1), hash(10)),
+            Some(Reorg {
+                first_block: 11,
+                depth: 2
+            })
+        );
+        assert_eq!(head.highest(), 12);
+        assert_eq!(head.observe(12, hash(0xb2), hash(0xb1)), None);
+
+        // 13 building on an unseen 12 replaces 12
+        assert_eq!(
+            head.observe(13, hash(13), hash(0xc2)),
+            Some(Reorg {
+                first_block: 12,
+                depth: 2
+            })
+        );
+        assert_eq!(head.highest(), 13);
+
+        // Off: only the highest is tracked
+        let mut off = ChainHead::new(0);
+        assert_eq!(off.observe(12, hash(12), hash(11)), None);
+        assert_eq!(off.observe(11, hash(0xb1), hash(10)), None);
+        assert_eq!(off.highest(), 12);
+    }
+}
@@ -0,0 +1,129 @@
+//! # Submission Ledger
+//!
+//! File-backed set of bot addresses with a trap submitted or in flight.
+//! Consulted before proving/submitting so restarts and concurrent tasks
+//! never trap the same address twice; complements the on-chain
+//! `isPredator` check, which lags behind pending submissions.
+
+use alloy::primitives::Address;
+use eyre::{Result, WrapErr};
+use std::collections::HashSet;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Mutex;
+
+/// One lowercase address per line; claims append, releases replace the file
+/// through a temporary file and a rename
+#[derive(Debug)]
+pub struct SubmissionLedger {
+    path: Option<PathBuf>,
+    entries: Mutex<HashSet<Address>>,
+}
+
+impl SubmissionLedger {
+    /// Load the ledger from `path`, or keep it in memory only when `None`
+    pub fn load(path: Option<&str>) -> Result<Self> {
+        let mut entries = HashSet::new();
+        if let Some(path) = path {
+            match std::fs::read_to_string(path) {
+                Ok(content) => {
+                    for line in content.lines().map(str::trim).filter(|l| !l.is_empty()) {
+                        let addr = Address::from_str(line)
+                            .wrap_err_with(|| format!("Invalid address in ledger: {}", line))?;
+                        entries.insert(addr);
+                    }
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e).wrap_err_with(|| format!("Failed to read {}", path)),
+            }
+        }
+
+        Ok(Self {
+            path: path.map(PathBuf::from),
+            entries: Mutex::new(entries),
+        })
+    }
+
+    /// Claim `bot` for submission. Returns `false` if it was already claimed.
+    pub fn claim(&self, bot: Address) -> Result<bool> {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        if !entries.insert(bot) {
+            return Ok(false);
+        }
+
+        if let Some(path) = &self.path {
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+            writeln!(file, "{:#x}", bot)?;
+            file.sync_all()?;
+        }
+        Ok(true)
+    }
+
+    /// Drop a claim after a failed attempt so the address can be retried
+    pub fn release(&self, bot: Address) -> Result<()> {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        if !entries.remove(&bot) {
+            return Ok(());
+        }
+
+        if let Some(path) = &self.path {
+            // Write a temporary file and rename it over the ledger, so a crash
+            // mid-write can't leave a truncated file that forgets claims
+            let content: String = entries.iter().map(|a| format!("{:#x}\n", a)).collect();
+            let mut tmp = path.clone().into_os_string();
+            tmp.push(".tmp");
+            let mut file = std::fs::File::create(&tmp)?;
+            file.write_all(content.as_bytes())?;
+            file.sync_all()?;
+            std::fs::rename(tmp, path)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ledger_path(name: &str) -> String {
+        let dir = std::env::temp_dir().join("beetrap-ledger-tests");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        let _ = std::fs::remove_file(&path);
+        path.to_string_lossy().into_owned()
+    }
+
+    #[test]
+    fn claims_once_and_survives_reload() {
+        let path = ledger_path("reload.txt");
+        let bot = Address::repeat_byte(0xab);
+
+        let ledger = SubmissionLedger::load(Some(&path)).unwrap();
+        assert!(ledger.claim(bot).unwrap());
+        assert!(!ledger.claim(bot).unwrap());
+
+        let reloaded = SubmissionLedger::load(Some(&path)).unwrap();
+        assert!(!reloaded.claim(bot).unwrap());
+    }
+
+    #[test]
+    fn release_allows_retry_after_restart() {
+        let path = ledger_path("release.txt");
+        let bot = Address::repeat_byte(0xcd);
+        let other = Address::repeat_byte(0xef);
+
+        let ledger = SubmissionLedger::load(Some(&path)).unwrap();
+        ledger.claim(bot).unwrap();
+        ledger.claim(other).unwrap();
+        ledger.release(bot).unwrap();
+
+        let reloaded = SubmissionLedger::load(Some(&path)).unwrap();
+        assert!(reloaded.claim(bot).unwrap());
+        assert!(!reloaded.claim(other).unwrap());
+    }
+}
@@ -0,0 +1,215 @@
+//! # Sender Allow/Deny Lists
+//!
+//! `ALLOWLIST_PATH` senders are never flagged (a recurring false positive,
+//! a known market maker); `DENYLIST_PATH` senders are always flagged once
+//! scored. Each file holds one address per line, `#` starts a comment.
+//! Operators edit them live from the TUI (`a` / `d` on the selected row);
+//! an edit appends the address to its file, or comments out its line when
+//! it moves to the other list, so it survives a restart. Every other line,
+//! comments included, is kept as written.
+
+use eyre::{Result, WrapErr};
+use std::collections::BTreeSet;
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+
+/// Lists shared by the processor (reads) and the TUI (edits)
+pub type SharedLists = Arc<RwLock<AddressLists>>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListKind {
+    Allow,
+    Deny,
+}
+
+impl fmt::Display for ListKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Allow => write!(f, "allowlist"),
+            Self::Deny => write!(f, "denylist"),
+        }
+    }
+}
+
+/// Lowercased addresses plus the file they persist to (None = session only)
+#[derive(Debug, Default)]
+pub struct AddressList {
+    path: Option<PathBuf>,
+    addresses: BTreeSet<String>,
+}
+
+impl AddressList {
+    /// Read `path`; a missing file is an empty list that is created on first edit
+    pub fn load(path: Option<&str>) -> Result<Self> {
+        let Some(path) = path else {
+            return Ok(Self::default());
+        };
+        let addresses = match std::fs::read_to_string(path) {
+            Ok(contents) => parse(&contents),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeSet::new(),
+            Err(e) => return Err(e).wrap_err_with(|| format!("Failed to read {}", path)),
+        };
+        Ok(Self {
+            path: Some(PathBuf::from(path)),
+            addresses,
+        })
+    }
+
+    pub fn contains(&self, address: &str) -> bool {
+        self.addresses.contains(&address.to_lowercase())
+    }
+
+    pub fn len(&self) -> usize {
+        self.addresses.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.addresses.is_empty()
+    }
+
+    /// Append `address` (lowercase) as a line of its own
+    fn save_added(&self, address: &str) -> std::io::Result<()> {
+        self.rewrite(|contents| {
+            let mut contents = contents.to_string();
+            if !contents.is_empty() && !contents.ends_with('\n') {
+                contents.push('\n');
+            }
+            contents.push_str(address);
+            contents.push('\n');
+            contents
+        })
+    }
+
+    /// Comment out the lines holding `address` (lowercase), keeping any
+    /// annotation on them
+    fn save_removed(&self, address: &str) -> std::io::Result<()> {
+        self.rewrite(|contents| {
+            contents
+                .split_inclusive('\n')
+                .map(|line| {
+                    if line_address(line) == address {
+                        format!("# {}", line)
+                    } else {
+                        line.to_string()
+                    }
+                })
+                .collect()
+        })
+    }
+
+    /// Replace the file with `edit` of its contents, through a temporary
+    /// file and a rename so a crash never leaves it half-written
+    fn rewrite(&self, edit: impl FnOnce(&str) -> String) -> std::io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let contents = match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e),
+        };
+        let mut tmp = path.clone().into_os_string();
+        tmp.push(".tmp");
+        std::fs::write(&tmp, edit(&contents))?;
+        std::fs::rename(tmp, path)
+    }
+}
+
+/// The lowercase address on `line`, or "" for a blank or comment line
+fn line_address(line: &str) -> String {
+    line.split('#').next().unwrap_or("").trim().to_lowercase()
+}
+
+fn parse(contents: &str) -> BTreeSet<String> {
+    contents
+        .lines()
+        .map(line_address)
+        .filter(|address| !address.is_empty())
+        .collect()
+}
+
+#[derive(Debug, Default)]
+pub struct AddressLists {
+    pub allow: AddressList,
+    pub deny: AddressList,
+}
+
+impl AddressLists {
+    pub fn load(allow_path: Option<&str>, deny_path: Option<&str>) -> Result<Self> {
+        Ok(Self {
+            allow: AddressList::load(allow_path)?,
+            deny: AddressList::load(deny_path)?,
+        })
+    }
+
+    /// Which list `address` is on, if any
+    pub fn verdict(&self, address: &str) -> Option<ListKind> {
+        if self.allow.contains(address) {
+            Some(ListKind::Allow)
+        } else if self.deny.contains(address) {
+            Some(ListKind::Deny)
+        } else {
+            None
+        }
+    }
+
+    /// Put `address` on `kind`, taking it off the other list, and persist
+    /// the edit to both files. Returns false when it was already there.
+    pub fn add(&mut self, kind: ListKind, address: &str) -> std::io::Result<bool> {
+        let address = address.to_lowercase();
+        let (list, other) = match kind {
+            ListKind::Allow => (&mut self.allow, &mut self.deny),
+            ListKind::Deny => (&mut self.deny, &mut self.allow),
+        };
+        if !list.addresses.insert(address.clone()) {
+            return Ok(false);
+        }
+        list.save_added(&address)?;
+        if other.addresses.remove(&address) {
+            other.save_removed(&address)?;
+        }
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edits_move_addresses_between_lists_and_persist() {
+        let dir = std::env::temp_dir().join(format!("beetrap-lists-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let allow = dir.join("allow.txt");
+        let deny = dir.join("deny.txt");
+        std::fs::write(&allow, "# market makers\n0xAAAA  # desk\n0xCCCC # otc\n").unwrap();
+        std::fs::write(&deny, "# sandwich bots\n0xDDDD").unwrap();
+
+        let mut lists = AddressLists::load(allow.to_str(), deny.to_str()).unwrap();
+        assert_eq!(lists.verdict("0xaaaa"), Some(ListKind::Allow));
+        assert_eq!(lists.verdict("0xBBBB"), None);
+
+        assert!(lists.add(ListKind::Deny, "0xBBBB").unwrap());
+        assert!(!lists.add(ListKind::Deny, "0xbbbb").unwrap());
+        assert!(lists.add(ListKind::Deny, "0xAAAA").unwrap());
+        assert_eq!(lists.verdict("0xaaaa"), Some(ListKind::Deny));
+
+        // Reloading sees the edits
+        let reloaded = AddressLists::load(allow.to_str(), deny.to_str()).unwrap();
+        assert_eq!(reloaded.allow.len(), 1);
+        assert_eq!(reloaded.deny.len(), 3);
+
+        // Only the moved address's line changed; comments are kept
+        assert_eq!(
+            std::fs::read_to_string(&allow).unwrap(),
+            "# market makers\n# 0xAAAA  # desk\n0xCCCC # otc\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(&deny).unwrap(),
+            "# sandwich bots\n0xDDDD\n0xbbbb\n0xaaaa\n"
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
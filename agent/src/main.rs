@@ -1,73 +1,378 @@
 use crate::{
     indexer::spawn_mempool_listener,
-    processor::spawn_processor,
-    types::{Config, PendingTransaction, UiMessage},
+    processor::{spawn_processor, ProcessorSettings},
+    types::{Config, NetworkStatus, PendingTransaction, Reorg, UiMessage, UiSender},
 };
 use eyre::Result;
-use tokio::sync::mpsc;
-use tracing::{info, Level};
-use tracing_subscriber::FmtSubscriber;
+use std::time::{Duration, SystemTime};
+use tokio::sync::{mpsc, watch};
+use tracing::info;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::layer::SubscriberExt;
 
+pub mod api;
+pub mod artifacts;
+pub mod audit;
+pub mod banner;
+pub mod chains;
+pub mod clusters;
+pub mod dataset;
+pub mod detections;
+pub mod error;
+pub mod explain;
+pub mod features;
+pub mod history;
+pub mod hook_abi;
 pub mod indexer;
+pub mod ledger;
+pub mod lists;
 pub mod network;
+pub mod pacer;
+pub mod policy;
 pub mod processor;
+pub mod prover;
+pub mod relay;
+pub mod router;
+pub mod schedule;
+pub mod simulation;
+pub mod telemetry;
+#[cfg(test)]
+mod test_support;
 pub mod types;
 pub mod ui; // Add UI module
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    // 1. Initialize Logging (File only, to avoid TUI conflict)
+    // 1. Load Config (needed first for the log rotation policy)
+    let mut config = Config::from_env()?;
+
+    // 2. Initialize Logging (File only, to avoid TUI conflict)
     // TUI takes over stdout. We should log to file.
-    let file_appender = tracing_appender::rolling::daily("logs", "sentinel.log");
+    let rotation = match config.log_rotation.as_str() {
+        "hourly" => Rotation::HOURLY,
+        "never" => Rotation::NEVER,
+        _ => Rotation::DAILY,
+    };
+    let removed_logs = prune_old_logs("logs", config.log_retention_days);
+    let file_appender = RollingFileAppender::new(rotation, "logs", "sentinel.log");
     let (non_blocking, _guard) = tracing_appender::non_blocking(file_appender);
 
-    let subscriber = FmtSubscriber::builder()
-        .with_max_level(Level::INFO)
-        .with_writer(non_blocking)
-        .with_ansi(false) // Disable colors for file log
-        .finish();
+    // Optional OTLP export of the same spans; the guard flushes them on exit
+    let (otel_layer, _telemetry) = match config.otlp_endpoint.as_deref() {
+        Some(endpoint) => {
+            let (layer, guard) = telemetry::layer(endpoint)?;
+            (Some(layer), Some(guard))
+        }
+        None => (None, None),
+    };
+
+    let subscriber = tracing_subscriber::registry()
+        .with(LevelFilter::INFO)
+        .with(
+            tracing_subscriber::fmt::layer()
+                .with_writer(non_blocking)
+                .with_ansi(false), // Disable colors for file log
+        )
+        .with(otel_layer);
 
     tracing::subscriber::set_global_default(subscriber)?;
 
-    // 2. Load Config
-    let config = Config::from_env()?;
     info!("Starting BeeTrap Sentinel...");
+    if removed_logs > 0 {
+        info!(
+            "Removed {} log file(s) older than {} day(s)",
+            removed_logs, config.log_retention_days
+        );
+    }
+    // A reordered feature mapping would score garbage without any error
+    processor::check_feature_order()?;
+
+    // `explain <tx_hash>`: score one transaction and exit, without the TUI
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).map(String::as_str) == Some("explain") {
+        let hash = args
+            .get(2)
+            .ok_or_else(|| eyre::eyre!("Usage: beetrap-sentinel explain <tx_hash>"))?;
+        return explain::run(&config, hash).await;
+    }
+
+    // `verify-audit [path]`: check the audit log's hash chain and exit
+    if args.get(1).map(String::as_str) == Some("verify-audit") {
+        let path = args
+            .get(2)
+            .cloned()
+            .or(config.audit_log_path.clone())
+            .ok_or_else(|| eyre::eyre!("Usage: beetrap-sentinel verify-audit <path>"))?;
+        let records = audit::verify(&path)?;
+        println!("{}: {} record(s), chain intact", path, records);
+        return Ok(());
+    }
+
     info!("RPC URL: {}", config.rpc_url);
-    info!("Target Pool Manager: {}", config.pool_manager_address);
+    info!(
+        "Target Pool Manager(s): {}",
+        config.pool_manager_addresses.join(", ")
+    );
+
+    // Preflight: validate both endpoints before the TUI takes over the terminal
+    // (scoring-only mode never touches the execution chain)
+    let mut endpoints = vec![("Mempool", &config.rpc_url)];
+    let mut chains = Vec::new();
+    if !config.scoring_only {
+        endpoints.push(("Execution", &config.execution_rpc_url));
+    }
+    for (label, url) in endpoints {
+        match network::check_endpoint(url).await {
+            Ok((chain_id, block_number)) => {
+                info!(
+                    "{} RPC OK: {} (chain id {}, block #{})",
+                    label, url, chain_id, block_number
+                );
+                chains.push(banner::ChainInfo {
+                    label,
+                    url: url.clone(),
+                    chain_id,
+                });
+            }
+            Err(e) => {
+                tracing::error!("{} RPC preflight failed for {}: {:?}", label, url, e);
+                eprintln!("{} RPC unreachable ({}): {}", label, url, e);
+                return Err(
+                    eyre::Report::new(e).wrap_err(format!("{} RPC preflight failed", label))
+                );
+            }
+        }
+    }
+
+    // Amounts are labelled in each chain's own currency
+    let chain_of = |label: &str| {
+        chains
+            .iter()
+            .find(|c| c.label == label)
+            .map(|c| chains::Chain::by_id(c.chain_id))
+    };
+    let monitored = chain_of("Mempool").unwrap_or_default();
+    let units = chains::Units {
+        monitored,
+        execution: chain_of("Execution").unwrap_or(monitored),
+        precision: config.eth_precision,
+    };
 
     // 3. Setup Channels
     // Channel from Indexer -> Processor (Bounded to 100 to prevent OOM)
-    let (tx_sender, tx_receiver) = mpsc::channel::<PendingTransaction>(100);
+    let (tx_sender, tx_receiver) = mpsc::channel::<PendingTransaction>(indexer::TX_QUEUE_CAPACITY);
 
     // Channel from Processor/Indexer -> UI (TUI)
     let (ui_sender, ui_receiver) = mpsc::unbounded_channel::<UiMessage>();
+    let ui_sender = UiSender::new(ui_sender);
+
+    // Explanation requests for the selected tx: UI -> Processor
+    let (explain_sender, explain_receiver) = mpsc::unbounded_channel::<String>();
+
+    // Allow/deny lists: read by the processor, edited from the TUI
+    let lists = std::sync::Arc::new(std::sync::RwLock::new(lists::AddressLists::load(
+        config.allowlist_path.as_deref(),
+        config.denylist_path.as_deref(),
+    )?));
+    let processor_lists = lists.clone();
+
+    // Model reload requests (SIGHUP) -> Processor
+    let (reload_sender, reload_receiver) = mpsc::unbounded_channel::<()>();
+    spawn_reload_signal(reload_sender);
+
+    // Latest block number: Indexer -> Processor
+    let (block_sender, block_receiver) = watch::channel::<u64>(0);
+
+    // Reorgs: Indexer -> Processor, for follow-up detection records
+    let (reorg_sender, reorg_receiver) = mpsc::unbounded_channel::<Reorg>();
+
+    // 4. Setup Network Client (Signer), unless we only score
+    let client = if config.scoring_only {
+        info!("SCORING_ONLY: no wallet or execution client, detections are not trapped");
+        None
+    } else {
+        info!(
+            "Initializing Sentinel Client (Executor -> {})...",
+            config.execution_rpc_url
+        );
+        // We hack the config temporarily or update build_client to use execution_rpc_url
+        // Actually network::build_client uses config.rpc_url. We should fix network.rs too or swap it here.
+        // Let's swap it here for simplicity:
+        let mut execution_config = config.clone();
+        execution_config.rpc_url = config.execution_rpc_url.clone();
+        let client = network::build_client(&execution_config).await?;
+        Some(std::sync::Arc::new(client))
+    };
+
+    // On-chain policy: the AgentNFT metadata overrides env config when present
+    if let Some(client) = client.as_ref().filter(|_| config.onchain_config) {
+        let policy = client
+            .agent_token_uri()
+            .await
+            .map_err(eyre::Report::from)
+            .and_then(|uri| policy::parse_token_uri(&uri));
+        match policy {
+            Ok(Some(policy)) => {
+                info!("Applying on-chain agent policy: {:?}", policy);
+                policy.apply(&mut config);
+            }
+            Ok(None) => info!("No on-chain policy in AgentNFT metadata, using env config"),
+            Err(e) => tracing::warn!("Failed to read on-chain policy, using env config: {:?}", e),
+        }
+    }
 
-    // 4. Setup Network Client (Signer)
+    // What this instance is actually doing, after any on-chain overrides
+    let line_mode = std::env::args().any(|a| a == "--line-mode");
+    let headless = std::env::var("HEADLESS").is_ok();
+    let ui_mode = if line_mode {
+        "line mode"
+    } else if headless {
+        "headless"
+    } else {
+        "TUI"
+    };
+    config.inference_provider = processor::resolve_inference_provider(config.inference_provider);
+    let model_hash = std::fs::read(&config.model_path)
+        .ok()
+        .map(|model| audit::keccak(&model));
+    let summary = banner::startup_summary(&config, &chains, model_hash, ui_mode);
     info!(
-        "Initializing Sentinel Client (Executor -> {})...",
-        config.execution_rpc_url
+        "Startup summary\n{}",
+        banner::boxed("BeeTrap Sentinel", &summary)
     );
-    // We hack the config temporarily or update build_client to use execution_rpc_url
-    // Actually network::build_client uses config.rpc_url. We should fix network.rs too or swap it here.
-    // Let's swap it here for simplicity:
-    let mut execution_config = config.clone();
-    execution_config.rpc_url = config.execution_rpc_url.clone();
-    let client = network::build_client(&execution_config).await?;
-    let client = std::sync::Arc::new(client);
+    let _ = ui_sender.send(UiMessage::StartupSummary(summary));
+
+    // Poll the execution chain: gas price (header display + submission
+    // logging) and connection/head for its own header segment
+    let gas_ui_sender = ui_sender.clone();
+    let gas_poll_interval = std::time::Duration::from_secs(config.gas_poll_interval_secs.max(1));
+    let degrade_after = config.execution_degrade_after;
+    let gas_poll_handle = client.clone().map(|gas_client| {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(gas_poll_interval);
+            let mut status = NetworkStatus::default();
+            // Execution down while the mempool is up: keep scoring, stop trapping
+            let mut health = network::ExecutionHealth::new(degrade_after);
+            loop {
+                interval.tick().await;
+                match gas_client.poll_gas_price().await {
+                    Ok(price) => {
+                        status.gas_price = price;
+                        let _ = gas_ui_sender.send(UiMessage::ExecutionGasUpdate(price));
+                    }
+                    Err(e) => tracing::warn!("Execution gas price poll failed: {}", e),
+                }
+                match gas_client.poll_execution_head().await {
+                    Ok((chain_id, block_number)) => {
+                        status.chain_id = chain_id;
+                        status.block_number = block_number;
+                        status.connected = true;
+                    }
+                    Err(e) => {
+                        tracing::warn!("Execution head poll failed: {}", e);
+                        status.connected = false;
+                    }
+                }
+                if let Some(degraded) = health.observe(status.connected) {
+                    gas_client.set_execution_degraded(degraded);
+                    let msg = if degraded {
+                        tracing::warn!(
+                            "Execution RPC unreachable for {} polls: scoring only until it returns",
+                            degrade_after
+                        );
+                        "Execution degraded: scoring only, traps paused"
+                    } else {
+                        tracing::info!("Execution RPC recovered: trapping resumed");
+                        "Execution recovered: traps resumed"
+                    };
+                    let _ = gas_ui_sender.send(UiMessage::Log(msg.to_string()));
+                    let _ = gas_ui_sender.send(UiMessage::ExecutionDegraded(degraded));
+                }
+                let _ = gas_ui_sender.send(UiMessage::ExecutionNetworkUpdate(status.clone()));
+            }
+        })
+    });
+
+    // Poll signer balances; warn once when they cross the minimum
+    let balance_ui_sender = ui_sender.clone();
+    let min_balance = config.min_wallet_balance_wei;
+    let balance_poll_interval =
+        std::time::Duration::from_secs(config.balance_poll_interval_secs.max(1));
+    let balance_poll_handle = client.clone().map(|balance_client| {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(balance_poll_interval);
+            let mut was_low = false;
+            loop {
+                interval.tick().await;
+                match balance_client.poll_wallet_balance().await {
+                    Ok(balance) => {
+                        let low = balance < min_balance;
+                        if low && !was_low {
+                            tracing::warn!(
+                                "LOW WALLET BALANCE: {} wei (minimum {} wei)",
+                                balance,
+                                min_balance
+                            );
+                            let _ = balance_ui_sender.send(UiMessage::Log(format!(
+                                "LOW WALLET BALANCE: {}, traps paused",
+                                units.execution_amount(balance as f64 / 1e18)
+                            )));
+                        }
+                        was_low = low;
+                        let _ =
+                            balance_ui_sender.send(UiMessage::WalletBalanceUpdate(balance, low));
+                    }
+                    Err(e) => tracing::warn!("Wallet balance poll failed: {}", e),
+                }
+            }
+        })
+    });
+
+    // Hard bound on proof artifacts left behind by crashes and aborted proofs
+    let sweeper_handle = (config.prove_dir_max_mb > 0)
+        .then(|| artifacts::spawn_sweeper(config.prove_dir_max_mb * 1024 * 1024));
+
+    // Read-only view of the resolved config for ops tooling
+    let api_handle = match config.api_addr.as_deref() {
+        Some(addr) => Some(api::spawn_server(addr, std::sync::Arc::new(config.clone())).await?),
+        None => None,
+    };
+
+    // Header state for TRAP_SCHEDULE
+    let schedule_handle = config
+        .trap_schedule
+        .clone()
+        .map(|schedule| schedule::spawn_watch(schedule, ui_sender.clone()));
 
     // 4. Spawn Indexer
     let rpc_url = config.rpc_url.clone(); // MAINNET: Listen for traffic
-    let target_address = config.pool_manager_address.clone();
-    let router_address = config.universal_router_address.clone();
+    let targets = indexer::Targets::parse(
+        &config.pool_manager_addresses,
+        &config.universal_router_addresses,
+    )?;
+    let batch_window = std::time::Duration::from_millis(config.tx_batch_window_ms);
+    let fee_model = config.fee_model;
+    let detections_raw_tx = config.detections_raw_tx;
+    let reorg_window = config.reorg_window_blocks;
+    // Every mempool hash the listener sees; the processor only sees a sample
+    let observed = std::sync::Arc::new(std::sync::atomic::AtomicU64::new(0));
+    let observed_listener = observed.clone();
     let tx_sender_clone = tx_sender.clone();
     let ui_sender_clone = ui_sender.clone();
     let indexer_handle = tokio::spawn(async move {
         if let Err(e) = spawn_mempool_listener(
             rpc_url,
-            target_address,
-            router_address,
+            targets,
             tx_sender_clone,
             ui_sender_clone,
+            block_sender,
+            batch_window,
+            fee_model,
+            detections_raw_tx,
+            reorg_window,
+            reorg_sender,
+            observed_listener,
         )
         .await
         {
@@ -80,7 +385,14 @@ async fn main() -> Result<()> {
     let model_path = config.model_path.clone();
     let rpc_url_processor = config.execution_rpc_url.clone(); // UNICHAIN: Execute/Estimate
 
-    let confidence_threshold = config.confidence_threshold;
+    let settings = ProcessorSettings::from(&config);
+    // The TUI flips this to arm or stand down
+    let arm_switch = client.as_ref().map(|c| c.arm_switch());
+    if arm_switch.is_some() && !config.start_armed && (line_mode || headless) {
+        tracing::warn!(
+            "START_ARMED is off and there is no TUI to arm from: nothing will be submitted"
+        );
+    }
 
     let processor_handle = tokio::spawn(async move {
         if let Err(e) = spawn_processor(
@@ -88,8 +400,14 @@ async fn main() -> Result<()> {
             ui_sender,
             model_path,
             rpc_url_processor,
-            confidence_threshold,
+            settings,
             client,
+            block_receiver,
+            explain_receiver,
+            reload_receiver,
+            reorg_receiver,
+            processor_lists,
+            observed,
         )
         .await
         {
@@ -98,25 +416,58 @@ async fn main() -> Result<()> {
     });
 
     // 6. Run TUI (Blocking Main Thread)
-    // 6. Run TUI or Headless
-    if std::env::var("HEADLESS").is_ok() {
+    // 6. Run TUI, Line Mode or Headless
+    if line_mode {
+        info!("Running in LINE mode (compact stdout events)");
+        ui::line::run_line_mode(ui_receiver, units).await;
+    } else if headless {
         info!("Running in HEADLESS mode. Logs in logs/sentinel.log");
         // Drain UI receiver to prevent memory leak and log important events
         let mut rx = ui_receiver;
         while let Some(msg) = rx.recv().await {
             match msg {
                 UiMessage::Log(s) => info!("[UI LOG] {}", s),
-                UiMessage::NewDetection(d) => info!(
-                    "[DETECTED] Bot: {} (Confidence: {:.4})",
-                    d.bot_address, d.confidence
-                ),
+                UiMessage::NewDetection(d) => match d.features.as_ref() {
+                    Some(features) => info!(
+                        features = %detections::features_json(features),
+                        "[DETECTED] Bot: {} (Confidence: {:.4})",
+                        d.bot_address,
+                        d.confidence
+                    ),
+                    None => info!(
+                        "[DETECTED] Bot: {} (Confidence: {:.4})",
+                        d.bot_address, d.confidence
+                    ),
+                },
                 _ => {}
             }
         }
     } else {
         // Must run in current thread to handle terminal
         info!("Launching TUI...");
-        if let Err(e) = ui::run_tui(ui_receiver, config.confidence_threshold).await {
+        // A bad theme only costs colors; keep the sentinel running
+        let theme = ui::theme::Theme::resolve(&config.ui_theme).unwrap_or_else(|e| {
+            tracing::warn!("{:?}. Using the default theme.", e);
+            ui::theme::Theme::default()
+        });
+        if let Err(e) = ui::run_tui(
+            ui_receiver,
+            explain_sender,
+            lists,
+            config.dataset_csv.clone(),
+            config.decision_threshold(),
+            config.model_task,
+            (config.ui_tx_max_age_secs > 0)
+                .then(|| std::time::Duration::from_secs(config.ui_tx_max_age_secs)),
+            config.mouse_capture && !std::env::args().any(|a| a == "--no-mouse"),
+            units,
+            config.ui_max_detections,
+            theme,
+            config.score_display,
+            arm_switch,
+        )
+        .await
+        {
             eprintln!("TUI Error: {}", e);
         }
     }
@@ -125,6 +476,71 @@ async fn main() -> Result<()> {
     // We can abort background tasks
     indexer_handle.abort();
     processor_handle.abort();
+    for handle in [
+        gas_poll_handle,
+        balance_poll_handle,
+        sweeper_handle,
+        api_handle,
+        schedule_handle,
+    ]
+    .into_iter()
+    .flatten()
+    {
+        handle.abort();
+    }
 
     Ok(())
 }
+
+/// Forwards SIGHUP as a model reload request (`kill -HUP <pid>`)
+fn spawn_reload_signal(reload_sender: mpsc::UnboundedSender<()>) {
+    #[cfg(unix)]
+    tokio::spawn(async move {
+        use tokio::signal::unix::{signal, SignalKind};
+        let mut hangup = match signal(SignalKind::hangup()) {
+            Ok(hangup) => hangup,
+            Err(e) => {
+                tracing::warn!("SIGHUP handler unavailable, model reload disabled: {}", e);
+                return;
+            }
+        };
+        while hangup.recv().await.is_some() {
+            info!("SIGHUP received: reloading model");
+            if reload_sender.send(()).is_err() {
+                break;
+            }
+        }
+    });
+    #[cfg(not(unix))]
+    drop(reload_sender);
+}
+
+/// Deletes rotated `sentinel.log.*` files older than the retention window.
+/// Returns the number of files removed; a retention of 0 disables the sweep.
+fn prune_old_logs(dir: &str, retention_days: u64) -> usize {
+    if retention_days == 0 {
+        return 0;
+    }
+    let max_age = Duration::from_secs(retention_days * 24 * 60 * 60);
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return 0;
+    };
+
+    let mut removed = 0;
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        if !name.to_string_lossy().starts_with("sentinel.log.") {
+            continue;
+        }
+        let expired = entry
+            .metadata()
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+            .is_some_and(|age| age > max_age);
+        if expired && std::fs::remove_file(entry.path()).is_ok() {
+            removed += 1;
+        }
+    }
+    removed
+}
@@ -1,10 +1,22 @@
-use crate::types::Config;
+use crate::error::SentinelError;
+use crate::hook_abi::{TrapCall, TrapCallArgs};
+use crate::ledger::SubmissionLedger;
+use crate::pacer::SubmissionPacer;
+use crate::relay::{PrivateRelay, PRIVATE_TX_MAX_BLOCKS};
+use crate::simulation::Simulator;
+use crate::types::{Config, TrapAction};
+use alloy::network::TransactionBuilder;
 use alloy::primitives::{Address, Bytes, U256};
+use alloy::providers::fillers::{CachedNonceManager, ChainIdFiller, NonceFiller};
 use alloy::providers::{Provider, ProviderBuilder, WsConnect};
+use alloy::rpc::types::TransactionRequest;
 use alloy::signers::local::PrivateKeySigner;
 use alloy::sol;
 use eyre::{Result, WrapErr};
 use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use tracing::{info, warn};
 
 // Define the AgentNFT interface (Proxy)
 sol! {
@@ -17,6 +29,7 @@ sol! {
             bytes calldata proof,
             uint256[] calldata publicInputs
         ) external;
+        function tokenURI(uint256 tokenId) external view returns (string memory);
     }
 
     #[sol(rpc)]
@@ -28,6 +41,41 @@ sol! {
             bytes calldata proof,
             uint256[] calldata publicInputs
         ) external;
+        function recordProof(
+            address bot,
+            bytes calldata proof,
+            uint256[] calldata publicInputs
+        ) external;
+    }
+}
+
+/// Gas budgeted for one `markAsPredatorWithProof` (Halo2 verification dominates)
+const TRAP_GAS_ESTIMATE: u128 = 800_000;
+
+/// An included trap transaction and what it cost
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrapReceipt {
+    pub hash: String,
+    /// Executed without reverting
+    pub success: bool,
+    pub gas_used: u128,
+    /// Price actually paid per gas, in wei
+    pub effective_gas_price: u128,
+}
+
+impl TrapReceipt {
+    fn from_receipt(receipt: &alloy::rpc::types::TransactionReceipt) -> Self {
+        Self {
+            hash: receipt.transaction_hash.to_string(),
+            success: receipt.status(),
+            gas_used: receipt.gas_used,
+            effective_gas_price: receipt.effective_gas_price,
+        }
+    }
+
+    /// Fee paid for the trap, in wei
+    pub fn cost_wei(&self) -> u128 {
+        self.gas_used.saturating_mul(self.effective_gas_price)
     }
 }
 
@@ -41,6 +89,31 @@ pub struct SentinelClient<P> {
         alloy::network::Ethereum,
     >,
     agent_token_id: U256,
+    /// Signer addresses registered in the provider wallet, used round-robin
+    signers: Vec<Address>,
+    next_signer: AtomicUsize,
+    /// Proofs larger than this are rejected before sending (calldata limit)
+    max_proof_bytes: usize,
+    /// Proofs with more public inputs than this are rejected before sending (0 = no limit)
+    max_public_inputs: usize,
+    /// Bots with a submission already sent or in flight
+    ledger: SubmissionLedger,
+    /// Last polled gas price on the execution chain, in wei (0 = not polled yet)
+    gas_price: AtomicU64,
+    /// Lowest signer balance from the last poll, in wei
+    wallet_balance: Mutex<Option<u128>>,
+    /// Trap function from `HOOK_ABI_PATH`; the compiled binding when `None`
+    trap_call: Option<TrapCall>,
+    /// Private relay tried before the public mempool (`PRIVATE_TX_URL`)
+    private_relay: Option<PrivateRelay>,
+    /// Rate limit on submissions (`SUBMIT_MIN_INTERVAL_MS`)
+    pacer: Option<SubmissionPacer>,
+    /// Fork every submission is mirrored to (`SIMULATION_RPC_URL`)
+    simulator: Option<Simulator>,
+    /// Submissions are withheld while off (warm standby, `START_ARMED`)
+    armed: Arc<AtomicBool>,
+    /// Execution RPC unreachable: detections are scored but not trapped
+    execution_degraded: AtomicBool,
 }
 
 impl<P> SentinelClient<P>
@@ -48,63 +121,406 @@ where
     P: Provider<alloy::pubsub::PubSubFrontend, alloy::network::Ethereum> + Clone,
 {
     /// Create a new SentinelClient
-    pub fn new(provider: P, agent_nft_addr: Address, hook_addr: Address, token_id: U256) -> Self {
+    ///
+    /// `signers` must all be registered in the provider's wallet, and there
+    /// must be at least one: submissions rotate through them.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        provider: P,
+        agent_nft_addr: Address,
+        hook_addr: Address,
+        token_id: U256,
+        signers: Vec<Address>,
+        max_proof_bytes: usize,
+        max_public_inputs: usize,
+        ledger: SubmissionLedger,
+    ) -> Result<Self> {
+        if signers.is_empty() {
+            return Err(eyre::eyre!("SentinelClient needs at least one signer"));
+        }
         let agent_nft = AgentNFT::new(agent_nft_addr, provider.clone());
         let beetrap_hook = BeeTrapHook::new(hook_addr, provider);
-        Self {
+        Ok(Self {
             agent_nft,
             beetrap_hook,
             agent_token_id: token_id,
+            signers,
+            next_signer: AtomicUsize::new(0),
+            max_proof_bytes,
+            max_public_inputs,
+            ledger,
+            gas_price: AtomicU64::new(0),
+            wallet_balance: Mutex::new(None),
+            trap_call: None,
+            private_relay: None,
+            pacer: None,
+            simulator: None,
+            armed: Arc::new(AtomicBool::new(true)),
+            execution_degraded: AtomicBool::new(false),
+        })
+    }
+
+    /// Send traps through `relay` first, falling back to the public mempool
+    pub fn with_private_relay(mut self, relay: PrivateRelay) -> Self {
+        self.private_relay = Some(relay);
+        self
+    }
+
+    /// Space submissions out to stay within the execution provider's limits
+    pub fn with_pacer(mut self, pacer: SubmissionPacer) -> Self {
+        self.pacer = Some(pacer);
+        self
+    }
+
+    /// Mirror every submission to `simulator` and log where they disagree
+    pub fn with_simulator(mut self, simulator: Simulator) -> Self {
+        self.simulator = Some(simulator);
+        self
+    }
+
+    /// Start armed or in warm standby
+    pub fn with_armed(self, armed: bool) -> Self {
+        self.armed.store(armed, Ordering::Relaxed);
+        self
+    }
+
+    /// Whether submissions are currently enabled
+    pub fn is_armed(&self) -> bool {
+        self.armed.load(Ordering::Relaxed)
+    }
+
+    /// Shared switch gating submissions, flipped from the TUI
+    pub fn arm_switch(&self) -> Arc<AtomicBool> {
+        self.armed.clone()
+    }
+
+    /// Whether the execution RPC is considered down (`EXECUTION_DEGRADE_AFTER`)
+    pub fn execution_degraded(&self) -> bool {
+        self.execution_degraded.load(Ordering::Relaxed)
+    }
+
+    pub fn set_execution_degraded(&self, degraded: bool) {
+        self.execution_degraded.store(degraded, Ordering::Relaxed);
+    }
+
+    /// Submissions waiting for pacing, and those dropped with the queue full
+    pub fn submission_queue(&self) -> Option<(usize, u64)> {
+        self.pacer.as_ref().map(|p| (p.queued(), p.dropped()))
+    }
+
+    /// Encode trap submissions from a loaded hook ABI instead of the compiled binding
+    pub fn with_trap_call(mut self, trap_call: TrapCall) -> Self {
+        self.trap_call = Some(trap_call);
+        self
+    }
+
+    /// Fetch the execution chain's gas price and remember it
+    pub async fn poll_gas_price(&self) -> Result<u128, SentinelError> {
+        let price = self.beetrap_hook.provider().get_gas_price().await?;
+        self.gas_price
+            .store(price.min(u64::MAX as u128) as u64, Ordering::Relaxed);
+        Ok(price)
+    }
+
+    /// Chain id and latest block of the execution chain
+    pub async fn poll_execution_head(&self) -> Result<(u64, u64), SentinelError> {
+        let provider = self.beetrap_hook.provider();
+        let chain_id = provider.get_chain_id().await?;
+        let block_number = provider.get_block_number().await?;
+        Ok((chain_id, block_number))
+    }
+
+    /// Last polled execution-chain gas price in wei, if any
+    pub fn latest_gas_price(&self) -> Option<u128> {
+        match self.gas_price.load(Ordering::Relaxed) {
+            0 => None,
+            price => Some(price as u128),
+        }
+    }
+
+    /// Fetch every signer's balance and remember the lowest
+    pub async fn poll_wallet_balance(&self) -> Result<u128, SentinelError> {
+        let provider = self.beetrap_hook.provider();
+        let mut lowest = u128::MAX;
+        for signer in &self.signers {
+            let balance = provider.get_balance(*signer).await?;
+            lowest = lowest.min(balance.saturating_to::<u128>());
+        }
+        *self
+            .wallet_balance
+            .lock()
+            .unwrap_or_else(|e| e.into_inner()) = Some(lowest);
+        Ok(lowest)
+    }
+
+    /// Lowest signer balance from the last poll, if any
+    pub fn latest_wallet_balance(&self) -> Option<u128> {
+        *self
+            .wallet_balance
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Whether the poorest signer still covers `min_balance` and one trap at
+    /// the last polled gas price. An unpolled balance counts as affordable.
+    pub fn can_afford_trap(&self, min_balance: u128) -> bool {
+        let Some(balance) = self.latest_wallet_balance() else {
+            return true;
+        };
+        let trap_cost = self
+            .latest_gas_price()
+            .unwrap_or(0)
+            .saturating_mul(TRAP_GAS_ESTIMATE);
+        balance >= min_balance.max(trap_cost)
+    }
+
+    /// Claim `bot` before proving/submitting; `false` if already submitted or in flight
+    pub fn claim_submission(&self, bot: Address) -> Result<bool> {
+        self.ledger.claim(bot)
+    }
+
+    /// Release a claim after a failed attempt so the bot can be retried
+    pub fn release_submission(&self, bot: Address) {
+        if let Err(e) = self.ledger.release(bot) {
+            tracing::warn!("Failed to release submission claim for {}: {}", bot, e);
         }
     }
 
-    /// Submit a predator detection with ZK proof
+    /// Pick the next signer in round-robin order
+    fn next_signer(&self) -> Address {
+        let idx = self.next_signer.fetch_add(1, Ordering::Relaxed);
+        self.signers[idx % self.signers.len()]
+    }
+
+    /// Submit a predator detection with ZK proof. `action` picks the
+    /// `status` argument: trap the bot, or only record the verified proof.
     pub async fn submit_detection(
         &self,
         bot_address: Address,
+        action: TrapAction,
         proof_bytes: Vec<u8>,
         public_inputs: Vec<U256>,
-    ) -> Result<String> {
+    ) -> Result<TrapReceipt, SentinelError> {
+        // Oversized proofs revert on gas/calldata limits; fail early with the size
+        info!(
+            "Proof size for {}: {} bytes ({} public inputs)",
+            bot_address,
+            proof_bytes.len(),
+            public_inputs.len()
+        );
+        check_calldata_bounds(
+            proof_bytes.len(),
+            public_inputs.len(),
+            self.max_proof_bytes,
+            self.max_public_inputs,
+        )?;
+        if !self.is_armed() {
+            return Err(SentinelError::Disarmed);
+        }
+
+        if let Some(pacer) = &self.pacer {
+            pacer.acquire().await?;
+        }
+
         let proof = Bytes::from(proof_bytes);
+        let signer = self.next_signer();
+        info!(
+            "Submitting {} for {} from signer {} (execution gas: {:.2} Gwei)",
+            action,
+            bot_address,
+            signer,
+            self.latest_gas_price().unwrap_or(0) as f64 / 1e9
+        );
 
         // Call the BeeTrapHook directly (Bypassing AgentNFT to ensure msg.sender == AI_AGENT)
+        let request = match &self.trap_call {
+            Some(trap_call) => {
+                let input = trap_call.encode(&TrapCallArgs {
+                    bot: bot_address,
+                    status: action.status(),
+                    proof,
+                    public_inputs,
+                    token_id: self.agent_token_id,
+                })?;
+                TransactionRequest::default()
+                    .with_to(*self.beetrap_hook.address())
+                    .with_input(input)
+            }
+            // Flag-only goes through `recordProof`, which never writes
+            // `isPredator`: a flag must not clear a mark set in between
+            None => match action {
+                TrapAction::Trap => self
+                    .beetrap_hook
+                    .markAsPredatorWithProof(bot_address, true, proof, public_inputs)
+                    .into_transaction_request(),
+                TrapAction::Flag => self
+                    .beetrap_hook
+                    .recordProof(bot_address, proof, public_inputs)
+                    .into_transaction_request(),
+            },
+        }
+        .with_from(signer);
+
+        let Some(simulator) = &self.simulator else {
+            return self.send_trap(bot_address, request).await;
+        };
+        let (simulated, result) = tokio::join!(
+            simulator.simulate(&request),
+            self.send_trap(bot_address, request.clone())
+        );
+        match crate::simulation::discrepancy(&simulated, &result) {
+            Some(difference) => warn!(
+                "Shadow submission DISCREPANCY for {}: {}",
+                bot_address, difference
+            ),
+            None => info!(
+                "Shadow submission for {}: {:?}, consistent with the real one",
+                bot_address, simulated
+            ),
+        }
+        result
+    }
+
+    /// Send a built trap request, privately first when a relay is configured,
+    /// and wait for its receipt
+    async fn send_trap(
+        &self,
+        bot_address: Address,
+        mut request: TransactionRequest,
+    ) -> Result<TrapReceipt, SentinelError> {
+        // Keep the trap out of the public mempool the bots are watching
+        if let Some(relay) = &self.private_relay {
+            match self.submit_private(relay, request.clone()).await {
+                Ok(receipt) => return Ok(receipt),
+                Err((filled, e)) => {
+                    warn!(
+                        "Private submission for {} failed ({}): {}. Falling back to public mempool.",
+                        bot_address,
+                        e.kind(),
+                        e
+                    );
+                    request = filled;
+                }
+            }
+        }
+
         let tx = self
             .beetrap_hook
-            .markAsPredatorWithProof(
-                bot_address,
-                true, // status = true
-                proof,
-                public_inputs,
-            )
-            .send()
-            .await?;
+            .provider()
+            .send_transaction(request)
+            .await
+            .map_err(|e| match SentinelError::from(e) {
+                SentinelError::Rpc(reason) => SentinelError::Submission(reason),
+                other => other,
+            })?;
+
+        let receipt = tx
+            .get_receipt()
+            .await
+            .map_err(|e| SentinelError::Submission(e.to_string()))?;
 
-        let receipt = tx.get_receipt().await?;
-        let hash = receipt.transaction_hash;
+        Ok(TrapReceipt::from_receipt(&receipt))
+    }
 
-        Ok(hash.to_string())
+    /// Send `request` via the private relay and wait for inclusion.
+    ///
+    /// On failure, returns the request to fall back with: once filled it
+    /// keeps the nonce, so a public resend replaces rather than gaps.
+    async fn submit_private(
+        &self,
+        relay: &PrivateRelay,
+        request: TransactionRequest,
+    ) -> Result<TrapReceipt, (TransactionRequest, SentinelError)> {
+        let provider = self.beetrap_hook.provider();
+        let filled = match relay.fill(provider, request.clone()).await {
+            Ok(filled) => filled,
+            Err(e) => return Err((request, e)),
+        };
+        let outcome = async {
+            let max_block = provider.get_block_number().await? + PRIVATE_TX_MAX_BLOCKS;
+            let hash = relay.send(filled.clone(), max_block).await?;
+            info!(
+                "Trap sent privately as {} (valid to block {})",
+                hash, max_block
+            );
+            loop {
+                if let Some(receipt) = provider.get_transaction_receipt(hash).await? {
+                    return Ok(TrapReceipt::from_receipt(&receipt));
+                }
+                if provider.get_block_number().await? > max_block {
+                    return Err(SentinelError::Submission(format!(
+                        "private trap {} not included by block {}",
+                        hash, max_block
+                    )));
+                }
+                tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+            }
+        }
+        .await;
+        outcome.map_err(|e| (filled, e))
+    }
+
+    /// Fetch the metadata URI of this agent's NFT
+    pub async fn agent_token_uri(&self) -> Result<String, SentinelError> {
+        let return_value = self.agent_nft.tokenURI(self.agent_token_id).call().await?;
+        Ok(return_value._0)
     }
 
     /// Check if an address is already marked as a predator
-    pub async fn is_predator(&self, bot_address: Address) -> Result<bool> {
+    pub async fn is_predator(&self, bot_address: Address) -> Result<bool, SentinelError> {
         let return_value = self.beetrap_hook.isPredator(bot_address).call().await?;
         Ok(return_value._0)
     }
 }
 
+/// Preflight an RPC endpoint: connect and fetch chain id and block number.
+///
+/// Returns `(chain_id, block_number)`.
+pub async fn check_endpoint(url: &str) -> Result<(u64, u64), SentinelError> {
+    let probe = async {
+        let provider = ProviderBuilder::new()
+            .on_ws(WsConnect::new(url))
+            .await
+            .map_err(|e| SentinelError::RpcDisconnected(format!("connection failed: {}", e)))?;
+        let chain_id = provider.get_chain_id().await?;
+        let block_number = provider.get_block_number().await?;
+        Ok::<_, SentinelError>((chain_id, block_number))
+    };
+
+    tokio::time::timeout(std::time::Duration::from_secs(10), probe)
+        .await
+        .map_err(|_| SentinelError::RpcDisconnected("timed out after 10s".to_string()))?
+}
+
 /// Build the client with recommended fillers and wallet
 pub async fn build_client(
     config: &Config,
 ) -> Result<
     SentinelClient<impl Provider<alloy::pubsub::PubSubFrontend, alloy::network::Ethereum> + Clone>,
 > {
-    let signer = PrivateKeySigner::from_str(&config.private_key).wrap_err("Invalid private key")?;
-    let wallet = alloy::network::EthereumWallet::from(signer);
+    // Register every key in one wallet; the `from` field selects the signer per submission
+    let mut signers = Vec::with_capacity(config.private_keys.len());
+    let mut wallet: Option<alloy::network::EthereumWallet> = None;
+    for key in &config.private_keys {
+        let signer = PrivateKeySigner::from_str(key).wrap_err("Invalid private key")?;
+        signers.push(signer.address());
+        match wallet.as_mut() {
+            Some(w) => w.register_signer(signer),
+            None => wallet = Some(alloy::network::EthereumWallet::from(signer)),
+        }
+    }
+    let wallet = wallet.ok_or_else(|| eyre::eyre!("No private keys configured"))?;
+    info!("Loaded {} signer(s) for submissions", signers.len());
 
+    // Cached nonce manager tracks nonces per signer address, so concurrent
+    // submissions from different signers never contend for the same nonce.
     let ws = WsConnect::new(&config.rpc_url);
+    let nonces = CachedNonceManager::default();
     let provider = ProviderBuilder::new()
-        .with_recommended_fillers()
-        .wallet(wallet)
+        .with_gas_estimation()
+        .filler(NonceFiller::new(nonces.clone()))
+        .filler(ChainIdFiller::default())
+        .wallet(wallet.clone())
         .on_ws(ws)
         .await?;
 
@@ -113,10 +529,281 @@ pub async fn build_client(
     let hook_address =
         Address::from_str(&config.hook_address).wrap_err("Invalid BeeTrapHook address")?;
 
-    Ok(SentinelClient::new(
+    let mut client = SentinelClient::new(
         provider,
         agent_nft_address,
         hook_address,
         U256::from(config.agent_nft_id),
-    ))
+        signers,
+        config.max_proof_bytes,
+        config.max_public_inputs,
+        SubmissionLedger::load(config.submission_ledger_path.as_deref())?,
+    )?;
+
+    if let Some(url) = config.private_tx_url.clone() {
+        // The relay only needs a stable identity; it never holds funds
+        let auth = match config.private_tx_auth_key.as_deref() {
+            Some(key) => PrivateKeySigner::from_str(key).wrap_err("Invalid PRIVATE_TX_AUTH_KEY")?,
+            None => PrivateKeySigner::random(),
+        };
+        info!("Trap submissions go to private relay {}", url);
+        client = client.with_private_relay(PrivateRelay::new(url, auth, wallet, nonces));
+    }
+
+    if !config.start_armed {
+        warn!("Warm standby: trap submissions are withheld until the agent is armed");
+    }
+    client = client.with_armed(config.start_armed);
+
+    if config.submit_min_interval_ms > 0 {
+        info!(
+            "Submissions paced: {} ms apart, bursts of {}, up to {} queued",
+            config.submit_min_interval_ms, config.submit_burst, config.submit_queue_max
+        );
+        client = client.with_pacer(SubmissionPacer::new(
+            std::time::Duration::from_millis(config.submit_min_interval_ms),
+            config.submit_burst,
+            config.submit_queue_max,
+        ));
+    }
+
+    if let Some(url) = config.simulation_rpc_url.as_deref() {
+        info!(
+            "Trap submissions are mirrored to simulation endpoint {}",
+            url
+        );
+        match Simulator::connect(url).await {
+            Ok(simulator) => client = client.with_simulator(simulator),
+            Err(e) => warn!("Simulation mirror disabled: {:#}", e),
+        }
+    }
+
+    match config.hook_abi_path.as_deref() {
+        Some(path) => {
+            let trap_call = TrapCall::load(path)?;
+            info!("Hook ABI loaded from {}: {}", path, trap_call.signature());
+            Ok(client.with_trap_call(trap_call))
+        }
+        None => Ok(client),
+    }
+}
+
+/// End-to-end check of the on-chain path against a throwaway Anvil node.
+///
+/// Needs `anvil` on `PATH` and the mock contracts compiled with `forge build`
+/// in `contracts/`. Run with `cargo test --features anvil-e2e`.
+#[cfg(all(test, feature = "anvil-e2e"))]
+mod anvil_e2e {
+    use super::*;
+    use alloy::network::EthereumWallet;
+    use std::process::{Child, Command, Stdio};
+
+    /// Anvil's first default account
+    const ANVIL_KEY: &str = "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+
+    /// Kills the node when the test ends, pass or fail
+    struct AnvilNode(Child);
+
+    impl Drop for AnvilNode {
+        fn drop(&mut self) {
+            let _ = self.0.kill();
+        }
+    }
+
+    fn spawn_anvil(port: u16) -> AnvilNode {
+        let child = Command::new(std::env::var("ANVIL_BIN").unwrap_or_else(|_| "anvil".into()))
+            .args(["--port", &port.to_string(), "--silent"])
+            .stdout(Stdio::null())
+            .spawn()
+            .expect("failed to start anvil (is Foundry installed?)");
+        AnvilNode(child)
+    }
+
+    /// Creation bytecode from a `forge build` artifact
+    fn artifact_bytecode(contract: &str) -> Bytes {
+        let path = format!(
+            "{}/../contracts/out/MockSentinelTargets.sol/{}.json",
+            env!("CARGO_MANIFEST_DIR"),
+            contract
+        );
+        let artifact: serde_json::Value = serde_json::from_str(
+            &std::fs::read_to_string(&path)
+                .unwrap_or_else(|_| panic!("missing {} (run `forge build` in contracts/)", path)),
+        )
+        .unwrap();
+        let code = artifact["bytecode"]["object"].as_str().unwrap();
+        Bytes::from(hex::decode(code.trim_start_matches("0x")).unwrap())
+    }
+
+    #[tokio::test]
+    async fn submits_detection_and_marks_predator() {
+        let port: u16 = std::env::var("ANVIL_PORT")
+            .ok()
+            .and_then(|p| p.parse().ok())
+            .unwrap_or(8546);
+        let _node = spawn_anvil(port);
+        let url = format!("ws://127.0.0.1:{}", port);
+
+        // Wait for the node to accept connections
+        let mut ready = false;
+        for _ in 0..50 {
+            if check_endpoint(&url).await.is_ok() {
+                ready = true;
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        }
+        assert!(ready, "anvil did not come up on {}", url);
+
+        // Deploy the mocks from the default account
+        let signer = PrivateKeySigner::from_str(ANVIL_KEY).unwrap();
+        let deployer = ProviderBuilder::new()
+            .with_recommended_fillers()
+            .wallet(EthereumWallet::from(signer))
+            .on_ws(WsConnect::new(&url))
+            .await
+            .unwrap();
+        let mut addresses = Vec::new();
+        for contract in ["MockBeeTrapHook", "MockAgentNFT"] {
+            let tx = TransactionRequest::default().with_deploy_code(artifact_bytecode(contract));
+            let receipt = deployer
+                .send_transaction(tx)
+                .await
+                .unwrap()
+                .get_receipt()
+                .await
+                .unwrap();
+            addresses.push(receipt.contract_address.expect("no contract address"));
+        }
+
+        let mut config = Config::from_env().unwrap();
+        config.rpc_url = url;
+        config.private_keys = vec![ANVIL_KEY.to_string()];
+        config.hook_address = addresses[0].to_string();
+        config.agent_nft_address = addresses[1].to_string();
+        config.submission_ledger_path = None;
+        let client = build_client(&config).await.unwrap();
+
+        let bot = Address::repeat_byte(0xbe);
+        assert!(!client.is_predator(bot).await.unwrap());
+
+        let receipt = client
+            .submit_detection(bot, TrapAction::Trap, vec![0xab; 64], vec![U256::from(1)])
+            .await
+            .unwrap();
+        assert!(receipt.gas_used > 0);
+        assert!(receipt.cost_wei() > 0);
+        assert!(client.is_predator(bot).await.unwrap());
+
+        // tokenURI round-trips through the policy parser
+        let uri = client.agent_token_uri().await.unwrap();
+        let policy = crate::policy::parse_token_uri(&uri).unwrap().unwrap();
+        assert_eq!(policy.confidence_threshold, Some(0.9));
+    }
+}
+
+/// Consecutive failed execution polls, and whether they add up to an outage
+#[derive(Debug)]
+pub struct ExecutionHealth {
+    /// Failures in a row before degrading (0 = never)
+    degrade_after: u32,
+    failures: u32,
+    degraded: bool,
+}
+
+impl ExecutionHealth {
+    pub fn new(degrade_after: u32) -> Self {
+        Self {
+            degrade_after,
+            failures: 0,
+            degraded: false,
+        }
+    }
+
+    /// Record a poll outcome; returns the new state when it changes. One
+    /// success recovers.
+    pub fn observe(&mut self, ok: bool) -> Option<bool> {
+        self.failures = if ok { 0 } else { self.failures + 1 };
+        let degraded = self.degrade_after > 0 && self.failures >= self.degrade_after;
+        (degraded != self.degraded).then(|| {
+            self.degraded = degraded;
+            degraded
+        })
+    }
+}
+
+/// Reject a proof whose calldata would blow the gas limit and revert with
+/// nothing to go on: too many bytes, or too many public inputs (0 = no limit)
+fn check_calldata_bounds(
+    proof_len: usize,
+    public_inputs: usize,
+    max_proof_bytes: usize,
+    max_public_inputs: usize,
+) -> Result<(), SentinelError> {
+    if proof_len > max_proof_bytes {
+        return Err(SentinelError::ProofTooLarge {
+            size: proof_len,
+            max: max_proof_bytes,
+        });
+    }
+    if max_public_inputs > 0 && public_inputs > max_public_inputs {
+        return Err(SentinelError::TooManyPublicInputs {
+            count: public_inputs,
+            max: max_public_inputs,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{mock_provider, MockNode};
+
+    #[test]
+    fn degrades_after_consecutive_failures_and_recovers_at_once() {
+        let mut health = ExecutionHealth::new(3);
+        assert_eq!(health.observe(false), None);
+        assert_eq!(health.observe(false), None);
+        // A success in between starts the count over
+        assert_eq!(health.observe(true), None);
+        assert_eq!(health.observe(false), None);
+        assert_eq!(health.observe(false), None);
+        assert_eq!(health.observe(false), Some(true));
+        assert_eq!(health.observe(false), None);
+        assert_eq!(health.observe(true), Some(false));
+
+        let mut never = ExecutionHealth::new(0);
+        assert!((0..10).all(|_| never.observe(false).is_none()));
+    }
+
+    #[test]
+    fn rejects_oversized_calldata_before_sending() {
+        assert!(check_calldata_bounds(1_000, 8, 100_000, 256).is_ok());
+        assert!(matches!(
+            check_calldata_bounds(200_000, 8, 100_000, 256),
+            Err(SentinelError::ProofTooLarge { size: 200_000, .. })
+        ));
+        let err = check_calldata_bounds(1_000, 10_000, 100_000, 256).unwrap_err();
+        assert_eq!(err.kind(), "too_many_public_inputs");
+        assert!(err.to_string().contains("10000 public inputs"));
+        // 0 = no limit
+        assert!(check_calldata_bounds(1_000, 10_000, 100_000, 0).is_ok());
+    }
+
+    #[tokio::test]
+    async fn clients_need_a_signer() {
+        let node = mock_provider(MockNode(|_| None)).await;
+        let client = SentinelClient::new(
+            node,
+            Address::ZERO,
+            Address::ZERO,
+            U256::from(1),
+            Vec::new(),
+            usize::MAX,
+            usize::MAX,
+            crate::ledger::SubmissionLedger::load(None).unwrap(),
+        );
+        assert!(client.is_err());
+    }
 }
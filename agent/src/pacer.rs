@@ -0,0 +1,158 @@
+//! # Submission Pacing
+//!
+//! A burst of detections turns into a burst of `submit_detection` calls, and
+//! execution providers rate-limit those. With `SUBMIT_MIN_INTERVAL_MS` set,
+//! submissions take a token from a bucket refilled at one token per
+//! interval (up to `SUBMIT_BURST`). Submissions without a token wait their
+//! turn in arrival order; once `SUBMIT_QUEUE_MAX` are waiting, further ones
+//! are dropped instead of queueing without bound.
+
+use crate::error::SentinelError;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+/// Tokens refilled continuously at one per `interval`, capped at `burst`
+#[derive(Debug)]
+struct TokenBucket {
+    interval: Duration,
+    burst: f64,
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl TokenBucket {
+    fn new(interval: Duration, burst: u32, now: Instant) -> Self {
+        let burst = burst.max(1) as f64;
+        Self {
+            interval,
+            burst,
+            tokens: burst,
+            refilled_at: now,
+        }
+    }
+
+    /// Take a token at `now`, or return how long until one is available
+    fn take(&mut self, now: Instant) -> Result<(), Duration> {
+        let elapsed = now.saturating_duration_since(self.refilled_at);
+        self.tokens =
+            (self.tokens + elapsed.as_secs_f64() / self.interval.as_secs_f64()).min(self.burst);
+        self.refilled_at = now;
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(self.interval.mul_f64(1.0 - self.tokens))
+        }
+    }
+}
+
+/// Paces on-chain submissions with a bounded wait queue
+#[derive(Debug)]
+pub struct SubmissionPacer {
+    /// Held while waiting for a token, so waiters are served in order
+    bucket: Mutex<TokenBucket>,
+    max_queued: usize,
+    queued: AtomicUsize,
+    dropped: AtomicU64,
+}
+
+impl SubmissionPacer {
+    pub fn new(min_interval: Duration, burst: u32, max_queued: usize) -> Self {
+        Self {
+            bucket: Mutex::new(TokenBucket::new(min_interval, burst, Instant::now())),
+            max_queued: max_queued.max(1),
+            queued: AtomicUsize::new(0),
+            dropped: AtomicU64::new(0),
+        }
+    }
+
+    /// Submissions waiting for (or holding) the bucket
+    pub fn queued(&self) -> usize {
+        self.queued.load(Ordering::Relaxed)
+    }
+
+    /// Submissions dropped because the queue was full
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Wait for a submission slot, or fail at once when the queue is full
+    pub async fn acquire(&self) -> Result<(), SentinelError> {
+        let queued = self.queued.fetch_add(1, Ordering::Relaxed);
+        if queued >= self.max_queued {
+            self.queued.fetch_sub(1, Ordering::Relaxed);
+            let dropped = self.dropped.fetch_add(1, Ordering::Relaxed) + 1;
+            warn!(
+                "Submission queue full ({} waiting); dropped ({} so far)",
+                queued, dropped
+            );
+            return Err(SentinelError::SubmissionQueueFull { queued });
+        }
+        let mut bucket = self.bucket.lock().await;
+        while let Err(wait) = bucket.take(Instant::now()) {
+            info!(
+                "Submission paced: waiting {} ms ({} queued)",
+                wait.as_millis(),
+                self.queued()
+            );
+            tokio::time::sleep(wait).await;
+        }
+        drop(bucket);
+        self.queued.fetch_sub(1, Ordering::Relaxed);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bucket_allows_a_burst_then_paces() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(Duration::from_millis(100), 2, start);
+        assert_eq!(bucket.take(start), Ok(()));
+        assert_eq!(bucket.take(start), Ok(()));
+        assert_eq!(bucket.take(start), Err(Duration::from_millis(100)));
+
+        // Partly refilled: wait for the rest of the interval
+        let later = start + Duration::from_millis(40);
+        let wait = bucket.take(later).unwrap_err();
+        assert!(wait.abs_diff(Duration::from_millis(60)) < Duration::from_millis(1));
+        assert_eq!(bucket.take(start + Duration::from_millis(100)), Ok(()));
+
+        // Idle time never banks more than the burst
+        let idle = start + Duration::from_secs(10);
+        assert_eq!(bucket.take(idle), Ok(()));
+        assert_eq!(bucket.take(idle), Ok(()));
+        assert!(bucket.take(idle).is_err());
+    }
+
+    #[tokio::test]
+    async fn full_queue_drops_submissions() {
+        let pacer = std::sync::Arc::new(SubmissionPacer::new(Duration::from_millis(20), 1, 2));
+        pacer.acquire().await.unwrap();
+
+        // Two wait for tokens; the third finds the queue full
+        let waiting: Vec<_> = (0..2)
+            .map(|_| {
+                let pacer = pacer.clone();
+                tokio::spawn(async move { pacer.acquire().await })
+            })
+            .collect();
+        tokio::task::yield_now().await;
+        assert_eq!(pacer.queued(), 2);
+        assert!(matches!(
+            pacer.acquire().await,
+            Err(SentinelError::SubmissionQueueFull { queued: 2 })
+        ));
+        assert_eq!(pacer.dropped(), 1);
+
+        for handle in waiting {
+            handle.await.unwrap().unwrap();
+        }
+        assert_eq!(pacer.queued(), 0);
+    }
+}
@@ -0,0 +1,170 @@
+//! # On-Chain Agent Policy
+//!
+//! Reads the detection policy (confidence threshold, model path, expected
+//! model hash) from the AgentNFT `tokenURI` metadata so the deployed NFT is
+//! authoritative over env configuration.
+
+use crate::types::Config;
+use base64::Engine;
+use eyre::{Result, WrapErr};
+use serde_json::Value;
+
+/// Policy fields published in the agent NFT metadata. Absent fields keep
+/// their env-configured value.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OnchainPolicy {
+    pub confidence_threshold: Option<f32>,
+    pub model_path: Option<String>,
+    /// Keccak-256 of the ONNX model the agent must be running
+    pub model_hash: Option<[u8; 32]>,
+}
+
+impl OnchainPolicy {
+    /// True when the metadata carried no policy fields at all
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Override env configuration with the fields present on-chain
+    pub fn apply(&self, config: &mut Config) {
+        if let Some(threshold) = self.confidence_threshold {
+            config.confidence_threshold = threshold;
+        }
+        if let Some(path) = &self.model_path {
+            config.model_path = path.clone();
+        }
+        if let Some(hash) = self.model_hash {
+            config.expected_model_hash = Some(hash);
+        }
+    }
+}
+
+/// Parse policy from a `tokenURI`.
+///
+/// Only inline `data:application/json` URIs (plain or base64) are read;
+/// remote URIs return `Ok(None)` so the caller falls back to env config.
+pub fn parse_token_uri(uri: &str) -> Result<Option<OnchainPolicy>> {
+    let Some(rest) = uri.strip_prefix("data:application/json") else {
+        return Ok(None);
+    };
+
+    let json = if let Some(encoded) = rest.strip_prefix(";base64,") {
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(encoded.trim())
+            .wrap_err("Invalid base64 in tokenURI")?;
+        String::from_utf8(bytes).wrap_err("tokenURI metadata is not UTF-8")?
+    } else if let Some((_, raw)) = rest.split_once(',') {
+        raw.to_string()
+    } else {
+        return Err(eyre::eyre!("Malformed data URI in tokenURI"));
+    };
+
+    let metadata: Value = serde_json::from_str(&json).wrap_err("tokenURI metadata is not JSON")?;
+    let policy = parse_metadata(&metadata)?;
+    Ok((!policy.is_empty()).then_some(policy))
+}
+
+/// Look up policy keys at the top level, under `properties`, or in
+/// OpenSea-style `attributes: [{ trait_type, value }]`.
+fn parse_metadata(metadata: &Value) -> Result<OnchainPolicy> {
+    let lookup = |key: &str| -> Option<Value> {
+        if let Some(v) = metadata.get(key) {
+            return Some(v.clone());
+        }
+        if let Some(v) = metadata.get("properties").and_then(|p| p.get(key)) {
+            return Some(v.clone());
+        }
+        metadata
+            .get("attributes")?
+            .as_array()?
+            .iter()
+            .find(|a| a.get("trait_type").and_then(Value::as_str) == Some(key))
+            .and_then(|a| a.get("value").cloned())
+    };
+
+    let confidence_threshold = match lookup("confidence_threshold") {
+        Some(v) => {
+            let threshold = v
+                .as_f64()
+                .or_else(|| v.as_str().and_then(|s| s.parse().ok()))
+                .ok_or_else(|| eyre::eyre!("confidence_threshold is not a number"))?;
+            if !(0.0..=1.0).contains(&threshold) {
+                return Err(eyre::eyre!(
+                    "confidence_threshold {} out of range 0.0 - 1.0",
+                    threshold
+                ));
+            }
+            Some(threshold as f32)
+        }
+        None => None,
+    };
+
+    let model_path = lookup("model_path").and_then(|v| v.as_str().map(str::to_string));
+
+    let model_hash = match lookup("model_hash") {
+        Some(v) => {
+            let s = v
+                .as_str()
+                .ok_or_else(|| eyre::eyre!("model_hash is not a string"))?;
+            let bytes = hex::decode(s.trim_start_matches("0x")).wrap_err("Invalid model_hash")?;
+            let hash: [u8; 32] = bytes
+                .try_into()
+                .map_err(|_| eyre::eyre!("model_hash must be 32 bytes"))?;
+            Some(hash)
+        }
+        None => None,
+    };
+
+    Ok(OnchainPolicy {
+        confidence_threshold,
+        model_path,
+        model_hash,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HASH: &str = "0x1111111111111111111111111111111111111111111111111111111111111111";
+
+    #[test]
+    fn parses_plain_json_data_uri() {
+        let uri = format!(
+            r#"data:application/json,{{"name":"BeeTrap Agent","confidence_threshold":0.9,"model_hash":"{}"}}"#,
+            HASH
+        );
+        let policy = parse_token_uri(&uri).unwrap().unwrap();
+
+        assert_eq!(policy.confidence_threshold, Some(0.9));
+        assert_eq!(policy.model_hash, Some([0x11; 32]));
+        assert_eq!(policy.model_path, None);
+    }
+
+    #[test]
+    fn parses_base64_attributes() {
+        let json = r#"{"attributes":[{"trait_type":"confidence_threshold","value":"0.97"},{"trait_type":"model_path","value":"assets/v2.onnx"}]}"#;
+        let uri = format!(
+            "data:application/json;base64,{}",
+            base64::engine::general_purpose::STANDARD.encode(json)
+        );
+        let policy = parse_token_uri(&uri).unwrap().unwrap();
+
+        assert_eq!(policy.confidence_threshold, Some(0.97));
+        assert_eq!(policy.model_path.as_deref(), Some("assets/v2.onnx"));
+    }
+
+    #[test]
+    fn remote_or_policy_free_metadata_falls_back() {
+        assert_eq!(parse_token_uri("ipfs://bafy.../agent.json").unwrap(), None);
+        assert_eq!(
+            parse_token_uri(r#"data:application/json,{"name":"BeeTrap Agent"}"#).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn rejects_out_of_range_threshold() {
+        assert!(parse_token_uri(r#"data:application/json,{"confidence_threshold":1.5}"#).is_err());
+    }
+}
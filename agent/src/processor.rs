@@ -1,16 +1,38 @@
-use crate::types::{FeatureVector, PendingTransaction, ProcessingStage, SentinelStats, UiMessage};
-use alloy::primitives::{Address, U256};
-use alloy::providers::{Provider, ProviderBuilder, WsConnect};
+use crate::audit::AuditEntry;
+use crate::clusters::AddressClusters;
+use crate::dataset::{Recorder, Sample};
+use crate::detections::DetectionFifo;
+use crate::error::SentinelError;
+use crate::features::{ChainContext, FeatureExtractor};
+use crate::history::SenderHistory;
+use crate::lists::{ListKind, SharedLists};
+use crate::prover::ProverPool;
+use crate::router::ApprovalTracker;
+use crate::schedule::TrapSchedule;
+use crate::types::{
+    AddressScore, Config, DecisionModifier, DecisionTrace, Detection, DetectionReason,
+    EfficiencyBasis, FeatureContribution, FeatureVector, InferenceProvider, ModelTask,
+    PendingTransaction, ProcessingStage, PublicInputLayout, Reorg, RpcCall, SentinelStats,
+    SkipReason, TrapAction, TrapCost, TxTarget, UiMessage, UiSender,
+};
+use alloy::primitives::{Address, TxHash, U256};
+use alloy::providers::{Provider, ProviderBuilder, RootProvider, WsConnect};
 use alloy::pubsub::PubSubFrontend;
 use alloy::rpc::types::TransactionRequest;
+use alloy::transports::{RpcError, TransportErrorKind};
 
 use eyre::{Result, WrapErr};
 use ndarray::Array2;
+use ort::ep::ExecutionProviderDispatch;
 use ort::session::{builder::GraphOptimizationLevel, Session};
+use ort::value::{DynValue, ValueType};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::process::Command;
 use std::str::FromStr;
-use tokio::sync::{mpsc::UnboundedSender, Mutex};
-use tracing::{error, info, instrument};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc::UnboundedReceiver, watch, Mutex, Semaphore};
+use tracing::{error, info, info_span, instrument, Instrument};
 
 // ═══════════════════════════════════════════════════════════════════════════
 //                          CONSTANTS (NORMALIZATION)
@@ -38,18 +60,762 @@ const SCALES: [f32; 6] = [
     85.2014871109067,    // tx_index
 ];
 
+/// ONNX custom metadata keys a training export sets to the scaler it
+/// used: six comma-separated values in the `MEANS`/`SCALES` order
+const SCALER_MEANS_KEY: &str = "feature_means";
+const SCALER_SCALES_KEY: &str = "feature_scales";
+
+/// Detections remembered for reorg follow-up records
+const REORG_JOURNAL_SIZE: usize = 256;
+
+/// How long an approval of a monitored contract counts as bot setup
+const APPROVAL_WINDOW_SECS: u64 = 120;
+
+/// Threshold reduction for swaps from a sender that just approved the router
+/// or PoolManager. The model score itself is untouched, so proofs stay valid.
+const APPROVAL_THRESHOLD_DISCOUNT: f32 = 0.05;
+
+/// Threshold reduction for a transaction that replaces an earlier broadcast
+/// of the same nonce with a higher bid, a classic bot gas-war move
+const ESCALATION_THRESHOLD_DISCOUNT: f32 = 0.05;
+
+/// Threshold reduction for a held detection whose sender sent more
+/// transactions during the hold, e.g. the back leg of a sandwich
+const COMPANION_THRESHOLD_DISCOUNT: f32 = 0.05;
+
+/// Threshold reduction for a swap whose sender shares a funding cluster with
+/// another swap in the same block (a possible self-sandwich)
+const CLUSTER_THRESHOLD_DISCOUNT: f32 = 0.05;
+
+/// How long transactions are remembered for held detections (hold is capped at 2s)
+const ACTIVITY_RETENTION: Duration = Duration::from_secs(5);
+
+/// Extra inference attempts before a transaction is reported as unscored
+const INFERENCE_RETRIES: u32 = 1;
+
+/// Base delay between EZKL stage retries; grows linearly per attempt
+const EZKL_RETRY_DELAY_MS: u64 = 500;
+
+/// Feature names in model input order (matches `MEANS`/`SCALES`)
+pub const FEATURE_NAMES: [&str; 6] = [
+    "gas_price_gwei",
+    "priority_fee_gwei",
+    "gas_usage_ratio",
+    "gas_used",
+    "native_value",
+    "tx_index",
+];
+
+/// Off-schedule detections held for the next window before further ones drop
+const MAX_HELD_DETECTIONS: usize = 256;
+
+/// How often the processing loop proves it is alive to the watchdog
+const WATCHDOG_BEAT: Duration = Duration::from_secs(1);
+
+/// Pause before a failed processing loop is respawned, so a loop that
+/// fails on every start does not spin
+const WATCHDOG_RESTART_DELAY: Duration = Duration::from_secs(1);
+
+/// Normalized inputs kept for on-demand explanations (matches the UI's 100-row table)
+const FEATURE_CACHE_SIZE: usize = 100;
+
+// ═══════════════════════════════════════════════════════════════════════════
+//                          PROCESSOR SETTINGS
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// Detection tunables and model metadata resolved at startup
+#[derive(Debug, Clone, Default)]
+pub struct ProcessorSettings {
+    /// Confidence threshold for detection (0.0 - 1.0)
+    pub confidence_threshold: f32,
+    /// Reset per-block detection counters whenever a new block is seen
+    pub reset_on_new_block: bool,
+    /// Append-only audit log of submitted traps
+    pub audit_log_path: Option<String>,
+    /// Refuse to start if the model does not hash to this value
+    pub expected_model_hash: Option<[u8; 32]>,
+    /// `is_predator` checks after a successful submission before warning
+    pub post_verify_attempts: u32,
+    /// Delay between post-verification checks
+    pub post_verify_delay_ms: u64,
+    /// Skip proof generation for detections below this value (wei)
+    pub min_trap_value_wei: u128,
+    /// `FeatureExtractor` name, resolved by `spawn_processor`
+    pub feature_extractor: String,
+    /// Per-reason overrides of `confidence_threshold`
+    pub reason_thresholds: HashMap<DetectionReason, f32>,
+    /// Savings multiplier per reason; unlisted reasons count at 1.0
+    pub reason_impact_weights: HashMap<DetectionReason, f64>,
+    /// Candidate model scored alongside the primary (never traps)
+    pub shadow_model_path: Option<String>,
+    /// Fraction of transactions (0.0 - 1.0) also scored by the shadow model
+    pub shadow_sample_rate: f32,
+    /// Order of witness values in the verifier's public-input array
+    pub public_input_layout: PublicInputLayout,
+    /// Public-input count the deployed verifier expects, if known
+    pub expected_public_inputs: Option<usize>,
+    /// JSON Lines stream of every detection
+    pub detections_jsonl: Option<String>,
+    /// Rotate the detections stream at this size (0 = never)
+    pub detections_jsonl_max_bytes: u64,
+    /// Named pipe for detection records (`FIFO_PATH`)
+    pub fifo_path: Option<String>,
+    /// Attach the normalized features to each detection
+    pub detection_features: bool,
+    /// Denominator of the headline efficiency figure
+    pub efficiency_basis: EfficiencyBasis,
+    /// Mempool chain RPC, for sender history and mined checks
+    pub mempool_rpc_url: String,
+    /// Look up detected senders' transaction counts
+    pub sender_history: bool,
+    /// Skip proving detections whose transaction already has a receipt
+    pub skip_mined: bool,
+    /// Skip proving when the signer balance is below this (wei)
+    pub min_wallet_balance_wei: u128,
+    /// Model output holding class probabilities (None = auto-detect)
+    pub probability_output_index: Option<usize>,
+    /// Class whose probability is the predator score
+    pub probability_class_index: usize,
+    /// Extra attempts per EZKL stage after a transient failure
+    pub ezkl_retries: u32,
+    /// Log every decision trace and send it to the UI
+    pub decision_trace: bool,
+    /// Transactions scanned after startup before detections are trapped
+    pub warmup_txs: u64,
+    /// Deadline from mempool receipt to submission, in ms (0 = none)
+    pub pipeline_deadline_ms: u64,
+    /// Windows in which detections are trapped (None = always)
+    pub trap_schedule: Option<TrapSchedule>,
+    /// Hold off-schedule detections until the next window instead of dropping
+    pub trap_schedule_flush: bool,
+    /// Dedicated threads for EZKL proof generation
+    pub prover_threads: usize,
+    /// Weight of the newest score in each sender's moving average (0 = off)
+    pub score_ema_alpha: f32,
+    /// Senders whose moving average is remembered
+    pub score_ema_max_addresses: usize,
+    /// Count plain ETH transfers without estimating gas or scoring them
+    pub skip_plain_transfers: bool,
+    /// EIP-1559 txs tipping below this are marked safe unscored (0 = off)
+    pub min_priority_fee_wei: u128,
+    /// Calldata size above which a tx is not scored (0 = no limit)
+    pub max_input_bytes: usize,
+    /// Normalized features are clamped to ±this many σ (0 = off)
+    pub feature_clamp_sigma: f32,
+    /// Wait before a detection is re-evaluated and trapped (zero = off)
+    pub trap_hold: Duration,
+    /// Funding links cluster addresses for this long (zero = off)
+    pub cluster_window: Duration,
+    /// Funding links kept for clustering; the oldest go first
+    pub cluster_max_links: usize,
+    /// Whether scores are probabilities or expected profits
+    pub model_task: ModelTask,
+    /// Expected profit that traps in regression mode (native currency)
+    pub profit_threshold_eth: f32,
+    /// Execution provider sessions are built with
+    pub inference_provider: InferenceProvider,
+    /// Score at which a detection traps rather than flags (0 = always trap)
+    pub trap_score: f32,
+    /// Restart the processing loop after this long without a heartbeat (zero = off)
+    pub processor_watchdog: Duration,
+    /// Retraining dataset every scored transaction is appended to
+    pub dataset_csv: Option<String>,
+    /// Priority fee at which the rule baseline flags a swap (0 = off)
+    pub baseline_priority_fee_wei: u128,
+    /// Scored transactions per baseline comparison window
+    pub baseline_window_txs: u64,
+    /// Rule flags in an all-safe window that warn of model degradation
+    pub baseline_min_flags: u64,
+    /// Copy each successful proof's artifacts to `latest.*` in the prove dir
+    pub publish_latest_proof: bool,
+}
+
+impl From<&Config> for ProcessorSettings {
+    fn from(config: &Config) -> Self {
+        Self {
+            confidence_threshold: config.confidence_threshold,
+            reset_on_new_block: config.reset_on_new_block,
+            audit_log_path: config.audit_log_path.clone(),
+            expected_model_hash: config.expected_model_hash,
+            post_verify_attempts: config.post_verify_attempts,
+            post_verify_delay_ms: config.post_verify_delay_ms,
+            min_trap_value_wei: config.min_trap_value_wei,
+            feature_extractor: config.feature_extractor.clone(),
+            reason_thresholds: config.reason_thresholds.clone(),
+            reason_impact_weights: config.reason_impact_weights.clone(),
+            shadow_model_path: config.shadow_model_path.clone(),
+            shadow_sample_rate: config.shadow_sample_rate,
+            public_input_layout: config.public_input_layout,
+            expected_public_inputs: config.expected_public_inputs,
+            detections_jsonl: config.detections_jsonl.clone(),
+            detections_jsonl_max_bytes: config.detections_jsonl_max_bytes,
+            fifo_path: config.fifo_path.clone(),
+            detection_features: config.detection_features,
+            efficiency_basis: config.efficiency_basis,
+            mempool_rpc_url: config.rpc_url.clone(),
+            sender_history: config.detection_sender_history,
+            skip_mined: config.skip_mined,
+            min_wallet_balance_wei: config.min_wallet_balance_wei,
+            probability_output_index: config.probability_output_index,
+            probability_class_index: config.probability_class_index,
+            ezkl_retries: config.ezkl_retries,
+            decision_trace: config.decision_trace,
+            warmup_txs: config.warmup_txs,
+            pipeline_deadline_ms: config.pipeline_deadline_ms,
+            trap_schedule: config.trap_schedule.clone(),
+            trap_schedule_flush: config.trap_schedule_flush,
+            prover_threads: config.prover_threads,
+            score_ema_alpha: config.score_ema_alpha,
+            score_ema_max_addresses: config.score_ema_max_addresses,
+            skip_plain_transfers: config.skip_plain_transfers,
+            min_priority_fee_wei: (config.min_priority_fee_gwei.max(0.0) * 1e9) as u128,
+            max_input_bytes: config.max_input_bytes,
+            feature_clamp_sigma: config.feature_clamp_sigma,
+            trap_hold: Duration::from_millis(config.trap_hold_ms),
+            cluster_window: Duration::from_secs(config.cluster_window_secs),
+            cluster_max_links: config.cluster_max_links,
+            model_task: config.model_task,
+            profit_threshold_eth: config.profit_threshold_eth,
+            inference_provider: config.inference_provider,
+            trap_score: config.trap_score,
+            processor_watchdog: Duration::from_secs(config.processor_watchdog_secs),
+            dataset_csv: config.dataset_csv.clone(),
+            baseline_priority_fee_wei: (config.baseline_priority_fee_gwei.max(0.0) * 1e9) as u128,
+            baseline_window_txs: config.baseline_window_txs,
+            baseline_min_flags: config.baseline_min_flags,
+            publish_latest_proof: config.publish_latest_proof,
+        }
+    }
+}
+
+impl ProcessorSettings {
+    /// Global threshold in the model task's unit
+    pub fn base_threshold(&self) -> f32 {
+        match self.model_task {
+            ModelTask::Classification => self.confidence_threshold,
+            ModelTask::Regression => self.profit_threshold_eth,
+        }
+    }
+
+    /// Threshold for `reason`, falling back to the global value
+    pub fn threshold_for(&self, reason: DetectionReason) -> f32 {
+        self.reason_thresholds
+            .get(&reason)
+            .copied()
+            .unwrap_or(self.base_threshold())
+    }
+
+    /// Multiplier on the savings credited to a trap for `reason`
+    pub fn impact_weight(&self, reason: DetectionReason) -> f64 {
+        self.reason_impact_weights
+            .get(&reason)
+            .copied()
+            .unwrap_or(1.0)
+    }
+
+    /// Lower `threshold` by a context discount: absolute on probabilities,
+    /// relative on profit estimates, whose scale depends on the market
+    fn discounted(&self, threshold: f32, discount: f32) -> f32 {
+        match self.model_task {
+            ModelTask::Classification => (threshold - discount).max(0.0),
+            ModelTask::Regression => threshold * (1.0 - discount),
+        }
+    }
+
+    /// Resolve the threshold for `reason` and compare `score` (or the
+    /// sender's moving average, when smoothing is on) against it, recording
+    /// which modifier had the final say
+    fn decide(
+        &self,
+        score: f32,
+        smoothed: Option<f32>,
+        reason: DetectionReason,
+        recent_approval: bool,
+        escalated: bool,
+    ) -> DecisionTrace {
+        let reason_threshold = self.threshold_for(reason);
+        let (threshold, decided_by) = if recent_approval {
+            (
+                self.discounted(reason_threshold, APPROVAL_THRESHOLD_DISCOUNT),
+                DecisionModifier::ApprovalContext,
+            )
+        } else if escalated {
+            (
+                self.discounted(reason_threshold, ESCALATION_THRESHOLD_DISCOUNT),
+                DecisionModifier::GasEscalation,
+            )
+        } else if self.reason_thresholds.contains_key(&reason) {
+            (reason_threshold, DecisionModifier::ReasonThreshold)
+        } else {
+            (reason_threshold, DecisionModifier::GlobalThreshold)
+        };
+        DecisionTrace {
+            score,
+            smoothed,
+            reason,
+            base_threshold: self.base_threshold(),
+            threshold,
+            decided_by,
+            trap: smoothed.unwrap_or(score) >= threshold,
+        }
+    }
+
+    /// Re-decide a held detection: the sender's average may have moved, and
+    /// companion transactions seen during the hold lower the bar
+    fn after_hold(
+        &self,
+        mut trace: DecisionTrace,
+        smoothed: Option<f32>,
+        companions: usize,
+    ) -> DecisionTrace {
+        if smoothed.is_some() {
+            trace.smoothed = smoothed;
+        }
+        if companions > 0
+            && matches!(
+                trace.decided_by,
+                DecisionModifier::GlobalThreshold | DecisionModifier::ReasonThreshold
+            )
+        {
+            trace.threshold = self.discounted(trace.threshold, COMPANION_THRESHOLD_DISCOUNT);
+            trace.decided_by = DecisionModifier::Companions;
+        }
+        trace.trap = trace.smoothed.unwrap_or(trace.score) >= trace.threshold;
+        trace
+    }
+
+    /// Lower the bar for a swap coordinated within its sender's cluster,
+    /// unless a stronger modifier already decided
+    fn coordinated(&self, mut trace: DecisionTrace) -> DecisionTrace {
+        if matches!(
+            trace.decided_by,
+            DecisionModifier::GlobalThreshold | DecisionModifier::ReasonThreshold
+        ) {
+            trace.threshold = self.discounted(trace.threshold, CLUSTER_THRESHOLD_DISCOUNT);
+            trace.decided_by = DecisionModifier::Cluster;
+            trace.trap = trace.smoothed.unwrap_or(trace.score) >= trace.threshold;
+        }
+        trace
+    }
+
+    /// On-chain action for a detection: below `trap_score` it is only
+    /// flagged. Denylisted senders always trap.
+    fn trap_action(&self, trace: &DecisionTrace) -> TrapAction {
+        let score = trace.smoothed.unwrap_or(trace.score);
+        if trace.decided_by == DecisionModifier::Denylist || score >= self.trap_score {
+            TrapAction::Trap
+        } else {
+            TrapAction::Flag
+        }
+    }
+
+    /// Why `tx` is left unscored, before any RPC call or inference
+    fn prefilter(&self, tx: &PendingTransaction) -> Option<SkipReason> {
+        if tx.is_blob() {
+            Some(SkipReason::BlobTransaction)
+        } else if tx.input_exceeds(self.max_input_bytes) {
+            Some(SkipReason::OversizedInput)
+        } else if self.skip_plain_transfers && tx.is_plain_transfer() {
+            Some(SkipReason::PlainTransfer)
+        } else if self.min_priority_fee_wei > 0 && tx.tips_below(self.min_priority_fee_wei) {
+            Some(SkipReason::LowPriorityFee)
+        } else {
+            None
+        }
+    }
+
+    /// Time since `received_at`, if it is past the pipeline deadline
+    fn overdue(&self, received_at: Instant) -> Option<Duration> {
+        let elapsed = received_at.elapsed();
+        (self.pipeline_deadline_ms > 0
+            && elapsed > Duration::from_millis(self.pipeline_deadline_ms))
+        .then_some(elapsed)
+    }
+
+    /// Log `trace` and forward it to the UI when tracing is enabled
+    fn emit_trace(&self, ui_sender: &UiSender, tx_hash: &str, trace: DecisionTrace) {
+        if self.decision_trace {
+            info!("Decision {}: {}", tx_hash, trace);
+            let _ = ui_sender.send(UiMessage::DecisionTrace(tx_hash.to_string(), trace));
+        } else {
+            tracing::debug!("Decision {}: {}", tx_hash, trace);
+        }
+    }
+}
+
+/// Recent normalized feature vectors by tx hash, oldest evicted first
+#[derive(Debug, Default)]
+struct FeatureCache {
+    features: HashMap<String, [f32; 6]>,
+    order: VecDeque<String>,
+}
+
+impl FeatureCache {
+    fn insert(&mut self, hash: String, features: [f32; 6]) {
+        if self.features.insert(hash.clone(), features).is_none() {
+            self.order.push_back(hash);
+        }
+        while self.order.len() > FEATURE_CACHE_SIZE {
+            if let Some(oldest) = self.order.pop_front() {
+                self.features.remove(&oldest);
+            }
+        }
+    }
+
+    fn get(&self, hash: &str) -> Option<[f32; 6]> {
+        self.features.get(hash).copied()
+    }
+}
+
+/// Moving average of predator scores per sender, oldest sender evicted first
+#[derive(Debug)]
+struct AddressScores {
+    alpha: f32,
+    capacity: usize,
+    scores: HashMap<String, AddressScore>,
+    order: VecDeque<String>,
+}
+
+impl AddressScores {
+    fn new(alpha: f32, capacity: usize) -> Self {
+        Self {
+            alpha,
+            capacity: capacity.max(1),
+            scores: HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    fn get(&self, address: &str) -> Option<AddressScore> {
+        self.scores.get(address).copied()
+    }
+
+    /// Fold `score` into `address`'s average. Averages start at zero, so a
+    /// sender has to score high repeatedly before it crosses the threshold.
+    fn update(&mut self, address: &str, score: f32) -> AddressScore {
+        if !self.scores.contains_key(address) {
+            while self.order.len() >= self.capacity {
+                if let Some(oldest) = self.order.pop_front() {
+                    self.scores.remove(&oldest);
+                }
+            }
+            self.order.push_back(address.to_string());
+        }
+        let entry = self
+            .scores
+            .entry(address.to_string())
+            .or_insert(AddressScore {
+                smoothed: 0.0,
+                samples: 0,
+            });
+        entry.smoothed += self.alpha * (score - entry.smoothed);
+        entry.samples += 1;
+        *entry
+    }
+}
+
+/// What arrived from a held detection's sender during the hold
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct HoldContext {
+    /// The same nonce was broadcast again; this tx will not be included
+    replaced: bool,
+    /// Transactions with other nonces (further legs of the attack)
+    companions: usize,
+}
+
+/// Senders and nonces of recently seen transactions, for held detections
+#[derive(Debug, Default)]
+struct RecentActivity {
+    seen: VecDeque<(String, u64, Instant)>,
+}
+
+impl RecentActivity {
+    fn record(&mut self, sender: &str, nonce: u64, at: Instant) {
+        while self
+            .seen
+            .front()
+            .is_some_and(|(_, _, t)| at.saturating_duration_since(*t) > ACTIVITY_RETENTION)
+        {
+            self.seen.pop_front();
+        }
+        self.seen.push_back((sender.to_string(), nonce, at));
+    }
+
+    /// Transactions from `sender` seen after `since`
+    fn after(&self, sender: &str, nonce: u64, since: Instant) -> HoldContext {
+        let mut context = HoldContext::default();
+        for (_, n, _) in self
+            .seen
+            .iter()
+            .filter(|(s, _, t)| s == sender && *t > since)
+        {
+            if *n == nonce {
+                context.replaced = true;
+            } else {
+                context.companions += 1;
+            }
+        }
+        context
+    }
+}
+
+/// How a detection relates to others from the same sender in its block
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Coalesce {
+    /// First detection of this sender in the block: trap it
+    Lead,
+    /// More confident than the current leader, whose proof is still running;
+    /// the trap claim passes to this transaction
+    TakeOver,
+    /// A leader at least as confident exists, or it already went to submission
+    Coalesced,
+    /// No leader in this block, and the submission ledger refused the claim:
+    /// another transaction of this sender is in flight or was sent
+    Claimed,
+}
+
+/// The most confident detection per sender in the current block.
+///
+/// A bot sending several transactions in one block should cost one proof
+/// and one trap, carrying its most confident transaction. The leader owns
+/// the sender's submission-ledger claim; a takeover inherits it, and only
+/// the current owner may release it.
+#[derive(Debug, Default)]
+struct BlockCoalescer {
+    /// sender -> (block, leading tx hash, confidence, submitting)
+    leaders: HashMap<String, (u64, String, f32, bool)>,
+    /// Leaders replaced by a takeover; they no longer own the claim
+    superseded: HashSet<String>,
+}
+
+impl BlockCoalescer {
+    /// Decide how `hash` relates to its sender's block. A new leader takes
+    /// the ledger claim through `claim`, which runs under the same lock so no
+    /// other detection of the sender can slip in between.
+    fn offer(
+        &mut self,
+        sender: &str,
+        block: u64,
+        hash: &str,
+        confidence: f32,
+        claim: impl FnOnce() -> bool,
+    ) -> Coalesce {
+        // Only the current block can coalesce; older leaders are done
+        self.leaders.retain(|_, (b, ..)| *b >= block);
+        let coalesce = match self.leaders.get(sender) {
+            Some((b, _, _, submitting)) if *b == block && *submitting => Coalesce::Coalesced,
+            Some((b, _, best, _)) if *b == block && confidence <= *best => Coalesce::Coalesced,
+            Some((b, leader, ..)) if *b == block => {
+                self.superseded.insert(leader.clone());
+                Coalesce::TakeOver
+            }
+            _ if claim() => Coalesce::Lead,
+            _ => Coalesce::Claimed,
+        };
+        if matches!(coalesce, Coalesce::Lead | Coalesce::TakeOver) {
+            self.leaders.insert(
+                sender.to_string(),
+                (block, hash.to_string(), confidence, false),
+            );
+        }
+        coalesce
+    }
+
+    /// Called with a finished proof: submit only if `hash` still owns the
+    /// claim, and from then on coalesce every later detection into it
+    fn commit(&mut self, sender: &str, block: u64, hash: &str) -> bool {
+        if self.superseded.remove(hash) {
+            return false;
+        }
+        if let Some((b, leader, _, submitting)) = self.leaders.get_mut(sender) {
+            if *b == block && leader == hash {
+                *submitting = true;
+            }
+        }
+        true
+    }
+
+    /// Called when `hash` gives up: true if it owned the claim, which the
+    /// caller must then release. Later detections in the block lead afresh.
+    fn release(&mut self, sender: &str, block: u64, hash: &str) -> bool {
+        if self.superseded.remove(hash) {
+            return false;
+        }
+        if self
+            .leaders
+            .get(sender)
+            .is_some_and(|(b, leader, ..)| *b == block && leader == hash)
+        {
+            self.leaders.remove(sender);
+        }
+        true
+    }
+}
+
+/// Detections outside `TRAP_SCHEDULE` waiting for the next window
+/// (`TRAP_SCHEDULE_FLUSH`)
+struct ScheduleHold {
+    /// One permit per held detection, `MAX_HELD_DETECTIONS` in all
+    slots: Semaphore,
+    /// Re-read when the window opens: the dequeue-time head is long gone
+    block_rx: watch::Receiver<u64>,
+}
+
+/// A window in which the rule baseline flagged repeatedly and the model never did
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Disagreement {
+    scored: u64,
+    rule_flags: u64,
+}
+
+/// Cross-checks the model against a cheap rule (high-tip swap into a router
+/// or the pool manager) over fixed windows of scored transactions. A model
+/// that silently outputs 0 keeps scoring everything safe while the rule
+/// keeps firing.
+#[derive(Debug)]
+struct BaselineMonitor {
+    window: u64,
+    min_flags: u64,
+    scored: u64,
+    rule_flags: u64,
+    model_flags: u64,
+}
+
+impl BaselineMonitor {
+    fn new(window: u64, min_flags: u64) -> Self {
+        Self {
+            window: window.max(1),
+            min_flags: min_flags.max(1),
+            scored: 0,
+            rule_flags: 0,
+            model_flags: 0,
+        }
+    }
+
+    /// Count one scored transaction; at the end of each window, report it if
+    /// the model flagged nothing while the rule flagged at least `min_flags`
+    fn observe(&mut self, rule_flag: bool, model_flag: bool) -> Option<Disagreement> {
+        self.scored += 1;
+        self.rule_flags += rule_flag as u64;
+        self.model_flags += model_flag as u64;
+        if self.scored < self.window {
+            return None;
+        }
+        let disagreement =
+            (self.model_flags == 0 && self.rule_flags >= self.min_flags).then_some(Disagreement {
+                scored: self.scored,
+                rule_flags: self.rule_flags,
+            });
+        self.scored = 0;
+        self.rule_flags = 0;
+        self.model_flags = 0;
+        disagreement
+    }
+}
+
+/// Recent detections, with their audit entries once trapped. The detection
+/// stream and the audit log are append-only, so when a reorg replaces the
+/// block a detection was made at, its records are written again with
+/// `reorged` set.
+#[derive(Debug, Default)]
+struct ReorgJournal {
+    entries: VecDeque<(Detection, Option<AuditEntry>)>,
+}
+
+impl ReorgJournal {
+    fn record(&mut self, detection: Detection) {
+        if self.entries.len() == REORG_JOURNAL_SIZE {
+            self.entries.pop_front();
+        }
+        self.entries.push_back((detection, None));
+    }
+
+    /// Keep `entry` with its detection, marked if a reorg already hit it
+    fn audited(&mut self, tx_hash: &str, entry: &mut AuditEntry) {
+        if let Some((detection, audit)) =
+            self.entries.iter_mut().find(|(d, _)| d.tx_hash == tx_hash)
+        {
+            entry.reorged = detection.reorged;
+            *audit = Some(entry.clone());
+        }
+    }
+
+    /// Mark detections made at or after the reorged block; returns the
+    /// newly marked ones for their follow-up records
+    fn flag(&mut self, reorg: Reorg) -> Vec<(Detection, Option<AuditEntry>)> {
+        self.entries
+            .iter_mut()
+            .filter(|(d, _)| !d.reorged && d.block >= reorg.first_block)
+            .map(|(detection, audit)| {
+                detection.reorged = true;
+                if let Some(entry) = audit.as_mut() {
+                    entry.reorged = true;
+                }
+                (detection.clone(), audit.clone())
+            })
+            .collect()
+    }
+}
+
+/// Write follow-up records for detections a reorg replaced
+async fn record_reorged(
+    flagged: Vec<(Detection, Option<AuditEntry>)>,
+    settings: std::sync::Arc<ProcessorSettings>,
+    fifo: Option<DetectionFifo>,
+) {
+    tracing::warn!(
+        "Reorg replaced the block of {} recorded detection(s); writing follow-up records",
+        flagged.len()
+    );
+    if let Some(fifo) = &fifo {
+        for (detection, _) in &flagged {
+            fifo.send(detection);
+        }
+    }
+    match tokio::task::spawn_blocking(move || -> Result<()> {
+        for (detection, audit) in &flagged {
+            if let Some(path) = &settings.detections_jsonl {
+                crate::detections::append(path, settings.detections_jsonl_max_bytes, detection)?;
+            }
+            if let (Some(path), Some(entry)) = (&settings.audit_log_path, audit) {
+                crate::audit::append(path, entry)?;
+            }
+        }
+        Ok(())
+    })
+    .await
+    {
+        Ok(Ok(())) => {}
+        Ok(Err(e)) => error!("Failed to write reorg follow-up records: {:?}", e),
+        Err(e) => error!("Reorg follow-up task panicked: {}", e),
+    }
+}
+
+/// The rule baseline: a swap tipping at least `min_priority_fee`
+fn baseline_flags(tx: &PendingTransaction, min_priority_fee: u128) -> bool {
+    matches!(tx.target, TxTarget::Router | TxTarget::PoolManager)
+        && tx.priority_fee >= min_priority_fee
+}
+
 // ═══════════════════════════════════════════════════════════════════════════
 //                          PROCESSOR LOGIC
 // ═══════════════════════════════════════════════════════════════════════════
 
 /// Spawns the processing loop
+#[allow(clippy::too_many_arguments)]
 pub async fn spawn_processor<P>(
-    mut rx: tokio::sync::mpsc::Receiver<PendingTransaction>, // Bounded Receiver
-    ui_sender: UnboundedSender<UiMessage>,
+    rx: tokio::sync::mpsc::Receiver<PendingTransaction>, // Bounded Receiver
+    ui_sender: UiSender,
     model_path: String,
     rpc_url: String,
-    confidence_threshold: f32,
-    client: std::sync::Arc<crate::network::SentinelClient<P>>,
+    settings: ProcessorSettings,
+    client: Option<std::sync::Arc<crate::network::SentinelClient<P>>>,
+    block_rx: watch::Receiver<u64>,
+    explain_rx: UnboundedReceiver<String>,
+    reload_rx: UnboundedReceiver<()>,
+    reorg_rx: UnboundedReceiver<Reorg>,
+    lists: SharedLists,
+    observed: std::sync::Arc<AtomicU64>,
 ) -> Result<()>
 where
     P: Provider<PubSubFrontend, alloy::network::Ethereum> + Clone + 'static,
@@ -57,74 +823,671 @@ where
     info!("Starting AI Processor...");
 
     // Initialize ONNX Session at startup
-    // let model_path = "assets/network.onnx"; // REMOVED hardcode
-    let session = Session::builder()?
-        .with_optimization_level(GraphOptimizationLevel::Level3)?
-        .with_intra_threads(1)?
-        .commit_from_file(&model_path)
-        .wrap_err_with(|| format!("Failed to load ONNX model from {}", model_path))?;
+    let model = Model::load(&model_path, &settings)?;
+    for (i, output) in model.session.outputs().iter().enumerate() {
+        info!("Model output {}: {} {:?}", i, output.name(), output.dtype());
+    }
+    info!("Model hash 0x{}", hex::encode(model.hash));
+    if settings.expected_model_hash.is_some() {
+        info!("Model hash matches on-chain policy");
+    }
 
     // `ort::Session` requires &mut self for run(), so we need a Mutex.
-    let session = std::sync::Arc::new(Mutex::new(session));
+    // The same lock makes a reload swap atomic for in-flight scoring.
+    let model: SharedScorer = std::sync::Arc::new(Mutex::new(Box::new(model)));
+
+    // Shadow model: scored alongside the primary for comparison, never traps
+    let shadow = match settings.shadow_model_path.as_deref() {
+        Some(path) => {
+            info!(
+                "Shadow model {} on {:.0}% of transactions",
+                path,
+                settings.shadow_sample_rate * 100.0
+            );
+            let shadow = load_session(path, settings.inference_provider)?;
+            let output_types: Vec<ValueType> =
+                shadow.outputs().iter().map(|o| o.dtype().clone()).collect();
+            validate_output_selection(
+                &output_types,
+                settings.probability_output_index,
+                settings.probability_class_index,
+            )?;
+            Some(std::sync::Arc::new(Mutex::new(shadow)))
+        }
+        None => None,
+    };
+
+    let extractor = crate::features::by_name(&settings.feature_extractor)?;
+    info!("Feature extractor: {:?}", extractor);
+    let settings = std::sync::Arc::new(settings);
+    if settings.warmup_txs > 0 {
+        info!(
+            "Warmup: first {} transactions are not trapped",
+            settings.warmup_txs
+        );
+    }
+    let _ = ui_sender.send(UiMessage::WarmupProgress(0, settings.warmup_txs));
 
     // Global Stats Tracker (Thread-Safe)
     let stats = std::sync::Arc::new(Mutex::new(SentinelStats::default()));
+    let feature_cache = std::sync::Arc::new(Mutex::new(FeatureCache::default()));
+    let approvals = std::sync::Arc::new(Mutex::new(ApprovalTracker::new(
+        std::time::Duration::from_secs(APPROVAL_WINDOW_SECS),
+    )));
+    let coalescer = std::sync::Arc::new(Mutex::new(BlockCoalescer::default()));
+    let activity = (!settings.trap_hold.is_zero()).then(|| {
+        info!(
+            "Trap hold: {:?} before each detection is trapped",
+            settings.trap_hold
+        );
+        std::sync::Arc::new(Mutex::new(RecentActivity::default()))
+    });
+    let clusters = (!settings.cluster_window.is_zero()).then(|| {
+        info!(
+            "Address clusters: funding links kept {:?}, up to {}",
+            settings.cluster_window, settings.cluster_max_links
+        );
+        std::sync::Arc::new(Mutex::new(AddressClusters::new(
+            settings.cluster_window,
+            settings.cluster_max_links,
+        )))
+    });
+    let address_scores = (settings.score_ema_alpha > 0.0).then(|| {
+        info!(
+            "Per-sender score smoothing: alpha {}, up to {} senders",
+            settings.score_ema_alpha, settings.score_ema_max_addresses
+        );
+        std::sync::Arc::new(Mutex::new(AddressScores::new(
+            settings.score_ema_alpha,
+            settings.score_ema_max_addresses,
+        )))
+    });
+    let recorder = settings.dataset_csv.clone().map(|path| {
+        info!("Recording scored transactions to {}", path);
+        std::sync::Arc::new(Recorder::new(path))
+    });
+    let baseline = (settings.baseline_priority_fee_wei > 0).then(|| {
+        info!(
+            "Rule baseline cross-check: windows of {} txs, warn at {} rule flags",
+            settings.baseline_window_txs, settings.baseline_min_flags
+        );
+        std::sync::Arc::new(Mutex::new(BaselineMonitor::new(
+            settings.baseline_window_txs,
+            settings.baseline_min_flags,
+        )))
+    });
+    // Optional context for detections; scoring goes on without it
+    let mempool = if settings.sender_history || settings.skip_mined {
+        let ws = WsConnect::new(settings.mempool_rpc_url.clone());
+        match ProviderBuilder::new().on_ws(ws).await {
+            Ok(provider) => Some(provider),
+            Err(e) => {
+                tracing::warn!(
+                    "Mempool RPC lookups disabled (sender history, mined checks): {}",
+                    e
+                );
+                None
+            }
+        }
+    } else {
+        None
+    };
+    let history = mempool
+        .clone()
+        .filter(|_| settings.sender_history)
+        .map(|provider| {
+            info!("Sender history: transaction counts of detected senders");
+            std::sync::Arc::new(SenderHistory::new(provider))
+        });
+    // Receipt lookups cost an RPC call per detection, so they are opt-in
+    let mined_check = mempool.filter(|_| settings.skip_mined).map(|provider| {
+        info!("Mined check: detections already in a block are not proven");
+        std::sync::Arc::new(provider)
+    });
+    // A held detection is only safe to fire hours later if it can expire or
+    // be found mined by then
+    let schedule_hold = match &settings.trap_schedule {
+        Some(_) if settings.trap_schedule_flush => {
+            if settings.pipeline_deadline_ms > 0 || mined_check.is_some() {
+                Some(std::sync::Arc::new(ScheduleHold {
+                    slots: Semaphore::new(MAX_HELD_DETECTIONS),
+                    block_rx: block_rx.clone(),
+                }))
+            } else {
+                tracing::warn!(
+                    "TRAP_SCHEDULE_FLUSH needs PIPELINE_DEADLINE_MS or SKIP_MINED; \
+                     detections outside the schedule are dropped instead"
+                );
+                None
+            }
+        }
+        _ => None,
+    };
+    let fifo = match settings.fifo_path.as_deref() {
+        Some(path) => {
+            info!("Detection FIFO: {}", path);
+            Some(DetectionFifo::spawn(path)?)
+        }
+        None => None,
+    };
+    // Only worth keeping when detections are written somewhere
+    let journal = (settings.detections_jsonl.is_some()
+        || settings.audit_log_path.is_some()
+        || fifo.is_some())
+    .then(|| std::sync::Arc::new(Mutex::new(ReorgJournal::default())));
+    // EZKL runs on its own threads so proving never starves scoring
+    let prover = std::sync::Arc::new(
+        ProverPool::new(settings.prover_threads).wrap_err("Failed to start prover threads")?,
+    );
+    if client.is_some() {
+        info!("Prover pool: {} thread(s)", prover.threads());
+    }
 
-    // Create Alloy Provider for Gas Estimation
-    let ws = WsConnect::new(rpc_url);
-    let provider = ProviderBuilder::new().on_ws(ws).await?;
-    let provider = std::sync::Arc::new(provider);
+    // Create Alloy Provider for Gas Estimation. Without a client the
+    // processor only scores, so no execution RPC is needed at all.
+    let provider = match client {
+        Some(_) => {
+            let ws = WsConnect::new(rpc_url);
+            Some(std::sync::Arc::new(ProviderBuilder::new().on_ws(ws).await?))
+        }
+        None => {
+            info!("Scoring-only mode: no gas estimation, proofs or submissions");
+            None
+        }
+    };
 
-    while let Some(tx) = rx.recv().await {
+    // The loop runs as its own task under a watchdog. If it panics, or its
+    // heartbeat stops for `processor_watchdog` (a deadlock), it is respawned
+    // on the same queue and state, so detection resumes with stats intact.
+    // The heartbeat needs the model session lock, so a session held forever
+    // counts as a stall too.
+    let inputs = std::sync::Arc::new(Mutex::new((rx, explain_rx, reload_rx, reorg_rx)));
+    let heartbeat = std::sync::Arc::new(std::sync::Mutex::new(Instant::now()));
+    let spawn_loop = |model: SharedScorer| {
+        let inputs = inputs.clone();
+        let heartbeat = heartbeat.clone();
         let ui_sender = ui_sender.clone();
-        let session = session.clone();
+        let shadow = shadow.clone();
         let stats = stats.clone();
         let client = client.clone();
         let provider = provider.clone();
-
-        // Spawn a task for each transaction
+        let settings = settings.clone();
+        let feature_cache = feature_cache.clone();
+        let approvals = approvals.clone();
+        let address_scores = address_scores.clone();
+        let coalescer = coalescer.clone();
+        let activity = activity.clone();
+        let lists = lists.clone();
+        let prover = prover.clone();
+        let extractor = extractor.clone();
+        let recorder = recorder.clone();
+        let baseline = baseline.clone();
+        let history = history.clone();
+        let fifo = fifo.clone();
+        let mined_check = mined_check.clone();
+        let schedule_hold = schedule_hold.clone();
+        let clusters = clusters.clone();
+        let journal = journal.clone();
+        let model_path = model_path.clone();
+        let block_rx = block_rx.clone();
+        let observed = observed.clone();
+        *heartbeat.lock().unwrap_or_else(|e| e.into_inner()) = Instant::now();
         tokio::spawn(async move {
-            if let Err(e) = process_transaction(
-                tx,
-                ui_sender,
-                session,
-                stats,
-                provider,
-                confidence_threshold,
-                client,
-            )
-            .await
-            {
-                error!("Processing failed: {:?}", e);
+            let mut inputs = inputs.lock().await;
+            let (rx, explain_rx, reload_rx, reorg_rx) = &mut *inputs;
+            let mut beat = tokio::time::interval(WATCHDOG_BEAT);
+            let mut probe = None;
+            loop {
+                tokio::select! {
+                    // Liveness for the watchdog: a stuck loop stops ticking
+                    _ = beat.tick() => probe_session(&model, &heartbeat, &mut probe),
+                    maybe_tx = rx.recv() => {
+                        let Some(tx) = maybe_tx else { break };
+                        let ui_sender = ui_sender.clone();
+                        let model = model.clone();
+                        let shadow = shadow.clone();
+                        let stats = stats.clone();
+                        let client = client.clone();
+                        let provider = provider.clone();
+                        let settings = settings.clone();
+                        let feature_cache = feature_cache.clone();
+                        let approvals = approvals.clone();
+                        let address_scores = address_scores.clone();
+                        let coalescer = coalescer.clone();
+                        let activity = activity.clone();
+                        let lists = lists.clone();
+                        let prover = prover.clone();
+                        let extractor = extractor.clone();
+                        let recorder = recorder.clone();
+                        let baseline = baseline.clone();
+                        let history = history.clone();
+                        let fifo = fifo.clone();
+                        let mined_check = mined_check.clone();
+                        let schedule_hold = schedule_hold.clone();
+                        let clusters = clusters.clone();
+                        let journal = journal.clone();
+                        // Snapshot the head at dequeue time so "same block" reasoning is stable
+                        let current_block = *block_rx.borrow();
+                        let observed = observed.load(Ordering::Relaxed);
+
+                        // Spawn a task for each transaction
+                        tokio::spawn(async move {
+                            let tx_hash = tx.hash.clone();
+                            let outcome = process_transaction(
+                                tx,
+                                ui_sender.clone(),
+                                model,
+                                shadow,
+                                stats.clone(),
+                                provider,
+                                client,
+                                settings,
+                                feature_cache,
+                                approvals,
+                                address_scores,
+                                coalescer,
+                                activity,
+                                lists,
+                                prover,
+                                extractor,
+                                recorder.clone(),
+                                baseline,
+                                history,
+                                fifo,
+                                mined_check,
+                                schedule_hold,
+                                clusters,
+                                journal,
+                                current_block,
+                                observed,
+                            )
+                            .await;
+                            if let Some(recorder) = recorder {
+                                recorder.finish(&tx_hash, &outcome).await;
+                            }
+                            match outcome {
+                                Ok(Some(reason)) => {
+                                    info!("Tx {} not trapped: {}", tx_hash, reason);
+                                    let mut stats_guard = stats.lock().await;
+                                    stats_guard.skipped.record(reason);
+                                    // Stats snapshots only feed the UI; skip the clone once it exited
+                                    if !ui_sender.is_closed() {
+                                        let stats_copy = (*stats_guard).clone();
+                                        let _ = ui_sender.send(UiMessage::StatsUpdate(stats_copy));
+                                    }
+                                }
+                                Ok(None) => {}
+                                Err(e) => {
+                                    error!("Processing failed: {:?}", e);
+                                }
+                            }
+                        });
+                    }
+                    // On-demand explanation for the transaction selected in the TUI
+                    Some(hash) = explain_rx.recv() => {
+                        let ui_sender = ui_sender.clone();
+                        let model = model.clone();
+                        let feature_cache = feature_cache.clone();
+                        tokio::spawn(async move {
+                            let Some(features) = feature_cache.lock().await.get(&hash) else {
+                                return;
+                            };
+                            let mut model_guard = model.lock().await;
+                            match explain_prediction(&features, |f| {
+                                model_guard.score(f).map_err(eyre::Report::from)
+                            }) {
+                                Ok(contributions) => {
+                                    let _ = ui_sender
+                                        .send(UiMessage::FeatureImportance(hash, contributions));
+                                }
+                                Err(e) => error!("Explanation failed for {}: {:?}", hash, e),
+                            }
+                        });
+                    }
+                    // Follow-up records for detections whose block was replaced
+                    Some(reorg) = reorg_rx.recv() => {
+                        let Some(journal) = &journal else { continue };
+                        let flagged = journal.lock().await.flag(reorg);
+                        if !flagged.is_empty() {
+                            tokio::spawn(record_reorged(flagged, settings.clone(), fifo.clone()));
+                        }
+                    }
+                    // Hot model update (SIGHUP): the listener and stats keep running
+                    Some(()) = reload_rx.recv() => {
+                        let ui_sender = ui_sender.clone();
+                        let model = model.clone();
+                        let model_path = model_path.clone();
+                        let settings = settings.clone();
+                        tokio::spawn(async move {
+                            match reload_model(&model, model_path, settings).await {
+                                Ok((old, new)) => {
+                                    let note = if old == new {
+                                        "unchanged".to_string()
+                                    } else {
+                                        format!("0x{} -> 0x{}", hex::encode(old), hex::encode(new))
+                                    };
+                                    info!("Model reloaded: {}", note);
+                                    let _ = ui_sender.send(UiMessage::Log(format!(
+                                        "Model reloaded: {}",
+                                        note
+                                    )));
+                                }
+                                Err(e) => {
+                                    error!("Model reload failed, keeping the running model: {:?}", e);
+                                    let _ = ui_sender.send(UiMessage::Log(
+                                        "Model reload failed, running model kept".to_string(),
+                                    ));
+                                }
+                            }
+                        });
+                    }
+                }
             }
-        });
+        })
+    };
+
+    let mut model = model;
+    let mut restarts = 0u32;
+    loop {
+        let mut handle = spawn_loop(model.clone());
+        let cause = match supervise(&mut handle, &heartbeat, settings.processor_watchdog).await {
+            LoopExit::Closed => break,
+            LoopExit::Failed(cause) => cause,
+            LoopExit::Stalled(silent) => {
+                // Whatever holds the session never lets go: start from a new one
+                match Model::load(&model_path, &settings) {
+                    Ok(fresh) => model = std::sync::Arc::new(Mutex::new(Box::new(fresh))),
+                    Err(e) => error!("Failed to reload the model after a stall: {:?}", e),
+                }
+                format!("stalled for {:?}", silent)
+            }
+        };
+        restarts += 1;
+        error!(
+            "Processor loop {}. Restarting (restart #{}).",
+            cause, restarts
+        );
+        let _ = ui_sender.send(UiMessage::Log(format!(
+            "Processor {}, restarted (#{})",
+            cause, restarts
+        )));
+        tokio::time::sleep(WATCHDOG_RESTART_DELAY).await;
     }
 
     Ok(())
 }
 
-#[instrument(skip(ui_sender, session, tx, stats, provider, client), fields(hash = %tx.hash))]
+/// How the processing loop task ended
+#[derive(Debug, PartialEq, Eq)]
+enum LoopExit {
+    /// The transaction queue closed: normal shutdown
+    Closed,
+    /// Panicked; the cause reads after "Processor loop"
+    Failed(String),
+    /// No heartbeat for this long: the loop or the model session is stuck
+    Stalled(Duration),
+}
+
+/// Wait for the loop task behind `handle` to end, aborting it once
+/// `heartbeat` is older than `stall_after` (zero = no stall check)
+async fn supervise(
+    handle: &mut tokio::task::JoinHandle<()>,
+    heartbeat: &std::sync::Mutex<Instant>,
+    stall_after: Duration,
+) -> LoopExit {
+    let mut check = tokio::time::interval(WATCHDOG_BEAT);
+    loop {
+        tokio::select! {
+            joined = &mut *handle => {
+                return match joined {
+                    Ok(()) => LoopExit::Closed,
+                    Err(e) if e.is_panic() => {
+                        let payload = e.into_panic();
+                        let message = payload
+                            .downcast_ref::<&str>()
+                            .map(|m| m.to_string())
+                            .or_else(|| payload.downcast_ref::<String>().cloned())
+                            .unwrap_or_else(|| "unknown panic".to_string());
+                        LoopExit::Failed(format!("panicked: {}", message))
+                    }
+                    Err(e) => LoopExit::Failed(format!("ended unexpectedly: {}", e)),
+                };
+            }
+            _ = check.tick(), if !stall_after.is_zero() => {
+                let silent = heartbeat.lock().unwrap_or_else(|e| e.into_inner()).elapsed();
+                if silent > stall_after {
+                    handle.abort();
+                    return LoopExit::Stalled(silent);
+                }
+            }
+        }
+    }
+}
+
+/// Beat for the watchdog once the model session can be locked. The lock is
+/// fair, so under load the probe waits its turn; a session held forever
+/// (a deadlock) leaves `heartbeat` to age. One probe is in flight at a time.
+fn probe_session(
+    model: &SharedScorer,
+    heartbeat: &std::sync::Arc<std::sync::Mutex<Instant>>,
+    probe: &mut Option<tokio::task::JoinHandle<()>>,
+) {
+    if probe.as_ref().is_some_and(|p| !p.is_finished()) {
+        return;
+    }
+    let model = model.clone();
+    let heartbeat = heartbeat.clone();
+    *probe = Some(tokio::spawn(async move {
+        let _session = model.lock().await;
+        *heartbeat.lock().unwrap_or_else(|e| e.into_inner()) = Instant::now();
+    }));
+}
+
+/// Runs a single transaction through scoring, proving and submission.
+///
+/// Returns `Some(SkipReason)` when the transaction did not end in an on-chain trap.
+#[allow(clippy::too_many_arguments)]
+#[instrument(
+    skip(
+        ui_sender,
+        model,
+        shadow,
+        tx,
+        stats,
+        provider,
+        client,
+        settings,
+        feature_cache,
+        approvals,
+        address_scores,
+        coalescer,
+        activity,
+        lists,
+        prover,
+        extractor,
+        recorder,
+        baseline,
+        history,
+        fifo,
+        mined_check,
+        schedule_hold,
+        clusters,
+        journal
+    ),
+    fields(hash = %tx.hash)
+)]
 async fn process_transaction<P>(
     tx: PendingTransaction,
-    ui_sender: UnboundedSender<UiMessage>,
-    session: std::sync::Arc<Mutex<Session>>,
+    ui_sender: UiSender,
+    model: SharedScorer,
+    shadow: Option<std::sync::Arc<Mutex<Session>>>,
     stats: std::sync::Arc<Mutex<SentinelStats>>,
-    provider: std::sync::Arc<impl Provider<PubSubFrontend> + 'static>,
-    confidence_threshold: f32,
-    client: std::sync::Arc<crate::network::SentinelClient<P>>,
-) -> Result<()>
+    provider: Option<std::sync::Arc<impl Provider<PubSubFrontend> + 'static>>,
+    client: Option<std::sync::Arc<crate::network::SentinelClient<P>>>,
+    settings: std::sync::Arc<ProcessorSettings>,
+    feature_cache: std::sync::Arc<Mutex<FeatureCache>>,
+    approvals: std::sync::Arc<Mutex<ApprovalTracker>>,
+    address_scores: Option<std::sync::Arc<Mutex<AddressScores>>>,
+    coalescer: std::sync::Arc<Mutex<BlockCoalescer>>,
+    activity: Option<std::sync::Arc<Mutex<RecentActivity>>>,
+    lists: SharedLists,
+    prover: std::sync::Arc<ProverPool>,
+    extractor: std::sync::Arc<dyn FeatureExtractor>,
+    recorder: Option<std::sync::Arc<Recorder>>,
+    baseline: Option<std::sync::Arc<Mutex<BaselineMonitor>>>,
+    history: Option<std::sync::Arc<SenderHistory>>,
+    fifo: Option<DetectionFifo>,
+    mined_check: Option<std::sync::Arc<RootProvider<PubSubFrontend>>>,
+    schedule_hold: Option<std::sync::Arc<ScheduleHold>>,
+    clusters: Option<std::sync::Arc<Mutex<AddressClusters>>>,
+    journal: Option<std::sync::Arc<Mutex<ReorgJournal>>>,
+    mut current_block: u64,
+    observed: u64,
+) -> Result<Option<SkipReason>>
 where
     P: Provider<PubSubFrontend, alloy::network::Ethereum> + Clone + 'static,
 {
     let tx_hash = tx.hash.clone();
 
     // Update Stats: Scanned
-    {
+    let scanned = {
         let mut stats_guard = stats.lock().await;
         stats_guard.total_scanned += 1;
+        // Hashes dropped under load still count towards the mempool seen
+        stats_guard.total_observed = stats_guard.total_observed.max(observed);
+        stats_guard.efficiency_boost = stats_guard.efficiency(settings.efficiency_basis);
         // Optional: Send update on every scan? Maybe too noisy. Update on intervals or detection.
         // Let's update quietly for now or just assume UI polling? UI is push-based.
         // We can send stats update occasionally, but definitely on detection.
+        stats_guard.total_scanned
+    };
+    let warming_up = scanned <= settings.warmup_txs;
+    if warming_up || scanned == settings.warmup_txs + 1 {
+        let _ = ui_sender.send(UiMessage::WarmupProgress(
+            scanned.min(settings.warmup_txs),
+            settings.warmup_txs,
+        ));
+    }
+
+    if let Some(activity) = &activity {
+        activity
+            .lock()
+            .await
+            .record(&tx.from, tx.nonce, tx.received_at);
+    }
+
+    // Approve-then-swap: remember approvals of monitored contracts, and flag
+    // swaps from senders that approved one moments ago
+    let recent_approval = {
+        let mut approvals = approvals.lock().await;
+        if let Some(spender) = tx.approves {
+            approvals.record(&tx.from, spender, tx.received_at);
+            None
+        } else if tx.target != TxTarget::Other {
+            approvals.recent(&tx.from, tx.received_at)
+        } else {
+            None
+        }
+    };
+    if let Some((since, spender)) = recent_approval {
+        let note = format!(
+            "Sender approved the {} {}s before this swap (approve-then-swap setup)",
+            spender,
+            since.as_secs()
+        );
+        info!("Tx {}: {}", tx_hash, note);
+        let _ = ui_sender.send(UiMessage::ContextNote(tx_hash.clone(), note));
+    }
+    if let Some(escalation) = tx.gas_escalation {
+        info!("Tx {}: gas escalation, {}", tx_hash, escalation);
+    }
+
+    // Multi-address actors: transfers link funder and recipient (before the
+    // plain-transfer prefilter), swaps are checked against the cluster
+    let coordinated = match &clusters {
+        Some(clusters) => {
+            let mut clusters = clusters.lock().await;
+            match tx.to.as_deref() {
+                Some(to) if tx.is_plain_transfer() && tx.value > 0 => {
+                    clusters.link(&tx.from, to, tx.received_at);
+                    None
+                }
+                _ if tx.target != TxTarget::Other => {
+                    clusters.swap(&tx.from, current_block, tx.received_at)
+                }
+                _ => None,
+            }
+        }
+        None => None,
+    };
+    if let Some(coordinated) = &coordinated {
+        info!(
+            "Tx {}: {} swaps in block {} alongside cluster member(s) {} ({} linked addresses)",
+            tx_hash,
+            tx.from,
+            current_block,
+            coordinated.partners.join(", "),
+            coordinated.members.len()
+        );
+        let _ = ui_sender.send(UiMessage::Cluster(
+            tx_hash.clone(),
+            coordinated.members.clone(),
+        ));
+    }
+
+    // Allowlisted senders are never flagged; scoring them would only cost time
+    let listed = lists
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .verdict(&tx.from);
+    if listed == Some(ListKind::Allow) {
+        info!(
+            "Tx {} is from allowlisted {}. Not scored.",
+            tx_hash, tx.from
+        );
+        let _ = ui_sender.send(UiMessage::Unscored(
+            tx_hash.clone(),
+            SkipReason::Allowlisted,
+        ));
+        return Ok(Some(SkipReason::Allowlisted));
+    }
+
+    if let Some(reason) = settings.prefilter(&tx) {
+        match reason {
+            // Blob txs price data availability separately; the model was not
+            // trained on them and their gas features would be misleading
+            SkipReason::BlobTransaction => info!(
+                "Tx {} is a blob transaction ({} blobs, max blob fee {} wei). Not scored.",
+                tx_hash,
+                tx.blob_count,
+                tx.max_fee_per_blob_gas.unwrap_or(0)
+            ),
+            // Plain ETH transfers carry no swap, so they can't be a sandwich or
+            // frontrun leg: count them and skip the gas estimate and inference
+            SkipReason::PlainTransfer => {
+                tracing::debug!("Tx {} is a plain transfer. Not scored.", tx_hash)
+            }
+            // Bundles and other huge payloads are not the swap patterns we
+            // trap, and would be costly to estimate, score and prove
+            SkipReason::OversizedInput => info!(
+                "Tx {} carries {} bytes of calldata, over the {} byte limit. Not scored.",
+                tx_hash,
+                tx.input.len(),
+                settings.max_input_bytes
+            ),
+            // Frontrunners outbid the queue with their tip; without one the
+            // core signal is absent, so skip the gas estimate and inference
+            _ => tracing::debug!(
+                "Tx {} tips {} wei, below the priority fee floor. Marked safe.",
+                tx_hash,
+                tx.priority_fee
+            ),
+        }
+        // Blob rows already carry their own marker in the UI
+        if reason != SkipReason::BlobTransaction {
+            let _ = ui_sender.send(UiMessage::Unscored(tx_hash.clone(), reason));
+        }
+        return Ok(Some(reason));
     }
 
     // 1. EXTRACT FEATURES
@@ -136,134 +1499,368 @@ where
     static TX_COUNTER: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
     let current_index = TX_COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed) % 150; // Simulate block index 0-149
 
-    // Prepare Transaction Request for Gas Estimation
-    let from_addr = Address::from_str(&tx.from).unwrap_or_default();
-    let to_addr = tx.to.as_ref().and_then(|t| Address::from_str(t).ok());
-    let value_u256 = U256::from(tx.value);
+    // Skipped in scoring-only mode (no execution provider), and imputed
+    // while the execution RPC is down rather than failing every estimate
+    let degraded = client.as_ref().is_some_and(|c| c.execution_degraded());
+    let estimator = provider.as_deref().filter(|_| !degraded);
+    let (estimated_gas_used, estimate_reverted) =
+        estimate_gas_used(estimator, &tx, Some(&ui_sender)).await;
 
-    let mut tx_req = TransactionRequest::default()
-        .from(from_addr)
-        .value(value_u256)
-        .input(tx.input.clone().into());
-
-    if let Some(addr) = to_addr {
-        tx_req = tx_req.to(addr);
+    if estimate_reverted {
+        let mut stats_guard = stats.lock().await;
+        stats_guard.gas_estimate_reverts += 1;
     }
 
-    // Estimate Gas
-    let estimated_gas_used = match provider.estimate_gas(&tx_req).await {
-        Ok(gas) => gas as f32,
-        Err(_e) => {
-            // warn!("Gas estimation failed for {}: {:?}", tx_hash, _e);
-            // Fallback to limit or simple ratio
-            tx.gas_limit as f32 * 0.7 // Assume 70% usage if estimation fails
-        }
-    };
-
-    let simulated_gas_ratio = estimated_gas_used / (tx.gas_limit as f32 + 1.0); // Simple ratio
-
-    let raw_features = FeatureVector {
-        tx_index: current_index as f32,
-        gas_price_gwei: (tx.gas_price.unwrap_or(0) as f32) / 1e9,
-        priority_fee_gwei: (tx.priority_fee as f32) / 1e9,
-        gas_used: estimated_gas_used,
-        native_value: (tx.value as f32) / 1e18,
-        gas_usage_ratio: simulated_gas_ratio,
+    let ctx = ChainContext {
+        tx_index: current_index,
+        estimated_gas_used,
+        estimate_reverted,
+        block_number: current_block,
     };
+    let raw_features = info_span!("features").in_scope(|| extractor.extract(&tx, &ctx));
 
-    info!("Raw Features [{}]: {:?}", tx_hash, raw_features);
+    info!(
+        "Raw Features [{}]: {:?} (estimate reverted: {})",
+        tx_hash, raw_features, estimate_reverted
+    );
 
-    let normalized_features = normalize_features(&raw_features);
+    let mut normalized_features = normalize_features(&raw_features);
+    // Far outside the training range the model only extrapolates
+    let clamped = clamp_features(&mut normalized_features, settings.feature_clamp_sigma);
+    if !clamped.is_empty() {
+        info!(
+            "Tx {}: clamped {} to ±{}σ",
+            tx_hash,
+            clamped.join(", "),
+            settings.feature_clamp_sigma
+        );
+    }
     info!("Normalized [{}]: {:?}", tx_hash, normalized_features);
 
+    // Keep the normalized input around for on-demand explanations
+    feature_cache
+        .lock()
+        .await
+        .insert(tx_hash.clone(), normalized_features);
+
     // 2. RUN INFERENCE
-    // Input shape: [1, 6] - Model expects 6 features.
-    let input_vec = normalized_features.to_vec();
-    // input_vec.push(0.0); // Padding removed
+    // A broken runtime must never read as "safe": retry, then report it
+    let mut attempt = 0;
+    let (probability, model_hash) = loop {
+        let result = async {
+            // The hash is read under the same lock, so it names the model that scored
+            let mut model_guard = model.lock().await;
+            model_guard
+                .score(&normalized_features)
+                .map(|probability| (probability, model_guard.hash()))
+        }
+        .instrument(info_span!("inference", attempt))
+        .await;
+        match result {
+            Ok(scored) => break scored,
+            Err(e) if attempt < INFERENCE_RETRIES => {
+                attempt += 1;
+                tracing::warn!("Inference failed for {}: {}. Retrying.", tx_hash, e);
+            }
+            Err(e) => {
+                error!("Inference failed for {}: {}", tx_hash, e);
+                let _ = ui_sender.send(UiMessage::ProcessingUpdate(ProcessingStage::Error(
+                    tx_hash.clone(),
+                    "inference failed".to_string(),
+                )));
+                let _ = ui_sender.send(UiMessage::InferenceFailed(tx_hash.clone()));
+                return Ok(Some(SkipReason::InferenceFailed));
+            }
+        }
+    };
 
-    let input_tensor = Array2::from_shape_vec((1, 6), input_vec)?;
+    // Update UI with confidence score
+    let _ = ui_sender.send(UiMessage::ConfidenceUpdate(tx_hash.clone(), probability));
+    if let Some(recorder) = &recorder {
+        let sample = Sample {
+            scored_at: chrono::Utc::now(),
+            sender: tx.from.clone(),
+            raw: raw_features.to_array(),
+            score: probability,
+            action: None,
+        };
+        recorder.scored(&tx_hash, sample).await;
+    }
 
-    // Lock session for inference
-    let logit = {
-        let mut session_guard = session.lock().await;
-        // Convert to Value
-        let input_value = ort::value::Value::from_array(input_tensor.into_dyn())?;
+    // Per-sender smoothing: one noisy spike is not enough to trap
+    let address_score = match &address_scores {
+        Some(scores) => Some(scores.lock().await.update(&tx.from, probability)),
+        None => None,
+    };
+    if let Some(score) = address_score {
+        let _ = ui_sender.send(UiMessage::AddressScore(tx_hash.clone(), score));
+    }
 
-        // Dynamically get the first input name
-        let input_name = session_guard.inputs()[0].name().to_string();
-        let inputs = ort::inputs![
-            input_name => input_value,
-        ];
+    // Threshold check: per-reason override, else the global threshold
+    let reason = classify_reason(&tx, &normalized_features, estimate_reverted);
+    let mut decision = settings.decide(
+        probability,
+        address_score.map(|s| s.smoothed),
+        reason,
+        recent_approval.is_some(),
+        tx.gas_escalation.is_some(),
+    );
+    if coordinated.is_some() {
+        decision = settings.coordinated(decision);
+    }
+    let confidence_threshold = decision.threshold;
 
-        // Dynamically get the first output name
-        // Log all outputs for debugging
-        for (i, output) in session_guard.outputs().iter().enumerate() {
-            info!("Output {}: {:?}", i, output);
+    // Safety net against a silently broken model (e.g. always scoring 0)
+    if let Some(baseline) = &baseline {
+        let rule_flag = baseline_flags(&tx, settings.baseline_priority_fee_wei);
+        let model_flag = probability >= confidence_threshold;
+        if let Some(d) = baseline.lock().await.observe(rule_flag, model_flag) {
+            let warning = format!(
+                "Possible model degradation: no detections in the last {} txs, but the rule baseline flagged {}",
+                d.scored, d.rule_flags
+            );
+            tracing::warn!("{}", warning);
+            let _ = ui_sender.send(UiMessage::Log(warning));
         }
+    }
 
-        let outputs = session_guard.run(inputs)?;
-
-        // Strategy:
-        // 1. If we have >1 output, assume index 1 is probabilities [prob_0, prob_1].
-        // 2. If index 1 gives valid f32, use it.
-        // 3. Fallback to index 0 (Label), return 0.0 or 1.0.
-
-        let val = if outputs.len() >= 2 {
-            if let Ok(tensor) = outputs[1].try_extract_tensor::<f32>() {
-                if tensor.1.len() >= 2 {
-                    tensor.1[1] // Return Class 1 probability
-                } else {
-                    // unexpected shape
-                    if let Ok(t0) = outputs[0].try_extract_tensor::<f32>() {
-                        t0.1[0]
-                    } else if let Ok(t0) = outputs[0].try_extract_tensor::<i64>() {
-                        t0.1[0] as f32
-                    } else {
-                        0.0
-                    }
-                }
-            } else {
-                // output 1 not f32
-                if let Ok(t0) = outputs[0].try_extract_tensor::<f32>() {
-                    t0.1[0]
-                } else if let Ok(t0) = outputs[0].try_extract_tensor::<i64>() {
-                    t0.1[0] as f32
+    // Shadow comparison on a deterministic sample; shadow decisions never trap
+    if let Some(shadow) = shadow.filter(|_| in_shadow_sample(&tx_hash, settings.shadow_sample_rate))
+    {
+        match run_inference(
+            &mut *shadow.lock().await,
+            &normalized_features,
+            settings.probability_output_index,
+            settings.probability_class_index,
+        ) {
+            Ok(shadow_probability) => {
+                let primary_flag = probability >= confidence_threshold;
+                let shadow_flag = shadow_probability >= confidence_threshold;
+                let mut stats_guard = stats.lock().await;
+                stats_guard.shadow_compared += 1;
+                if primary_flag == shadow_flag {
+                    stats_guard.shadow_agreed += 1;
                 } else {
-                    0.0
+                    tracing::warn!(
+                        "Shadow DIVERGENCE on {}: primary {:.4} ({}) vs shadow {:.4} ({}), threshold {:.4}",
+                        tx_hash,
+                        probability,
+                        if primary_flag { "trap" } else { "safe" },
+                        shadow_probability,
+                        if shadow_flag { "trap" } else { "safe" },
+                        confidence_threshold
+                    );
                 }
             }
-        } else {
-            // Only 1 output
-            if let Ok(t0) = outputs[0].try_extract_tensor::<f32>() {
-                t0.1[0]
-            } else if let Ok(t0) = outputs[0].try_extract_tensor::<i64>() {
-                t0.1[0] as f32
-            } else {
-                tracing::error!("Failed to extract any output");
-                0.0
-            }
+            Err(e) => tracing::warn!("Shadow inference failed for {}: {:?}", tx_hash, e),
+        }
+    }
+
+    // Hold window: give the other legs of a multi-tx attack, or a replacement,
+    // time to show up, then decide again. Near-misses that a companion could
+    // push over the threshold are held too.
+    if let Some(activity) =
+        activity.filter(|_| decision.trap || settings.after_hold(decision.clone(), None, 1).trap)
+    {
+        tokio::time::sleep(settings.trap_hold).await;
+        let context = activity
+            .lock()
+            .await
+            .after(&tx.from, tx.nonce, tx.received_at);
+        if context.replaced {
+            info!(
+                "Tx {} was replaced (same nonce) during the trap hold. Not trapped.",
+                tx_hash
+            );
+            return Ok(Some(SkipReason::Replaced));
+        }
+        let smoothed = match &address_scores {
+            Some(scores) => scores.lock().await.get(&tx.from).map(|s| s.smoothed),
+            None => None,
         };
-        val
+        decision = settings.after_hold(decision, smoothed, context.companions);
+        if context.companions > 0 {
+            let note = format!(
+                "Sender sent {} more tx(s) during the {} ms trap hold",
+                context.companions,
+                settings.trap_hold.as_millis()
+            );
+            info!("Tx {}: {}", tx_hash, note);
+            let _ = ui_sender.send(UiMessage::ContextNote(tx_hash.clone(), note));
+        }
+    }
+
+    // Denylisted senders are flagged whatever the score
+    if listed == Some(ListKind::Deny) && !decision.trap {
+        info!("Tx {} is from denylisted {}. Flagged.", tx_hash, tx.from);
+        decision.decided_by = DecisionModifier::Denylist;
+        decision.trap = true;
+    }
+
+    if !decision.trap {
+        info!("Tx {} is SAFE (Confidence: {:.4})", tx_hash, probability);
+        settings.emit_trace(&ui_sender, &tx_hash, decision);
+        return Ok(Some(SkipReason::BelowThreshold));
+    }
+
+    let predator_addr = &tx.from;
+    tracing::warn!(
+        ">>> PREDATOR DETECTED: {} [{}] (Confidence: {:.4} > Threshold {:.4}, Estimate Reverted: {}) <<<",
+        predator_addr,
+        reason,
+        probability,
+        confidence_threshold,
+        estimate_reverted
+    );
+
+    let detection = Detection {
+        bot_address: predator_addr.clone(),
+        tx_hash: tx_hash.clone(),
+        confidence: probability,
+        detected_at: chrono::Utc::now(),
+        latency: tx.received_at.elapsed(),
+        reason,
+        raw_tx: tx.raw.as_ref().map(|raw| format!("0x{}", hex::encode(raw))),
+        block: current_block,
+        reorged: false,
+        features: settings.detection_features.then_some(normalized_features),
     };
+    if let Some(path) = settings.detections_jsonl.clone() {
+        let max_bytes = settings.detections_jsonl_max_bytes;
+        let record = detection.clone();
+        match tokio::task::spawn_blocking(move || {
+            crate::detections::append(&path, max_bytes, &record)
+        })
+        .await
+        {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => error!("Failed to write detections stream: {:?}", e),
+            Err(e) => error!("Detections stream task panicked: {}", e),
+        }
+    }
+    if let Some(fifo) = &fifo {
+        fifo.send(&detection);
+    }
+    if let Some(journal) = &journal {
+        journal.lock().await.record(detection.clone());
+    }
+    let _ = ui_sender.send(UiMessage::NewDetection(detection));
+    if let Some(history) = &history {
+        history.spawn_lookup(predator_addr.clone(), ui_sender.clone());
+    }
 
-    let probability = logit;
+    // Value floor: a proof can cost more gas than a tiny transaction puts at risk
+    if tx.value < settings.min_trap_value_wei {
+        info!(
+            "Predator {} below value floor ({} < {} wei). Skipping proof generation.",
+            predator_addr, tx.value, settings.min_trap_value_wei
+        );
+        let _ = ui_sender.send(UiMessage::Log(format!(
+            "Detected {} (low value, not trapped)",
+            predator_addr
+        )));
+        stats.lock().await.total_detected += 1;
+        decision.decided_by = DecisionModifier::ValueFloor;
+        decision.trap = false;
+        settings.emit_trace(&ui_sender, &tx_hash, decision);
+        return Ok(Some(SkipReason::BelowValueFloor));
+    }
+    let action = settings.trap_action(&decision);
+    let detection_reason = decision.reason;
+    if let Some(recorder) = &recorder {
+        recorder.submitting(&tx_hash, action).await;
+    }
+    settings.emit_trace(&ui_sender, &tx_hash, decision);
 
-    // Update UI with confidence score
-    let _ = ui_sender.send(UiMessage::ConfidenceUpdate(tx_hash.clone(), probability));
+    // Scoring-only mode: nothing is proven or submitted
+    let Some(client) = client else {
+        let _ = ui_sender.send(UiMessage::Log(format!(
+            "Detected {} (scoring only, not trapped)",
+            predator_addr
+        )));
+        stats.lock().await.total_detected += 1;
+        return Ok(Some(SkipReason::ScoringOnly));
+    };
+
+    // Execution RPC down: nothing could be submitted, so don't prove either
+    if client.execution_degraded() {
+        let _ = ui_sender.send(UiMessage::Log(format!(
+            "Detected {} (execution degraded, not trapped)",
+            predator_addr
+        )));
+        stats.lock().await.total_detected += 1;
+        return Ok(Some(SkipReason::ExecutionDegraded));
+    }
+
+    // Warmup: let stats and block-position features settle before spending gas
+    if warming_up {
+        info!(
+            "Predator {} detected during warmup ({}/{}). Not trapping.",
+            predator_addr, scanned, settings.warmup_txs
+        );
+        let _ = ui_sender.send(UiMessage::Log(format!(
+            "Detected {} (warming up, not trapped)",
+            predator_addr
+        )));
+        stats.lock().await.total_detected += 1;
+        return Ok(Some(SkipReason::Warmup));
+    }
+
+    // Schedule: outside the trap windows, log and drop or hold until one opens
+    if let Some(schedule) = &settings.trap_schedule {
+        let wait = schedule.until_active(chrono::Utc::now());
+        let held = match &schedule_hold {
+            Some(hold) if !wait.is_zero() => hold.slots.try_acquire().ok().map(|p| (hold, p)),
+            _ => None,
+        };
+        if let Some((hold, _slot)) = held {
+            info!(
+                "Predator {} detected outside the trap schedule. Holding for {}s.",
+                predator_addr,
+                wait.as_secs()
+            );
+            let _ = ui_sender.send(UiMessage::Log(format!(
+                "Detected {} (outside trap schedule, held for {}m)",
+                predator_addr,
+                wait.as_secs().div_ceil(60)
+            )));
+            tokio::time::sleep(wait).await;
+            current_block = *hold.block_rx.borrow();
+        } else if !wait.is_zero() {
+            info!(
+                "Predator {} detected outside the trap schedule ({}). Not trapping.",
+                predator_addr, schedule
+            );
+            let _ = ui_sender.send(UiMessage::Log(format!(
+                "Detected {} (outside trap schedule, not trapped)",
+                predator_addr
+            )));
+            stats.lock().await.total_detected += 1;
+            return Ok(Some(SkipReason::OutsideSchedule));
+        }
+    }
 
-    // Threshold check (Hardcoded 0.8 or from Config if available)
-    if probability < confidence_threshold {
-        info!("Tx {} is SAFE (Confidence: {:.4})", tx_hash, probability);
-        return Ok(());
+    // Deadline: a trap that lands after the bot's block only burns gas
+    if let Some(elapsed) = settings.overdue(tx.received_at) {
+        report_too_slow(&ui_sender, &tx_hash, elapsed, "proof generation");
+        return Ok(Some(SkipReason::TooSlow));
     }
 
-    let predator_addr = &tx.from;
-    tracing::warn!(
-        ">>> PREDATOR DETECTED: {} (Confidence: {:.4} > Threshold {:.4}) <<<",
-        predator_addr,
-        probability,
-        confidence_threshold
-    );
+    // Mined already (queueing, estimates, a schedule hold): nothing left to prevent
+    if let Some(mempool) = &mined_check {
+        if is_mined(mempool.as_ref(), &tx_hash).await {
+            info!(
+                "Tx {} from {} is already mined. Skipping proof generation.",
+                tx_hash, predator_addr
+            );
+            let _ = ui_sender.send(UiMessage::Log(format!(
+                "Detected {} (already mined, not trapped)",
+                predator_addr
+            )));
+            stats.lock().await.total_detected += 1;
+            return Ok(Some(SkipReason::AlreadyMined));
+        }
+    }
 
     // 0. PRE-CHECK ON-CHAIN STATUS
     let predator_address = Address::from_str(predator_addr).unwrap_or_default();
@@ -277,7 +1874,7 @@ where
                 "Skipping: {} is already trapped.",
                 predator_addr
             )));
-            return Ok(());
+            return Ok(Some(SkipReason::AlreadyTrapped));
         }
         Err(e) => {
             tracing::warn!(
@@ -291,36 +1888,122 @@ where
         _ => {}
     }
 
-    info!("Proceeding to generate ZK Proof and on-chain trap...");
+    // 0a. WALLET BALANCE: don't spend a proof on a trap we can't pay for
+    if !client.can_afford_trap(settings.min_wallet_balance_wei) {
+        tracing::warn!(
+            "Signer balance too low to trap {} (lowest {} wei). Skipping proof generation.",
+            predator_addr,
+            client.latest_wallet_balance().unwrap_or(0)
+        );
+        let _ = ui_sender.send(UiMessage::Log(format!(
+            "Skipping {}: wallet balance too low",
+            predator_addr
+        )));
+        return Ok(Some(SkipReason::InsufficientBalance));
+    }
+
+    // 0b. LOCAL IDEMPOTENCY GUARD (in-flight submissions and restarts).
+    // Several detections of one sender in a block make a single trap: the
+    // leader claims, a more confident takeover inherits its claim.
+    let coalesce = coalescer.lock().await.offer(
+        &tx.from,
+        current_block,
+        &tx_hash,
+        probability,
+        || match client.claim_submission(predator_address) {
+            Ok(claimed) => claimed,
+            Err(e) => {
+                tracing::warn!("Submission ledger unavailable for {}: {}", predator_addr, e);
+                true
+            }
+        },
+    );
+    match coalesce {
+        Coalesce::Lead | Coalesce::TakeOver => {}
+        Coalesce::Coalesced => {
+            info!(
+                "Predator {} already has a trap in block {} at higher confidence. Coalesced.",
+                predator_addr, current_block
+            );
+            stats.lock().await.total_detected += 1;
+            return Ok(Some(SkipReason::Coalesced));
+        }
+        Coalesce::Claimed => {
+            info!(
+                "Predator {} already has a submission in flight or sent. Skipping.",
+                predator_addr
+            );
+            let _ = ui_sender.send(UiMessage::Log(format!(
+                "Skipping: {} already submitted.",
+                predator_addr
+            )));
+            return Ok(Some(SkipReason::AlreadyTrapped));
+        }
+    }
+    // Only the detection that still owns the claim may give it back
+    let release_claim = || async {
+        if coalescer
+            .lock()
+            .await
+            .release(&tx.from, current_block, &tx_hash)
+        {
+            client.release_submission(predator_address);
+        }
+    };
+
+    info!("Proceeding to generate ZK Proof and on-chain {}...", action);
 
     // Update Stats: Detection & Economic Impact
     {
         let mut stats_guard = stats.lock().await;
         stats_guard.total_detected += 1;
-        stats_guard.total_trapped += 1; // Assuming we block it
+        // A replaced leader already counted this sender's trap and savings;
+        // a flag-only submission blocks nothing
+        if coalesce != Coalesce::TakeOver && action == TrapAction::Trap {
+            stats_guard.total_trapped += 1; // Assuming we block it
+
+            // Per-block window: restart the counter when the head advances
+            if current_block != stats_guard.stats_block {
+                stats_guard.stats_block = current_block;
+                if settings.reset_on_new_block {
+                    stats_guard.detections_in_block = 0;
+                }
+            }
+            stats_guard.detections_in_block += 1;
+            info!(
+                "Detection #{} in block {}",
+                stats_guard.detections_in_block, current_block
+            );
 
-        let eth_value = (tx.value as f64) / 1e18;
-        let saved_eth = eth_value * 0.01; // 1% Slippage Margin saved
-        stats_guard.eth_saved += saved_eth;
+            // A sandwich costs its victim more than a generic flag would, so
+            // savings are weighted by the detection reason
+            let weight = settings.impact_weight(detection_reason);
+            let eth_value = (tx.value as f64) / 1e18;
+            let saved_eth = eth_value * 0.01 * weight; // 1% Slippage Margin saved
 
-        // Gas Saved in Gwei
-        // total_fee_wei = gas_limit * gas_price
-        let gas_price = tx.gas_price.unwrap_or(0);
-        let total_fee_gwei = (tx.gas_limit as u128 * gas_price) / 1_000_000_000;
-        stats_guard.gas_saved += total_fee_gwei;
+            // Gas Saved: total_fee_wei = gas_limit * gas_price (kept in wei, as
+            // L2 fees are fractions of a Gwei)
+            let gas_price = tx.gas_price.unwrap_or(0);
+            let saved_gas = ((tx.gas_limit as u128 * gas_price) as f64 * weight) as u128;
+            stats_guard.eth_saved += saved_eth;
+            stats_guard.gas_saved_wei += saved_gas;
+            let by_reason = stats_guard
+                .saved_by_reason
+                .entry(detection_reason)
+                .or_default();
+            by_reason.trapped += 1;
+            by_reason.eth_saved += saved_eth;
+            by_reason.gas_saved_wei += saved_gas;
 
-        // Efficiency Boost: blocked / scanned * 100
-        if stats_guard.total_scanned > 0 {
-            stats_guard.efficiency_boost =
-                (stats_guard.total_trapped as f32 / stats_guard.total_scanned as f32) * 100.0;
-        }
+            // Efficiency Boost: blocked / (observed or scanned) * 100
+            stats_guard.efficiency_boost = stats_guard.efficiency(settings.efficiency_basis);
 
-        // History for Sparkline (store as u64 scaled by 1000 for simpler graph or just raw value if supported)
-        // Sparkline takes &[u64]. Let's store Accumulated ETH saved * 1000?
-        let history_val = (stats_guard.eth_saved * 1000.0) as u64;
-        stats_guard.history_saved.push(history_val);
-        if stats_guard.history_saved.len() > 100 {
-            stats_guard.history_saved.remove(0);
+            // History for Sparkline: raw cumulative ETH, scaled by the UI at draw time
+            let history_val = stats_guard.eth_saved;
+            stats_guard.history_saved.push(history_val);
+            if stats_guard.history_saved.len() > 100 {
+                stats_guard.history_saved.remove(0);
+            }
         }
 
         let stats_copy = (*stats_guard).clone();
@@ -337,12 +2020,50 @@ where
         ProcessingStage::CreatingZKProof(tx_hash.clone()),
     ));
 
-    // Call EZKL CLI
+    // Call EZKL CLI. Rehearsal proofs in warm standby go to a scratch dir.
     let tx_hash_cli = tx_hash.clone();
-    let proof_result =
-        tokio::task::spawn_blocking(move || run_ezkl_pipeline(&tx_hash_cli)).await??;
+    let ezkl_retries = settings.ezkl_retries;
+    let prove_dir = if client.is_armed() {
+        crate::artifacts::PROVE_DIR
+    } else {
+        crate::artifacts::STANDBY_DIR
+    };
+    // A failed stage is an ordinary skip; a missing prover is an error
+    let proof_result = match prover
+        .run(move || run_ezkl_pipeline(&tx_hash_cli, ezkl_retries, prove_dir))
+        .instrument(info_span!("proof"))
+        .await
+    {
+        Ok(Ok(())) => true,
+        Ok(Err(e @ SentinelError::ProofFailed { .. })) => {
+            error!("ZK proof for {} failed: {}", tx_hash, e);
+            false
+        }
+        Ok(Err(e)) | Err(e) => {
+            release_claim().await;
+            return Err(e.into());
+        }
+    };
     info!("ZK Proof generated for {} : {}", proof_result, tx_hash);
-    if proof_result {
+    if let Some(elapsed) = settings.overdue(tx.received_at).filter(|_| proof_result) {
+        release_claim().await;
+        report_too_slow(&ui_sender, &tx_hash, elapsed, "submission");
+        return Ok(Some(SkipReason::TooSlow));
+    }
+    if proof_result
+        && !coalescer
+            .lock()
+            .await
+            .commit(&tx.from, current_block, &tx_hash)
+    {
+        // The claim stays with the more confident transaction that took over
+        info!(
+            "Predator {} was taken over by a more confident tx in block {}. Not submitting.",
+            predator_addr, current_block
+        );
+        return Ok(Some(SkipReason::Coalesced));
+    }
+    let outcome = if proof_result {
         // Update Stats: ZK Proofs
         {
             let mut stats_guard = stats.lock().await;
@@ -357,37 +2078,116 @@ where
         info!("ZK Proof generated for {}", tx_hash);
 
         // Submit to Chain
-        let prove_dir = "assets/prove";
+        if settings.publish_latest_proof && prove_dir == crate::artifacts::PROVE_DIR {
+            let hash = tx_hash.clone();
+            match tokio::task::spawn_blocking(move || {
+                crate::artifacts::publish_latest(std::path::Path::new(prove_dir), &hash)
+            })
+            .await
+            {
+                Ok(Ok(())) => {}
+                Ok(Err(e)) => {
+                    tracing::warn!("Failed to publish latest proof for {}: {}", tx_hash, e)
+                }
+                Err(e) => tracing::warn!("Latest proof task panicked: {}", e),
+            }
+        }
         let calldata_path = format!("{}/calldata_{}.bytes", prove_dir, tx_hash);
         let witness_path = format!("{}/witness_{}.json", prove_dir, tx_hash);
 
         // Read proof and witness
         match (
             extract_proof_from_calldata(&calldata_path),
-            extract_public_output(&witness_path),
+            extract_public_output(
+                &witness_path,
+                settings.public_input_layout,
+                settings.expected_public_inputs,
+            ),
         ) {
             (Ok(proof_bytes), Ok(public_inputs)) => {
                 let bot_address = Address::from_str(&tx.from).unwrap_or_default();
+                let proof_hash = crate::audit::keccak(&proof_bytes);
+                let proof_size = proof_bytes.len();
                 match client
-                    .submit_detection(bot_address, proof_bytes, public_inputs)
+                    .submit_detection(bot_address, action, proof_bytes, public_inputs)
+                    .instrument(info_span!("submission"))
                     .await
                 {
-                    Ok(tx_hash_chain) => {
-                        info!("On-chain submission success: {}", tx_hash_chain);
-                        let _ =
-                            ui_sender.send(UiMessage::Log(format!("Trapped: {}", tx_hash_chain)));
+                    Ok(receipt) => {
+                        let tx_hash_chain = receipt.hash.clone();
+                        let cost = TrapCost {
+                            proof_bytes: proof_size,
+                            gas_used: receipt.gas_used,
+                            cost_wei: receipt.cost_wei(),
+                        };
+                        info!(
+                            "On-chain submission success: {} (gas used {}, cost {} wei)",
+                            tx_hash_chain, cost.gas_used, cost.cost_wei
+                        );
+                        let _ = ui_sender.send(UiMessage::Log(match action {
+                            TrapAction::Trap => format!("Trapped: {}", tx_hash_chain),
+                            TrapAction::Flag => format!("Flagged: {}", tx_hash_chain),
+                        }));
+                        let _ = ui_sender.send(UiMessage::TrapCost(tx_hash.clone(), cost));
+                        {
+                            let mut stats_guard = stats.lock().await;
+                            stats_guard.trap_gas_spent_wei += cost.cost_wei;
+                            let stats_copy = (*stats_guard).clone();
+                            let _ = ui_sender.send(UiMessage::StatsUpdate(stats_copy));
+                        }
+
+                        if let Some(path) = settings.audit_log_path.clone() {
+                            let mut entry = AuditEntry {
+                                timestamp: chrono::Utc::now(),
+                                bot: bot_address.to_string(),
+                                confidence: probability,
+                                model_hash: format!("0x{}", hex::encode(model_hash)),
+                                proof_hash: format!("0x{}", hex::encode(proof_hash)),
+                                chain_tx: tx_hash_chain.clone(),
+                                reorged: false,
+                            };
+                            if let Some(journal) = &journal {
+                                journal.lock().await.audited(&tx_hash, &mut entry);
+                            }
+                            match tokio::task::spawn_blocking(move || {
+                                crate::audit::append(&path, &entry)
+                            })
+                            .await
+                            {
+                                Ok(Ok(())) => {}
+                                Ok(Err(e)) => error!("Failed to write audit log: {:?}", e),
+                                Err(e) => error!("Audit log task panicked: {}", e),
+                            }
+                        }
+
+                        // A flag leaves the bot unmarked, so a later, more
+                        // confident detection can still trap it
+                        if action == TrapAction::Flag {
+                            release_claim().await;
+                            info!(
+                                "Flagged {} on-chain (proof recorded, not marked as predator)",
+                                bot_address
+                            );
+                            return Ok(None);
+                        }
 
                         // 5. POST-VERIFICATION
-                        // Wait a moment for indexing if needed (Anvil is instant usually)
-                        // Verify state
-                        match client.is_predator(bot_address).await {
+                        // Poll a few times: chains with propagation delay lag the receipt
+                        match verify_marked(
+                            &client,
+                            bot_address,
+                            settings.post_verify_attempts,
+                            settings.post_verify_delay_ms,
+                        )
+                        .await
+                        {
                             Ok(true) => {
                                 let msg = format!("SUCCESS: Address {} is officially marked as Predator in contract.", bot_address);
                                 info!("{}", msg);
                                 let _ = ui_sender.send(UiMessage::Log(msg));
                             }
                             Ok(false) => {
-                                let msg = format!("WARNING: Tx succeeded but {} is NOT marked as Predator yet (Pending indexing?).", bot_address);
+                                let msg = format!("WARNING: Tx succeeded but {} is NOT marked as Predator after {} checks.", bot_address, settings.post_verify_attempts.max(1));
                                 tracing::warn!("{}", msg);
                                 let _ = ui_sender.send(UiMessage::Log(msg));
                             }
@@ -395,26 +2195,637 @@ where
                                 tracing::error!("Failed to verify on-chain status: {}", e);
                             }
                         }
+                        None
+                    }
+                    Err(SentinelError::Disarmed) => {
+                        info!(
+                            "Warm standby: proof for {} ready, submission withheld",
+                            tx_hash
+                        );
+                        let _ = ui_sender.send(UiMessage::Log(format!(
+                            "Not armed: {} proven, not submitted",
+                            bot_address
+                        )));
+                        Some(SkipReason::Disarmed)
+                    }
+                    Err(e @ SentinelError::SubmissionQueueFull { .. }) => {
+                        let (_, dropped) = client.submission_queue().unwrap_or_default();
+                        error!(
+                            "On-chain submission for {} dropped: {} ({} dropped so far)",
+                            tx_hash, e, dropped
+                        );
+                        let _ = ui_sender.send(UiMessage::Log(format!(
+                            "Trap Dropped: {} ({} so far)",
+                            e, dropped
+                        )));
+                        Some(SkipReason::SubmitQueueFull)
                     }
                     Err(e) => {
-                        error!("On-chain submission failed: {}", e);
+                        error!("On-chain submission failed ({}): {}", e.kind(), e);
+                        // Refused before sending: not the endpoint's fault
+                        if !matches!(
+                            e,
+                            SentinelError::ProofTooLarge { .. }
+                                | SentinelError::TooManyPublicInputs { .. }
+                        ) {
+                            let _ = ui_sender.send(UiMessage::RpcError(RpcCall::Submit));
+                        }
                         let _ = ui_sender.send(UiMessage::Log(format!("Trap Failed: {}", e)));
+                        Some(SkipReason::SubmitFailed)
                     }
                 }
             }
-            (Err(e), _) => error!("Failed to read proof: {}", e),
-            (_, Err(e)) => error!("Failed to extract public inputs: {}", e),
+            (Err(e), _) => {
+                error!("Failed to read proof: {}", e);
+                Some(SkipReason::ProofFailed)
+            }
+            (_, Err(e)) => {
+                error!("Failed to extract public inputs: {}", e);
+                Some(SkipReason::ProofFailed)
+            }
         }
     } else {
         let _ = ui_sender.send(UiMessage::ProcessingUpdate(ProcessingStage::Error(
             tx_hash.clone(),
             "EZKL failed".to_string(),
         )));
+        Some(SkipReason::ProofFailed)
+    };
+
+    // Failed attempts must not block a retry on the next sighting
+    if outcome.is_some() {
+        release_claim().await;
+    }
+
+    Ok(outcome)
+}
+
+/// Log and surface a detection abandoned at `stage` for missing the deadline
+fn report_too_slow(ui_sender: &UiSender, tx_hash: &str, elapsed: Duration, stage: &str) {
+    tracing::warn!(
+        "Too slow: {} is {}ms past mempool receipt, abandoning before {}",
+        tx_hash,
+        elapsed.as_millis(),
+        stage
+    );
+    let _ = ui_sender.send(UiMessage::ProcessingUpdate(ProcessingStage::Error(
+        tx_hash.to_string(),
+        format!("Too slow ({}ms)", elapsed.as_millis()),
+    )));
+}
+
+/// Polls `is_predator` up to `attempts` times, `delay_ms` apart, returning
+/// as soon as the bot shows up as marked.
+async fn verify_marked<P>(
+    client: &crate::network::SentinelClient<P>,
+    bot_address: Address,
+    attempts: u32,
+    delay_ms: u64,
+) -> Result<bool>
+where
+    P: Provider<PubSubFrontend, alloy::network::Ethereum> + Clone + 'static,
+{
+    let attempts = attempts.max(1);
+    for attempt in 1..=attempts {
+        if client.is_predator(bot_address).await? {
+            return Ok(true);
+        }
+        if attempt < attempts {
+            info!(
+                "{} not marked yet (check {}/{}), retrying in {}ms",
+                bot_address, attempt, attempts, delay_ms
+            );
+            tokio::time::sleep(std::time::Duration::from_millis(delay_ms)).await;
+        }
+    }
+    Ok(false)
+}
+
+/// Whether `tx_hash` has a receipt. Lookup failures count as not mined, so
+/// an unreachable RPC never costs a trap.
+async fn is_mined<P>(provider: &P, tx_hash: &str) -> bool
+where
+    P: Provider<PubSubFrontend>,
+{
+    let Ok(hash) = TxHash::from_str(tx_hash) else {
+        return false;
+    };
+    match provider.get_transaction_receipt(hash).await {
+        Ok(receipt) => receipt.is_some(),
+        Err(e) => {
+            tracing::debug!("Receipt lookup failed for {}: {}", tx_hash, e);
+            false
+        }
+    }
+}
+
+/// Gas the node expects `tx` to use, and whether the estimate reverted.
+///
+/// A revert means the node simulated the tx and it would fail (common for
+/// failed sandwich attempts), which is a signal; a transport error is not.
+/// Other estimate failures fall back to 70% of the limit and are reported
+/// to `ui_sender`.
+#[instrument(skip_all, name = "gas_estimate")]
+async fn estimate_gas_used<P>(
+    provider: Option<&P>,
+    tx: &PendingTransaction,
+    ui_sender: Option<&UiSender>,
+) -> (f32, bool)
+where
+    P: Provider<PubSubFrontend>,
+{
+    // Prepare Transaction Request for Gas Estimation
+    let from_addr = Address::from_str(&tx.from).unwrap_or_default();
+    let to_addr = tx.to.as_ref().and_then(|t| Address::from_str(t).ok());
+    let value_u256 = U256::from(tx.value);
+
+    let mut tx_req = TransactionRequest::default()
+        .from(from_addr)
+        .value(value_u256)
+        .input(tx.input.clone().into());
+
+    if let Some(addr) = to_addr {
+        tx_req = tx_req.to(addr);
+    }
+
+    let estimate = match provider {
+        Some(provider) => Some(provider.estimate_gas(&tx_req).await),
+        None => None,
+    };
+    match estimate {
+        Some(Ok(gas)) => (gas as f32, false),
+        Some(Err(e)) if is_revert_error(&e) => {
+            info!("Gas estimate REVERTED for {}: {}", tx.hash, e);
+            (tx.gas_limit as f32 * 0.7, true)
+        }
+        Some(Err(e)) => {
+            tracing::debug!("Gas estimate failed for {}: {}", tx.hash, e);
+            if let Some(ui_sender) = ui_sender {
+                let _ = ui_sender.send(UiMessage::RpcError(RpcCall::Estimate));
+            }
+            (tx.gas_limit as f32 * 0.7, false)
+        }
+        None => {
+            // Fallback to limit or simple ratio
+            (tx.gas_limit as f32 * 0.7, false) // Assume 70% usage if estimation fails
+        }
+    }
+}
+
+/// True when the node evaluated the call and reported a revert, as opposed
+/// to a transport/timeout failure that says nothing about the transaction.
+pub fn is_revert_error(err: &RpcError<TransportErrorKind>) -> bool {
+    match err {
+        RpcError::ErrorResp(payload) => {
+            // Geth/Reth use code 3 for reverts with data; others only say so in the message
+            payload.code == 3 || payload.message.to_lowercase().contains("revert")
+        }
+        _ => false,
+    }
+}
+
+/// Turns a normalized feature vector into a predator probability.
+///
+/// `process_transaction` only sees this trait, so its decision flow can be
+/// driven by a fixed score in tests instead of an ONNX model.
+pub trait Scorer: Send {
+    fn score(&mut self, features: &[f32; 6]) -> Result<f32, SentinelError>;
+
+    /// Keccak of the model behind the scores, recorded in the audit log
+    fn hash(&self) -> [u8; 32];
+}
+
+/// The scorer shared by all processing tasks; a reload swaps it in place
+type SharedScorer = std::sync::Arc<Mutex<Box<dyn Scorer>>>;
+
+/// ONNX session plus the keccak of the file it was loaded from
+struct Model {
+    session: Session,
+    hash: [u8; 32],
+    output_index: Option<usize>,
+    class_index: usize,
+}
+
+impl Scorer for Model {
+    fn score(&mut self, features: &[f32; 6]) -> Result<f32, SentinelError> {
+        run_inference(
+            &mut self.session,
+            features,
+            self.output_index,
+            self.class_index,
+        )
+    }
+
+    fn hash(&self) -> [u8; 32] {
+        self.hash
+    }
+}
+
+impl Model {
+    /// Load `path` and check it fits before it scores anything: outputs
+    /// must match the configured probability selection, a 6-feature input
+    /// must score, and a hash pinned by the on-chain policy must match.
+    ///
+    /// Normalization (`MEANS`/`SCALES`) is compiled in, so a model whose
+    /// metadata names a different scaler is refused: it needs a rebuild,
+    /// not a reload. A model without scaler metadata loads with a warning.
+    fn load(path: &str, settings: &ProcessorSettings) -> Result<Self> {
+        let mut session = load_session(path, settings.inference_provider)?;
+        let metadata = session
+            .metadata()
+            .map_err(|e| eyre::eyre!("Failed to read metadata of model {}: {}", path, e))?;
+        let scaler_known = check_scaler(
+            metadata.custom(SCALER_MEANS_KEY).as_deref(),
+            metadata.custom(SCALER_SCALES_KEY).as_deref(),
+        )
+        .wrap_err_with(|| format!("Model {} was trained with another feature scaler", path))?;
+        drop(metadata);
+        if !scaler_known {
+            tracing::warn!(
+                "Model {} has no {}/{} metadata; cannot confirm it matches the compiled-in feature scaler",
+                path,
+                SCALER_MEANS_KEY,
+                SCALER_SCALES_KEY
+            );
+        }
+        let output_types: Vec<ValueType> = session
+            .outputs()
+            .iter()
+            .map(|o| o.dtype().clone())
+            .collect();
+        validate_output_selection(
+            &output_types,
+            settings.probability_output_index,
+            settings.probability_class_index,
+        )?;
+
+        let hash = crate::audit::keccak(
+            &std::fs::read(path)
+                .wrap_err_with(|| format!("Failed to read ONNX model from {}", path))?,
+        );
+        if let Some(expected) = settings.expected_model_hash {
+            if expected != hash {
+                return Err(eyre::eyre!(
+                    "Model {} hash 0x{} does not match on-chain policy 0x{}",
+                    path,
+                    hex::encode(hash),
+                    hex::encode(expected)
+                ));
+            }
+        }
+
+        // A model expecting a different input shape fails here, not mid-stream
+        run_inference(
+            &mut session,
+            &[0.0; 6],
+            settings.probability_output_index,
+            settings.probability_class_index,
+        )
+        .wrap_err_with(|| format!("Model {} cannot score a 6-feature input", path))?;
+
+        Ok(Self {
+            session,
+            hash,
+            output_index: settings.probability_output_index,
+            class_index: settings.probability_class_index,
+        })
+    }
+}
+
+/// Compare a model's scaler metadata with the compiled-in `MEANS`/`SCALES`.
+/// `Ok(false)` when the model doesn't carry both keys.
+fn check_scaler(means: Option<&str>, scales: Option<&str>) -> Result<bool> {
+    let (Some(means), Some(scales)) = (means, scales) else {
+        return Ok(false);
+    };
+    for (key, value, compiled) in [
+        (SCALER_MEANS_KEY, means, &MEANS),
+        (SCALER_SCALES_KEY, scales, &SCALES),
+    ] {
+        let values = value
+            .split(',')
+            .map(|v| v.trim().parse::<f32>())
+            .collect::<Result<Vec<_>, _>>()
+            .wrap_err_with(|| format!("Malformed {} metadata {:?}", key, value))?;
+        let matches = values.len() == compiled.len()
+            && values
+                .iter()
+                .zip(compiled)
+                .all(|(v, c)| (v - c).abs() <= c.abs().max(1.0) * 1e-4);
+        if !matches {
+            return Err(eyre::eyre!(
+                "{} is {:?}, this build normalizes with {:?}",
+                key,
+                values,
+                compiled
+            ));
+        }
+    }
+    Ok(true)
+}
+
+/// Load `model_path` again and swap it in for the running model.
+///
+/// Scoring keeps the old model until the new one passes `Model::load`;
+/// returns the old and new hashes.
+async fn reload_model(
+    model: &Mutex<Box<dyn Scorer>>,
+    model_path: String,
+    settings: std::sync::Arc<ProcessorSettings>,
+) -> Result<([u8; 32], [u8; 32])> {
+    let fresh = tokio::task::spawn_blocking(move || Model::load(&model_path, &settings))
+        .await
+        .wrap_err("Model load task panicked")??;
+    let mut current = model.lock().await;
+    let old = std::mem::replace(&mut *current, Box::new(fresh));
+    Ok((old.hash(), current.hash()))
+}
+
+/// Sessions for `provider` register it ahead of the built-in CPU provider.
+/// Registration is not fatal: ONNX Runtime falls back to CPU if it fails.
+fn execution_providers(provider: InferenceProvider) -> Vec<ExecutionProviderDispatch> {
+    match provider {
+        #[cfg(feature = "cuda")]
+        InferenceProvider::Cuda => vec![ort::ep::CUDA::default().build()],
+        #[cfg(feature = "coreml")]
+        InferenceProvider::CoreMl => vec![ort::ep::CoreML::default().build()],
+        _ => Vec::new(),
+    }
+}
+
+/// The provider inference will actually run on: `requested` when this
+/// build has its feature and the ONNX Runtime library ships it, else CPU
+pub fn resolve_inference_provider(requested: InferenceProvider) -> InferenceProvider {
+    let available = match requested {
+        InferenceProvider::Cpu => return requested,
+        #[cfg(feature = "cuda")]
+        InferenceProvider::Cuda => {
+            ort::ep::ExecutionProvider::is_available(&ort::ep::CUDA::default())
+                .map_err(|e| e.to_string())
+        }
+        #[cfg(feature = "coreml")]
+        InferenceProvider::CoreMl => {
+            ort::ep::ExecutionProvider::is_available(&ort::ep::CoreML::default())
+                .map_err(|e| e.to_string())
+        }
+        #[allow(unreachable_patterns)]
+        _ => Err(format!("built without the `{}` feature", requested)),
+    };
+    match available {
+        Ok(true) => requested,
+        Ok(false) => {
+            tracing::warn!(
+                "The ONNX Runtime library has no {} execution provider. Falling back to CPU.",
+                requested
+            );
+            InferenceProvider::Cpu
+        }
+        Err(reason) => {
+            tracing::warn!(
+                "Cannot use the {} execution provider ({}). Falling back to CPU.",
+                requested,
+                reason
+            );
+            InferenceProvider::Cpu
+        }
     }
+}
 
+/// Load an ONNX model for inference
+fn load_session(model_path: &str, provider: InferenceProvider) -> Result<Session, SentinelError> {
+    let model_error = |reason: String| SentinelError::ModelLoad {
+        path: model_path.to_string(),
+        reason,
+    };
+    Session::builder()
+        .map_err(|e| model_error(e.to_string()))?
+        .with_optimization_level(GraphOptimizationLevel::Level3)
+        .map_err(|e| model_error(e.to_string()))?
+        .with_intra_threads(1)
+        .map_err(|e| model_error(e.to_string()))?
+        .with_execution_providers(execution_providers(provider))
+        .map_err(|e| model_error(e.to_string()))?
+        .commit_from_file(model_path)
+        .map_err(|e| model_error(e.to_string()))
+}
+
+/// Deterministic per-transaction sampling: the same hash is always in or out
+fn in_shadow_sample(tx_hash: &str, rate: f32) -> bool {
+    let bucket = u16::from_str_radix(
+        tx_hash.get(tx_hash.len().saturating_sub(4)..).unwrap_or(""),
+        16,
+    )
+    .unwrap_or(0);
+    (bucket as f32) < rate * 65536.0
+}
+
+/// Check the configured probability output against the model's declared outputs.
+///
+/// With no `output_index` the auto-detect path is used and only needs one
+/// output. An explicit index must exist and be a tensor; when its last
+/// dimension is static, `class_index` must fall inside it.
+fn validate_output_selection(
+    outputs: &[ValueType],
+    output_index: Option<usize>,
+    class_index: usize,
+) -> Result<()> {
+    if outputs.is_empty() {
+        return Err(eyre::eyre!("Model declares no outputs"));
+    }
+    let Some(index) = output_index else {
+        return Ok(());
+    };
+    let output = outputs.get(index).ok_or_else(|| {
+        eyre::eyre!(
+            "PROBABILITY_OUTPUT_INDEX={} but the model has {} output(s)",
+            index,
+            outputs.len()
+        )
+    })?;
+    let shape = output.tensor_shape().ok_or_else(|| {
+        eyre::eyre!(
+            "Model output {} is not a tensor ({:?}); pick a tensor output",
+            index,
+            output
+        )
+    })?;
+    if let Some(&classes) = shape.last() {
+        if classes > 0 && class_index as i64 >= classes {
+            return Err(eyre::eyre!(
+                "PROBABILITY_CLASS_INDEX={} but model output {} has {} class(es)",
+                class_index,
+                index,
+                classes
+            ));
+        }
+    }
     Ok(())
 }
 
+/// Element `index` of a numeric output tensor (f32 scores or i64 labels)
+fn read_output(output: &DynValue, index: usize) -> Option<f32> {
+    if let Ok((_, data)) = output.try_extract_tensor::<f32>() {
+        data.get(index).copied()
+    } else if let Ok((_, data)) = output.try_extract_tensor::<i64>() {
+        data.get(index).map(|&v| v as f32)
+    } else {
+        None
+    }
+}
+
+/// Scores one normalized feature vector; returns the predator probability.
+///
+/// `output_index` / `class_index` pick the score explicitly. Without an
+/// output index the agent auto-detects: sklearn-style exports emit
+/// `[label, probabilities]`, so output 1 is read at `class_index` when it
+/// has that many entries, otherwise output 0 is read as the label/score.
+fn run_inference(
+    session: &mut Session,
+    features: &[f32; 6],
+    output_index: Option<usize>,
+    class_index: usize,
+) -> Result<f32, SentinelError> {
+    let inference_error = |e: &dyn std::fmt::Display| SentinelError::Inference(e.to_string());
+
+    // Input shape: [1, 6] - Model expects 6 features.
+    let input_tensor =
+        Array2::from_shape_vec((1, 6), features.to_vec()).map_err(|e| inference_error(&e))?;
+    let input_value =
+        ort::value::Value::from_array(input_tensor.into_dyn()).map_err(|e| inference_error(&e))?;
+
+    // Dynamically get the first input name
+    let input_name = session.inputs()[0].name().to_string();
+    let inputs = ort::inputs![
+        input_name => input_value,
+    ];
+
+    let outputs = session.run(inputs).map_err(|e| inference_error(&e))?;
+
+    let val = match output_index {
+        Some(index) => read_output(&outputs[index], class_index).ok_or_else(|| {
+            SentinelError::Inference(format!(
+                "Model output {} has no class {} score",
+                index, class_index
+            ))
+        })?,
+        None => {
+            let probabilities = if outputs.len() >= 2 {
+                read_output(&outputs[1], class_index)
+            } else {
+                None
+            };
+            probabilities
+                .or_else(|| read_output(&outputs[0], 0))
+                .ok_or_else(|| {
+                    SentinelError::Inference("No model output holds a readable score".to_string())
+                })?
+        }
+    };
+    Ok(val)
+}
+
+/// Heuristic label for why a transaction looks predatory.
+///
+/// A reverted estimate on a swap target is the classic failed sandwich leg;
+/// a priority fee more than 1σ above the training mean suggests a gas-war
+/// frontrun. Everything else is generic MEV.
+fn classify_reason(
+    tx: &PendingTransaction,
+    normalized: &[f32; 6],
+    estimate_reverted: bool,
+) -> DetectionReason {
+    if estimate_reverted && tx.target != TxTarget::Other {
+        DetectionReason::SandwichPattern
+    } else if normalized[1] > 1.0 {
+        DetectionReason::HighGasFrontrun
+    } else {
+        DetectionReason::GenericMEV
+    }
+}
+
+/// One transaction's path through scoring, as reported by `explain`
+#[derive(Debug, Clone)]
+pub struct Explanation {
+    /// Model inputs in `FEATURE_NAMES` order, before and after scaling
+    pub raw: [f32; 6],
+    pub normalized: [f32; 6],
+    /// Features clamped to `FEATURE_CLAMP_SIGMA` before inference
+    pub clamped: Vec<&'static str>,
+    pub estimate_reverted: bool,
+    pub model_hash: [u8; 32],
+    /// Set when the live pipeline would not score this transaction at all
+    pub unscored: Option<SkipReason>,
+    pub decision: DecisionTrace,
+    pub contributions: Vec<FeatureContribution>,
+}
+
+/// Score `tx` once, the way `process_transaction` would, without touching
+/// stats or the chain. Sender averages, approvals and gas escalations need
+/// live mempool history, so the decision uses the plain reason threshold.
+pub async fn explain_transaction<P>(
+    tx: &PendingTransaction,
+    tx_index: usize,
+    block_number: u64,
+    provider: Option<&P>,
+    model_path: &str,
+    settings: &ProcessorSettings,
+    extractor: &dyn FeatureExtractor,
+) -> Result<Explanation>
+where
+    P: Provider<PubSubFrontend>,
+{
+    let mut model = Model::load(model_path, settings)?;
+    let (estimated_gas_used, estimate_reverted) = estimate_gas_used(provider, tx, None).await;
+    let ctx = ChainContext {
+        tx_index,
+        estimated_gas_used,
+        estimate_reverted,
+        block_number,
+    };
+    let raw = extractor.extract(tx, &ctx);
+    let mut normalized = normalize_features(&raw);
+    let clamped = clamp_features(&mut normalized, settings.feature_clamp_sigma);
+
+    let probability = model.score(&normalized)?;
+    let reason = classify_reason(tx, &normalized, estimate_reverted);
+    let contributions =
+        explain_prediction(&normalized, |f| model.score(f).map_err(eyre::Report::from))?;
+
+    Ok(Explanation {
+        raw: raw.to_array(),
+        normalized,
+        clamped,
+        estimate_reverted,
+        model_hash: model.hash(),
+        unscored: settings.prefilter(tx),
+        decision: settings.decide(probability, None, reason, false, false),
+        contributions,
+    })
+}
+
+/// Approximate per-feature importance: shift each normalized feature by ±1σ
+/// (±1.0 after normalization), re-score, and report half the probability
+/// swing. Sorted by absolute impact, largest first.
+fn explain_prediction(
+    features: &[f32; 6],
+    mut score: impl FnMut(&[f32; 6]) -> Result<f32>,
+) -> Result<Vec<FeatureContribution>> {
+    let mut contributions = Vec::with_capacity(FEATURE_NAMES.len());
+    for (i, name) in FEATURE_NAMES.iter().enumerate() {
+        let mut up = *features;
+        up[i] += 1.0;
+        let mut down = *features;
+        down[i] -= 1.0;
+
+        contributions.push(FeatureContribution {
+            feature: name,
+            delta: (score(&up)? - score(&down)?) / 2.0,
+        });
+    }
+    contributions.sort_by(|a, b| b.delta.abs().total_cmp(&a.delta.abs()));
+    Ok(contributions)
+}
+
 fn normalize_features(features: &FeatureVector) -> [f32; 6] {
     let arr = features.to_array();
     let mut normalized = [0.0; 6];
@@ -430,11 +2841,74 @@ fn normalize_features(features: &FeatureVector) -> [f32; 6] {
     normalized
 }
 
-/// Runs the EZKL CLI pipeline
-fn run_ezkl_pipeline(tx_hash: &str) -> Result<bool> {
-    // Ensure assets/prove exists
-    let prove_dir = "assets/prove";
-    std::fs::create_dir_all(prove_dir).wrap_err("Failed to create assets/prove directory")?;
+/// Startup guard against `to_array`, `MEANS`/`SCALES` and `FEATURE_NAMES`
+/// drifting apart. Each field is set by name so that it normalizes to a
+/// distinct value (its 1-based position in `FEATURE_NAMES`). If any mapping
+/// disagrees, the wrong value lands in that slot and startup fails, instead
+/// of the model silently scoring shuffled inputs.
+pub fn check_feature_order() -> Result<()> {
+    let mut features = FeatureVector {
+        tx_index: f32::NAN,
+        gas_price_gwei: f32::NAN,
+        priority_fee_gwei: f32::NAN,
+        gas_used: f32::NAN,
+        native_value: f32::NAN,
+        gas_usage_ratio: f32::NAN,
+    };
+    for (i, name) in FEATURE_NAMES.iter().enumerate() {
+        let field = match *name {
+            "tx_index" => &mut features.tx_index,
+            "gas_price_gwei" => &mut features.gas_price_gwei,
+            "priority_fee_gwei" => &mut features.priority_fee_gwei,
+            "gas_used" => &mut features.gas_used,
+            "native_value" => &mut features.native_value,
+            "gas_usage_ratio" => &mut features.gas_usage_ratio,
+            other => eyre::bail!("FEATURE_NAMES lists unknown feature `{}`", other),
+        };
+        *field = MEANS[i] + (i + 1) as f32 * SCALES[i];
+    }
+
+    let normalized = normalize_features(&features);
+    for (i, (name, value)) in FEATURE_NAMES.iter().zip(normalized).enumerate() {
+        let expected = (i + 1) as f32;
+        if (value - expected).abs() > 1e-3 {
+            eyre::bail!(
+                "Feature order mismatch: input {} ({}) normalized to {} instead of {}. \
+                 FeatureVector::to_array, MEANS/SCALES and FEATURE_NAMES must use the \
+                 training order.",
+                i,
+                name,
+                value,
+                expected
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Clamp each normalized feature to ±`sigma` (0 = off), returning the names
+/// of the features that were out of range
+fn clamp_features(normalized: &mut [f32; 6], sigma: f32) -> Vec<&'static str> {
+    if sigma <= 0.0 {
+        return Vec::new();
+    }
+    let mut clamped = Vec::new();
+    for (value, name) in normalized.iter_mut().zip(FEATURE_NAMES) {
+        if value.abs() > sigma {
+            *value = value.clamp(-sigma, sigma);
+            clamped.push(name);
+        }
+    }
+    clamped
+}
+
+/// Runs the EZKL CLI pipeline, writing the artifacts to `prove_dir`
+fn run_ezkl_pipeline(tx_hash: &str, retries: u32, prove_dir: &str) -> Result<(), SentinelError> {
+    // Ensure the artifact directory exists
+    std::fs::create_dir_all(prove_dir).map_err(|e| SentinelError::ProofFailed {
+        stage: "Proof directory setup",
+        reason: e.to_string(),
+    })?;
 
     // Note: In a real app, you would generate a unique input.json per tx
     // For now we use the static one for demo/testing
@@ -443,8 +2917,9 @@ fn run_ezkl_pipeline(tx_hash: &str) -> Result<bool> {
     let proof_file = format!("{}/vanguard_{}.proof", prove_dir, tx_hash);
 
     // 1. Generate Witness
-    let witness_output = Command::new("ezkl")
-        .args([
+    run_ezkl_stage(
+        "Witness generation",
+        &[
             "gen-witness",
             "-D",
             input_file,
@@ -452,21 +2927,14 @@ fn run_ezkl_pipeline(tx_hash: &str) -> Result<bool> {
             "assets/network.ezkl",
             "-O",
             &witness_file,
-        ])
-        .output()
-        .wrap_err("Failed to execute ezkl gen-witness")?;
-
-    if !witness_output.status.success() {
-        error!(
-            "Witness generation failed: {}",
-            String::from_utf8_lossy(&witness_output.stderr)
-        );
-        return Ok(false);
-    }
+        ],
+        retries,
+    )?;
 
     // 2. Generate Proof
-    let prove_output = Command::new("ezkl")
-        .args([
+    run_ezkl_stage(
+        "Proof generation",
+        &[
             "prove",
             "-W",
             &witness_file,
@@ -478,42 +2946,97 @@ fn run_ezkl_pipeline(tx_hash: &str) -> Result<bool> {
             &proof_file,
             "--srs-path",
             "assets/kzg.srs",
-        ])
-        .output()
-        .wrap_err("Failed to execute ezkl prove")?;
+        ],
+        retries,
+    )?;
 
-    if !prove_output.status.success() {
-        error!(
-            "Proof generation failed: {}",
-            String::from_utf8_lossy(&prove_output.stderr)
-        );
-        return Ok(false);
-    }
+    // 3. Encode Proof to EVM Calldata
+    let calldata_file = format!("{}/calldata_{}.bytes", prove_dir, tx_hash);
+    run_ezkl_stage(
+        "Proof encoding",
+        &[
+            "encode-evm-calldata",
+            "--proof-path",
+            &proof_file,
+            "--calldata-path",
+            &calldata_file,
+        ],
+        retries,
+    )?;
+
+    // let _ = std::fs::remove_file(&proof_file); // Keep proof for now
+
+    Ok(())
+}
+
+/// Run one `ezkl` subcommand, retrying up to `retries` times when the
+/// failure looks transient. A stage that fails for good is `ProofFailed`;
+/// failing to launch `ezkl` at all is `ProverUnavailable`.
+fn run_ezkl_stage(stage: &'static str, args: &[&str], retries: u32) -> Result<(), SentinelError> {
+    let mut last_error = String::new();
+    for attempt in 1..=retries + 1 {
+        let output = Command::new("ezkl").args(args).output().map_err(|e| {
+            SentinelError::ProverUnavailable {
+                command: args[0].to_string(),
+                reason: e.to_string(),
+            }
+        })?;
 
-    // 3. Encode Proof to EVM Calldata
-    let calldata_file = format!("{}/calldata_{}.bytes", prove_dir, tx_hash);
-    let encode_output = Command::new("ezkl")
-        .args([
-            "encode-evm-calldata",
-            "--proof-path",
-            &proof_file,
-            "--calldata-path",
-            &calldata_file,
-        ])
-        .output()
-        .wrap_err("Failed to execute ezkl encode-evm-calldata")?;
+        if output.status.success() {
+            if attempt > 1 {
+                info!("{} succeeded on attempt {}", stage, attempt);
+            }
+            return Ok(());
+        }
 
-    if !encode_output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        // Killed by a signal (e.g. the OOM killer) rather than exiting with an error
+        let transient = output.status.code().is_none() || is_transient_ezkl_failure(&stderr);
         error!(
-            "Proof encoding failed: {}",
-            String::from_utf8_lossy(&encode_output.stderr)
+            "{} failed (attempt {}/{}, {}): {}",
+            stage,
+            attempt,
+            retries + 1,
+            if transient {
+                "transient"
+            } else {
+                "deterministic"
+            },
+            stderr
         );
-        return Ok(false);
+        last_error = stderr.trim().to_string();
+        if !transient {
+            break;
+        }
+        if attempt <= retries {
+            std::thread::sleep(std::time::Duration::from_millis(
+                EZKL_RETRY_DELAY_MS * attempt as u64,
+            ));
+        }
     }
+    Err(SentinelError::ProofFailed {
+        stage,
+        reason: last_error,
+    })
+}
 
-    // let _ = std::fs::remove_file(&proof_file); // Keep proof for now
-
-    Ok(true)
+/// Whether EZKL's stderr points at a contention/resource problem that may
+/// clear on its own, as opposed to bad input that will fail identically
+fn is_transient_ezkl_failure(stderr: &str) -> bool {
+    const TRANSIENT_MARKERS: [&str; 10] = [
+        "resource temporarily unavailable",
+        "would block",
+        "failed to lock",
+        "locked",
+        "too many open files",
+        "out of memory",
+        "cannot allocate memory",
+        "interrupted",
+        "device or resource busy",
+        "timed out",
+    ];
+    let stderr = stderr.to_lowercase();
+    TRANSIENT_MARKERS.iter().any(|m| stderr.contains(m))
 }
 
 fn extract_proof_from_calldata(calldata_path: &str) -> Result<Vec<u8>> {
@@ -552,10 +3075,16 @@ fn extract_proof_from_calldata(calldata_path: &str) -> Result<Vec<u8>> {
     Ok(data[proof_start..proof_start + proof_len].to_vec())
 }
 
-fn extract_public_output(witness_path: &str) -> Result<Vec<U256>> {
+/// Read the witness public values and arrange them as the verifier expects.
+///
+/// `expected_count`, when set, must match the final number of values.
+fn extract_public_output(
+    witness_path: &str,
+    layout: PublicInputLayout,
+    expected_count: Option<usize>,
+) -> Result<Vec<U256>> {
     let content = std::fs::read_to_string(witness_path)?;
     let json: serde_json::Value = serde_json::from_str(&content)?;
-    let mut public_inputs = Vec::new();
 
     // Helper to parse value
     let parse_val = |val: &serde_json::Value| -> Result<U256> {
@@ -579,37 +3108,1049 @@ fn extract_public_output(witness_path: &str) -> Result<Vec<U256>> {
     };
 
     // Helper to extract from array of arrays
-    let mut extract_from = |key: &str, source: &serde_json::Value| -> Result<()> {
+    let extract_from = |key: &str, source: &serde_json::Value| -> Result<Vec<U256>> {
+        let mut values = Vec::new();
         if let Some(field) = source.get(key) {
             if let Some(arr) = field.as_array() {
                 for inner in arr {
                     if let Some(inner_arr) = inner.as_array() {
                         for val in inner_arr {
-                            public_inputs.push(parse_val(val)?);
+                            values.push(parse_val(val)?);
                         }
                     } else {
                         // Handle flat array case if structure differs (some versions)
-                        public_inputs.push(parse_val(inner)?);
+                        values.push(parse_val(inner)?);
                     }
                 }
             }
         }
-        Ok(())
+        Ok(values)
     };
 
     // Prefer pretty_elements which has 0x prefixed hex strings
-    if let Some(pretty) = json.get("pretty_elements") {
-        extract_from("inputs", pretty)?;
-        extract_from("outputs", pretty)?;
-    } else {
-        // Fallback to root
-        extract_from("inputs", &json)?;
-        extract_from("outputs", &json)?;
-    }
+    let source = json.get("pretty_elements").unwrap_or(&json);
+    let inputs = extract_from("inputs", source)?;
+    let outputs = extract_from("outputs", source)?;
+
+    let public_inputs: Vec<U256> = match layout {
+        PublicInputLayout::InputsThenOutputs => inputs.into_iter().chain(outputs).collect(),
+        PublicInputLayout::OutputsThenInputs => outputs.into_iter().chain(inputs).collect(),
+        PublicInputLayout::InputsOnly => inputs,
+        PublicInputLayout::OutputsOnly => outputs,
+    };
 
     if public_inputs.is_empty() {
         return Err(eyre::eyre!("No public inputs found in witness.json"));
     }
+    if let Some(expected) = expected_count {
+        if public_inputs.len() != expected {
+            return Err(eyre::eyre!(
+                "Witness yields {} public inputs with layout {}, verifier expects {}",
+                public_inputs.len(),
+                layout,
+                expected
+            ));
+        }
+    }
 
     Ok(public_inputs)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lists::AddressLists;
+    use crate::test_support::{mock_provider, MockNode};
+
+    /// Hand-built fixtures in the ABI layout of EZKL's `encode-evm-calldata` /
+    /// `gen-witness` output: `verifyProof(bytes proof, uint256[] instances)` with
+    /// a 1472-byte proof and 6 public inputs + 1 output (one negative input
+    /// wrapped into the BN254 field). The proof bytes are filler, not a real
+    /// proof, so tests only check how the ABI is taken apart.
+    fn fixture(name: &str) -> String {
+        format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)
+    }
+
+    fn expected_public_inputs() -> Vec<U256> {
+        vec![
+            U256::from(0x0b),
+            U256::from(0x09),
+            U256::from(0x2c),
+            U256::from_str("0x30644e72e131a029b85045b68181585d2833e84879b9709143e1f593effffff0")
+                .unwrap(),
+            U256::from(0x04),
+            U256::from(0x5a),
+            U256::from(0x3e),
+        ]
+    }
+
+    #[test]
+    fn extracts_proof_bytes_from_calldata_fixture() {
+        let path = fixture("calldata_fixture.bytes");
+        let proof = extract_proof_from_calldata(&path).unwrap();
+
+        // The proof is the `bytes` payload right after its length word, and
+        // stops short of the instances array that follows it
+        let data = std::fs::read(&path).unwrap();
+        let len_offset = 4 + u32::from_be_bytes(data[32..36].try_into().unwrap()) as usize;
+        let instances_offset = 4 + u32::from_be_bytes(data[64..68].try_into().unwrap()) as usize;
+        assert_eq!(proof.len(), 1472);
+        assert_eq!(proof, &data[len_offset + 32..len_offset + 32 + 1472]);
+        assert_eq!(len_offset + 32 + proof.len(), instances_offset);
+    }
+
+    #[test]
+    fn extracts_public_inputs_from_witness_fixture() {
+        let public_inputs = extract_public_output(
+            &fixture("witness_fixture.json"),
+            PublicInputLayout::default(),
+            Some(7),
+        )
+        .unwrap();
+
+        assert_eq!(public_inputs, expected_public_inputs());
+    }
+
+    #[test]
+    fn calldata_instances_match_witness_public_inputs() {
+        // The instances array follows the proof in the calldata; it must carry
+        // exactly what we extract from the witness, or the verifier rejects.
+        let data = std::fs::read(fixture("calldata_fixture.bytes")).unwrap();
+        let instances_offset = 4 + u32::from_be_bytes(data[64..68].try_into().unwrap()) as usize;
+        let count =
+            U256::from_be_slice(&data[instances_offset..instances_offset + 32]).to::<usize>();
+        let instances: Vec<U256> = (0..count)
+            .map(|i| {
+                let start = instances_offset + 32 + i * 32;
+                U256::from_be_slice(&data[start..start + 32])
+            })
+            .collect();
+
+        let public_inputs = extract_public_output(
+            &fixture("witness_fixture.json"),
+            PublicInputLayout::default(),
+            Some(7),
+        )
+        .unwrap();
+        assert_eq!(instances, public_inputs);
+    }
+
+    #[test]
+    fn classifies_revert_errors_separately_from_transport_errors() {
+        let revert = |code: i64, message: &str| -> RpcError<TransportErrorKind> {
+            let payload = serde_json::json!({ "code": code, "message": message });
+            RpcError::ErrorResp(serde_json::from_value(payload).unwrap())
+        };
+
+        assert!(is_revert_error(&revert(
+            3,
+            "execution reverted: TooLittleReceived"
+        )));
+        assert!(is_revert_error(&revert(-32000, "Execution Reverted")));
+        assert!(!is_revert_error(&revert(
+            -32000,
+            "insufficient funds for gas"
+        )));
+        assert!(!is_revert_error(&TransportErrorKind::backend_gone()));
+    }
+
+    #[test]
+    fn rejects_truncated_calldata() {
+        let dir = std::env::temp_dir().join("beetrap-truncated-calldata");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("calldata_truncated.bytes");
+
+        let data = std::fs::read(fixture("calldata_fixture.bytes")).unwrap();
+        std::fs::write(&path, &data[..200]).unwrap();
+
+        assert!(extract_proof_from_calldata(path.to_str().unwrap()).is_err());
+    }
+
+    #[test]
+    fn explanation_ranks_features_by_probability_swing() {
+        // Linear stand-in for the model: weights per normalized feature
+        let weights = [0.02, -0.10, 0.0, 0.05, 0.01, -0.03];
+        let score = |f: &[f32; 6]| -> Result<f32> {
+            Ok(0.5 + f.iter().zip(weights).map(|(x, w)| x * w).sum::<f32>())
+        };
+
+        let ranked = explain_prediction(&[0.3; 6], score).unwrap();
+
+        let order: Vec<&str> = ranked.iter().map(|c| c.feature).collect();
+        assert_eq!(
+            order,
+            [
+                "priority_fee_gwei",
+                "gas_used",
+                "tx_index",
+                "gas_price_gwei",
+                "native_value",
+                "gas_usage_ratio"
+            ]
+        );
+        assert!((ranked[0].delta + 0.10).abs() < 1e-6);
+    }
+
+    #[test]
+    fn feature_cache_evicts_oldest_entries() {
+        let mut cache = FeatureCache::default();
+        for i in 0..=FEATURE_CACHE_SIZE {
+            cache.insert(format!("0x{:x}", i), [i as f32; 6]);
+        }
+
+        assert_eq!(cache.get("0x0"), None);
+        assert_eq!(cache.get("0x1"), Some([1.0; 6]));
+        assert_eq!(cache.features.len(), FEATURE_CACHE_SIZE);
+    }
+
+    #[test]
+    fn reorgs_mark_recorded_detections_once() {
+        let detection = |hash: &str, block: u64| Detection {
+            bot_address: "0xbot".to_string(),
+            tx_hash: hash.to_string(),
+            confidence: 0.97,
+            detected_at: chrono::Utc::now(),
+            latency: Duration::from_millis(1),
+            reason: DetectionReason::GenericMEV,
+            raw_tx: None,
+            block,
+            reorged: false,
+            features: None,
+        };
+        let audit = || AuditEntry {
+            timestamp: chrono::Utc::now(),
+            bot: "0xbot".to_string(),
+            confidence: 0.97,
+            model_hash: "0x00".to_string(),
+            proof_hash: "0x00".to_string(),
+            chain_tx: "0xtrap".to_string(),
+            reorged: false,
+        };
+        let mut journal = ReorgJournal::default();
+        journal.record(detection("0xa", 10));
+        journal.record(detection("0xb", 12));
+        journal.record(detection("0xc", 12));
+        journal.audited("0xb", &mut audit());
+
+        let reorg = Reorg {
+            first_block: 11,
+            depth: 2,
+        };
+        let flagged = journal.flag(reorg);
+        assert_eq!(flagged.len(), 2);
+        assert!(flagged.iter().all(|(d, _)| d.reorged && d.block == 12));
+        assert!(flagged[0].1.as_ref().unwrap().reorged);
+        assert!(flagged[1].1.is_none());
+        assert!(journal.flag(reorg).is_empty());
+
+        // Trapped after the reorg: the audit entry is written already marked
+        let mut late = audit();
+        journal.audited("0xc", &mut late);
+        assert!(late.reorged);
+    }
+
+    #[test]
+    fn models_with_another_scaler_are_refused() {
+        let join = |values: &[f32; 6]| {
+            values
+                .iter()
+                .map(|v| v.to_string())
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+        let (means, scales) = (join(&MEANS), join(&SCALES));
+        assert!(check_scaler(Some(&means), Some(&scales)).unwrap());
+        assert!(!check_scaler(None, Some(&scales)).unwrap());
+
+        let mut retrained = SCALES;
+        retrained[3] *= 1.5;
+        assert!(check_scaler(Some(&means), Some(&join(&retrained))).is_err());
+        assert!(check_scaler(Some("1,2,3"), Some(&scales)).is_err());
+        assert!(check_scaler(Some("not,numbers"), Some(&scales)).is_err());
+    }
+
+    #[test]
+    fn parses_reason_thresholds_and_ignores_bad_entries() {
+        let map = crate::types::parse_reason_thresholds(
+            "HighGasFrontrun=0.7, genericmev=0.85,Unknown=0.5,SandwichPattern=1.5,KnownBotPattern",
+            ModelTask::Classification,
+        );
+
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.get(&DetectionReason::HighGasFrontrun), Some(&0.7));
+        assert_eq!(map.get(&DetectionReason::GenericMEV), Some(&0.85));
+    }
+
+    #[test]
+    fn regression_thresholds_are_profits() {
+        let map = crate::types::parse_reason_thresholds(
+            "SandwichPattern=1.5,HighGasFrontrun=0.02,GenericMEV=-1",
+            ModelTask::Regression,
+        );
+
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.get(&DetectionReason::SandwichPattern), Some(&1.5));
+        assert_eq!(map.get(&DetectionReason::HighGasFrontrun), Some(&0.02));
+    }
+
+    #[test]
+    fn impact_weights_default_to_one() {
+        let settings = ProcessorSettings {
+            reason_impact_weights: crate::types::parse_reason_weights(
+                "SandwichPattern=2.5, genericmev=0.5,HighGasFrontrun=-1,Unknown=3",
+            ),
+            ..Default::default()
+        };
+        assert_eq!(settings.reason_impact_weights.len(), 2);
+        assert_eq!(
+            settings.impact_weight(DetectionReason::SandwichPattern),
+            2.5
+        );
+        assert_eq!(settings.impact_weight(DetectionReason::GenericMEV), 0.5);
+        assert_eq!(
+            settings.impact_weight(DetectionReason::HighGasFrontrun),
+            1.0
+        );
+    }
+
+    fn pending_tx() -> crate::types::PendingTransaction {
+        crate::types::PendingTransaction {
+            hash: "0x01".to_string(),
+            from: "0x02".to_string(),
+            to: None,
+            value: 0,
+            gas_price: None,
+            max_fee_per_gas: None,
+            max_priority_fee_per_gas: None,
+            priority_fee: 0,
+            gas_limit: 21_000,
+            input: Vec::new(),
+            received_at: std::time::Instant::now(),
+            chain_id: 1,
+            pool_key: None,
+            max_fee_per_blob_gas: None,
+            blob_count: 0,
+            approves: None,
+            target_address: None,
+            nonce: 0,
+            gas_escalation: None,
+            raw: None,
+            target: TxTarget::Router,
+        }
+    }
+
+    #[test]
+    fn classifies_reverted_swaps_and_high_tips() {
+        let mut tx = pending_tx();
+        let high_tip = [0.0, 2.5, 0.0, 0.0, 0.0, 0.0];
+
+        assert_eq!(
+            classify_reason(&tx, &high_tip, true),
+            DetectionReason::SandwichPattern
+        );
+        assert_eq!(
+            classify_reason(&tx, &high_tip, false),
+            DetectionReason::HighGasFrontrun
+        );
+        tx.target = TxTarget::Other;
+        assert_eq!(
+            classify_reason(&tx, &[0.0; 6], true),
+            DetectionReason::GenericMEV
+        );
+    }
+
+    #[test]
+    fn only_calls_without_calldata_are_plain_transfers() {
+        let mut tx = pending_tx();
+        assert!(!tx.is_plain_transfer(), "contract creation");
+
+        tx.to = Some("0x03".to_string());
+        assert!(tx.is_plain_transfer());
+
+        tx.input = vec![0xa9, 0x05, 0x9c, 0xbb];
+        assert!(!tx.is_plain_transfer());
+    }
+
+    #[test]
+    fn oversized_calldata_is_not_scored() {
+        let settings = ProcessorSettings {
+            max_input_bytes: 4,
+            ..Default::default()
+        };
+        let mut tx = pending_tx();
+        tx.input = vec![0; 4];
+        assert_eq!(settings.prefilter(&tx), None);
+
+        tx.input.push(0);
+        assert_eq!(settings.prefilter(&tx), Some(SkipReason::OversizedInput));
+
+        // 0 = no limit
+        assert!(!tx.input_exceeds(0));
+    }
+
+    #[test]
+    fn priority_fee_floor_spares_legacy_transactions() {
+        let mut tx = pending_tx();
+        tx.gas_price = Some(1_000_000_000);
+        assert!(!tx.tips_below(2_000_000_000), "legacy tx");
+
+        tx.max_priority_fee_per_gas = Some(100_000_000);
+        assert!(tx.tips_below(2_000_000_000));
+        tx.max_priority_fee_per_gas = Some(3_000_000_000);
+        assert!(!tx.tips_below(2_000_000_000));
+    }
+
+    #[test]
+    fn shadow_sample_is_deterministic_by_hash() {
+        assert!(in_shadow_sample("0xabc0000", 1.0));
+        assert!(!in_shadow_sample("0xabcffff", 0.5));
+        assert!(in_shadow_sample("0xabc1000", 0.5));
+        assert!(!in_shadow_sample("0xabc0000", 0.0));
+    }
+
+    #[test]
+    fn reorders_and_validates_public_inputs() {
+        let witness = fixture("witness_fixture.json");
+        let expected = expected_public_inputs();
+
+        let outputs_first =
+            extract_public_output(&witness, PublicInputLayout::OutputsThenInputs, None).unwrap();
+        assert_eq!(outputs_first[0], expected[6]);
+        assert_eq!(&outputs_first[1..], &expected[..6]);
+
+        let outputs_only =
+            extract_public_output(&witness, PublicInputLayout::OutputsOnly, Some(1)).unwrap();
+        assert_eq!(outputs_only, vec![expected[6]]);
+
+        assert!(
+            extract_public_output(&witness, PublicInputLayout::InputsThenOutputs, Some(6)).is_err()
+        );
+    }
+
+    #[test]
+    fn output_selection_is_validated_against_model_outputs() {
+        use ort::value::{Shape, SymbolicDimensions, TensorElementType};
+        let tensor = |ty, dims: Vec<i64>| ValueType::Tensor {
+            ty,
+            dimension_symbols: SymbolicDimensions::empty(dims.len()),
+            shape: Shape::from(dims),
+        };
+        // sklearn-style export: label [N], probabilities [N, 2]
+        let outputs = vec![
+            tensor(TensorElementType::Int64, vec![-1]),
+            tensor(TensorElementType::Float32, vec![-1, 2]),
+        ];
+
+        assert!(validate_output_selection(&outputs, None, 1).is_ok());
+        assert!(validate_output_selection(&outputs, Some(1), 1).is_ok());
+        assert!(validate_output_selection(&outputs, Some(2), 1).is_err());
+        assert!(validate_output_selection(&outputs, Some(1), 2).is_err());
+        // Dynamic class dimension can't be checked until inference
+        assert!(validate_output_selection(&outputs, Some(0), 5).is_ok());
+        assert!(validate_output_selection(&[], None, 1).is_err());
+
+        let zipmap = vec![ValueType::Sequence(Box::new(tensor(
+            TensorElementType::Float32,
+            vec![2],
+        )))];
+        assert!(validate_output_selection(&zipmap, Some(0), 1).is_err());
+    }
+
+    #[test]
+    fn normalization_follows_training_feature_order() {
+        // Each input sits a different number of std devs from its training
+        // mean, so any reordering between `to_array` and MEANS/SCALES shows up
+        let features = FeatureVector {
+            tx_index: 12.284,          // mean 54.8847, std 85.2015      -> -0.5
+            gas_price_gwei: 8.2076,    // mean 0.96868, std 7.23893      ->  1.0
+            priority_fee_gwei: 14.957, // mean 0.75662, std 7.10012      ->  2.0
+            gas_used: 1_005_869.4,     // mean 570146.967, std 871444.9  ->  0.5
+            native_value: 35.231,      // mean 0.48075, std 11.58339     ->  3.0
+            gas_usage_ratio: 0.38288,  // mean 0.57255, std 0.18968      -> -1.0
+        };
+
+        let normalized = normalize_features(&features);
+
+        let expected = [
+            ("gas_price_gwei", 1.0),
+            ("priority_fee_gwei", 2.0),
+            ("gas_usage_ratio", -1.0),
+            ("gas_used", 0.5),
+            ("native_value", 3.0),
+            ("tx_index", -0.5),
+        ];
+        for (i, (name, z)) in expected.iter().enumerate() {
+            assert_eq!(FEATURE_NAMES[i], *name);
+            assert!(
+                (normalized[i] - z).abs() < 1e-3,
+                "{} normalized to {}, expected {}",
+                name,
+                normalized[i],
+                z
+            );
+        }
+    }
+
+    #[test]
+    fn feature_order_self_check_passes() {
+        check_feature_order().unwrap();
+    }
+
+    #[test]
+    fn ezkl_failures_are_split_into_transient_and_deterministic() {
+        assert!(is_transient_ezkl_failure(
+            "Error: failed to lock file assets/kzg.srs"
+        ));
+        assert!(is_transient_ezkl_failure(
+            "memory allocation of 1073741824 bytes failed: Cannot allocate memory"
+        ));
+        assert!(is_transient_ezkl_failure(
+            "os error 11: Resource temporarily unavailable"
+        ));
+        assert!(!is_transient_ezkl_failure(
+            "Error: failed to deserialize input.json: missing field `input_data`"
+        ));
+        assert!(!is_transient_ezkl_failure(""));
+    }
+
+    #[test]
+    fn deadline_only_applies_when_configured() {
+        let received_at = Instant::now() - Duration::from_millis(50);
+        let mut settings = ProcessorSettings::default();
+        assert!(settings.overdue(received_at).is_none());
+
+        settings.pipeline_deadline_ms = 10;
+        assert!(settings.overdue(received_at).unwrap() >= Duration::from_millis(50));
+        assert!(settings.overdue(Instant::now()).is_none());
+    }
+
+    #[test]
+    fn decision_trace_names_the_deciding_modifier() {
+        let settings = ProcessorSettings {
+            confidence_threshold: 0.9,
+            reason_thresholds: HashMap::from([(DetectionReason::SandwichPattern, 0.8)]),
+            ..Default::default()
+        };
+
+        let global = settings.decide(0.85, None, DetectionReason::GenericMEV, false, false);
+        assert!(!global.trap);
+        assert_eq!(global.decided_by, DecisionModifier::GlobalThreshold);
+
+        let by_reason = settings.decide(0.85, None, DetectionReason::SandwichPattern, false, false);
+        assert!(by_reason.trap);
+        assert_eq!(by_reason.threshold, 0.8);
+        assert_eq!(by_reason.decided_by, DecisionModifier::ReasonThreshold);
+
+        let approved = settings.decide(0.86, None, DetectionReason::GenericMEV, true, true);
+        assert!(approved.trap);
+        assert_eq!(approved.base_threshold, 0.9);
+        assert_eq!(approved.decided_by, DecisionModifier::ApprovalContext);
+
+        let escalated = settings.decide(0.86, None, DetectionReason::GenericMEV, false, true);
+        assert!(escalated.trap);
+        assert_eq!(escalated.decided_by, DecisionModifier::GasEscalation);
+
+        // A cluster partner in the block lowers the bar, unless a stronger modifier decided
+        let clustered = settings.coordinated(global);
+        assert!(clustered.trap);
+        assert_eq!(clustered.decided_by, DecisionModifier::Cluster);
+        assert_eq!(
+            settings.coordinated(escalated).decided_by,
+            DecisionModifier::GasEscalation
+        );
+    }
+
+    #[test]
+    fn regression_compares_expected_profit() {
+        let settings = ProcessorSettings {
+            confidence_threshold: 0.9,
+            model_task: ModelTask::Regression,
+            profit_threshold_eth: 2.0,
+            ..Default::default()
+        };
+
+        // A profit far above 1.0 is not read as a probability
+        let profitable = settings.decide(3.5, None, DetectionReason::GenericMEV, false, false);
+        assert!(profitable.trap);
+        assert_eq!(profitable.base_threshold, 2.0);
+
+        let small = settings.decide(1.5, None, DetectionReason::GenericMEV, false, false);
+        assert!(!small.trap);
+
+        // Context discounts scale with the profit threshold
+        let escalated = settings.decide(1.95, None, DetectionReason::GenericMEV, false, true);
+        assert_eq!(
+            escalated.threshold,
+            2.0 * (1.0 - ESCALATION_THRESHOLD_DISCOUNT)
+        );
+        assert!(escalated.trap);
+    }
+
+    #[test]
+    fn detections_below_the_trap_score_are_flagged() {
+        let settings = ProcessorSettings {
+            confidence_threshold: 0.8,
+            trap_score: 0.9,
+            ..Default::default()
+        };
+        let flag = settings.decide(0.85, None, DetectionReason::GenericMEV, false, false);
+        assert!(flag.trap);
+        assert_eq!(settings.trap_action(&flag), TrapAction::Flag);
+        assert!(!TrapAction::Flag.status());
+
+        let trap = settings.decide(0.95, None, DetectionReason::GenericMEV, false, false);
+        assert_eq!(settings.trap_action(&trap), TrapAction::Trap);
+
+        let mut denylisted = flag.clone();
+        denylisted.decided_by = DecisionModifier::Denylist;
+        assert_eq!(settings.trap_action(&denylisted), TrapAction::Trap);
+
+        // Off by default: every detection traps
+        let default = ProcessorSettings::default();
+        assert_eq!(default.trap_action(&flag), TrapAction::Trap);
+    }
+
+    #[test]
+    fn baseline_warns_when_the_model_goes_quiet() {
+        let mut tx = pending_tx();
+        tx.priority_fee = 5_000_000_000;
+        assert!(baseline_flags(&tx, 2_000_000_000));
+        assert!(!baseline_flags(&tx, 10_000_000_000));
+        tx.target = TxTarget::Other;
+        assert!(!baseline_flags(&tx, 2_000_000_000));
+
+        let mut monitor = BaselineMonitor::new(4, 3);
+        // The model agrees at least once: no warning
+        for model_flag in [false, true, false] {
+            assert_eq!(monitor.observe(true, model_flag), None);
+        }
+        assert_eq!(monitor.observe(true, false), None);
+
+        // All safe while the rule fires 3 of 4 times: warn at the window end
+        for rule_flag in [true, false, true] {
+            assert_eq!(monitor.observe(rule_flag, false), None);
+        }
+        assert_eq!(
+            monitor.observe(true, false),
+            Some(Disagreement {
+                scored: 4,
+                rule_flags: 3
+            })
+        );
+
+        // Quiet traffic is not a disagreement
+        for _ in 0..4 {
+            assert_eq!(monitor.observe(false, false), None);
+        }
+    }
+
+    #[test]
+    fn sender_average_needs_repeated_high_scores() {
+        let settings = ProcessorSettings {
+            confidence_threshold: 0.8,
+            ..Default::default()
+        };
+        let mut scores = AddressScores::new(0.5, 2);
+
+        // A lone spike from a new sender stays below the threshold
+        let spike = scores.update("0xbot", 0.95);
+        assert_eq!(spike.samples, 1);
+        let decision = settings.decide(
+            0.95,
+            Some(spike.smoothed),
+            DetectionReason::GenericMEV,
+            false,
+            false,
+        );
+        assert!(!decision.trap);
+
+        // Persistently high scores accumulate until it does
+        let mut latest = spike;
+        for _ in 0..3 {
+            latest = scores.update("0xbot", 0.95);
+        }
+        assert_eq!(latest.samples, 4);
+        assert!(
+            settings
+                .decide(
+                    0.95,
+                    Some(latest.smoothed),
+                    DetectionReason::GenericMEV,
+                    false,
+                    false
+                )
+                .trap
+        );
+
+        // Bounded: a third sender evicts the oldest
+        scores.update("0xa", 0.1);
+        scores.update("0xb", 0.1);
+        assert_eq!(scores.scores.len(), 2);
+        assert_eq!(scores.update("0xbot", 0.95).samples, 1);
+    }
+
+    /// Fixed score in place of the ONNX model; `None` fails every inference
+    struct MockScorer(Option<f32>);
+
+    impl Scorer for MockScorer {
+        fn score(&mut self, _features: &[f32; 6]) -> Result<f32, SentinelError> {
+            self.0
+                .ok_or_else(|| SentinelError::Inference("mock failure".to_string()))
+        }
+
+        fn hash(&self) -> [u8; 32] {
+            [7; 32]
+        }
+    }
+
+    /// A receipt for `MINED_TX`
+    fn mined_receipt(method: &str) -> Option<serde_json::Value> {
+        (method == "eth_getTransactionReceipt").then(|| {
+            serde_json::json!({
+                "type": "0x2",
+                "status": "0x1",
+                "transactionHash": MINED_TX,
+                "transactionIndex": "0x0",
+                "blockHash": format!("0x{}", "11".repeat(32)),
+                "blockNumber": "0xa",
+                "from": format!("0x{}", "22".repeat(20)),
+                "to": format!("0x{}", "33".repeat(20)),
+                "cumulativeGasUsed": "0x5208",
+                "gasUsed": "0x5208",
+                "effectiveGasPrice": "0x1",
+                "contractAddress": null,
+                "logs": [],
+                "logsBloom": format!("0x{}", "00".repeat(256)),
+            })
+        })
+    }
+
+    const MINED_TX: &str = "0x4444444444444444444444444444444444444444444444444444444444444444";
+
+    #[tokio::test]
+    async fn receipts_mark_transactions_mined() {
+        let mined = mock_provider(MockNode(mined_receipt)).await;
+        assert!(is_mined(&mined, MINED_TX).await);
+        assert!(!is_mined(&mined, "0x01").await);
+
+        let pending = mock_provider(MockNode(|_| Some(serde_json::Value::Null))).await;
+        assert!(!is_mined(&pending, MINED_TX).await);
+
+        // An unreachable RPC never costs a trap
+        let down = mock_provider(MockNode(|_| None)).await;
+        assert!(!is_mined(&down, MINED_TX).await);
+    }
+
+    #[tokio::test]
+    async fn mined_detections_skip_proving() {
+        let node = mock_provider(MockNode(mined_receipt)).await;
+        let client = crate::network::SentinelClient::new(
+            node.clone(),
+            Address::ZERO,
+            Address::ZERO,
+            U256::from(1),
+            vec![Address::ZERO],
+            usize::MAX,
+            usize::MAX,
+            crate::ledger::SubmissionLedger::load(None).unwrap(),
+        )
+        .unwrap();
+        let mut tx = pending_tx();
+        tx.hash = MINED_TX.to_string();
+        let (skipped, messages, stats) = run_swap(
+            tx,
+            MockScorer(Some(0.95)),
+            AddressLists::default(),
+            Some(std::sync::Arc::new(client)),
+            Some(std::sync::Arc::new(node)),
+        )
+        .await;
+
+        assert_eq!(skipped, Some(SkipReason::AlreadyMined));
+        assert_eq!(stats.total_detected, 1);
+        assert_eq!(stats.zk_proofs_generated, 0);
+        assert!(messages.iter().any(|m| matches!(
+            m,
+            UiMessage::Log(line) if line.contains("already mined")
+        )));
+    }
+
+    /// Run one swap through `process_transaction` in scoring-only mode
+    async fn score_swap(
+        scorer: MockScorer,
+        lists: AddressLists,
+    ) -> (Option<SkipReason>, Vec<UiMessage>, SentinelStats) {
+        run_swap(pending_tx(), scorer, lists, None, None).await
+    }
+
+    /// Run `tx`, made a swap, through `process_transaction`; without a client
+    /// it is scoring only
+    async fn run_swap(
+        mut tx: crate::types::PendingTransaction,
+        scorer: MockScorer,
+        lists: AddressLists,
+        client: Option<
+            std::sync::Arc<crate::network::SentinelClient<RootProvider<PubSubFrontend>>>,
+        >,
+        mined_check: Option<std::sync::Arc<RootProvider<PubSubFrontend>>>,
+    ) -> (Option<SkipReason>, Vec<UiMessage>, SentinelStats) {
+        let (ui_sender, mut ui_rx) = tokio::sync::mpsc::unbounded_channel();
+        let ui_sender = UiSender::new(ui_sender);
+        let stats = std::sync::Arc::new(Mutex::new(SentinelStats::default()));
+        let settings = ProcessorSettings {
+            confidence_threshold: 0.8,
+            ..Default::default()
+        };
+        tx.to = Some("0x03".to_string());
+        tx.input = vec![0x35, 0x93, 0x56, 0x4c];
+
+        let skipped = process_transaction(
+            tx,
+            ui_sender,
+            std::sync::Arc::new(Mutex::new(Box::new(scorer) as Box<dyn Scorer>)),
+            None,
+            stats.clone(),
+            None::<std::sync::Arc<RootProvider<PubSubFrontend>>>,
+            client,
+            std::sync::Arc::new(settings),
+            std::sync::Arc::new(Mutex::new(FeatureCache::default())),
+            std::sync::Arc::new(Mutex::new(ApprovalTracker::new(Duration::from_secs(60)))),
+            None,
+            std::sync::Arc::new(Mutex::new(BlockCoalescer::default())),
+            None,
+            std::sync::Arc::new(std::sync::RwLock::new(lists)),
+            std::sync::Arc::new(ProverPool::new(1).unwrap()),
+            crate::features::by_name("standard").unwrap(),
+            None,
+            None,
+            None,
+            None,
+            mined_check,
+            None,
+            None,
+            None,
+            0,
+            0,
+        )
+        .await
+        .unwrap();
+
+        let mut messages = Vec::new();
+        while let Ok(message) = ui_rx.try_recv() {
+            messages.push(message);
+        }
+        let stats = stats.lock().await.clone();
+        (skipped, messages, stats)
+    }
+
+    #[tokio::test]
+    async fn watchdog_reports_panics_stalls_and_clean_exits() {
+        let fresh = std::sync::Mutex::new(Instant::now());
+        let watchdog = Duration::from_secs(30);
+
+        let mut closed = tokio::spawn(async {});
+        assert_eq!(
+            supervise(&mut closed, &fresh, watchdog).await,
+            LoopExit::Closed
+        );
+
+        let mut panicked = tokio::spawn(async { panic!("session poisoned") });
+        assert_eq!(
+            supervise(&mut panicked, &fresh, watchdog).await,
+            LoopExit::Failed("panicked: session poisoned".to_string())
+        );
+
+        // A loop that stopped beating is aborted
+        let stale = std::sync::Mutex::new(Instant::now());
+        let mut stuck = tokio::spawn(std::future::pending::<()>());
+        let stall_after = Duration::from_millis(1);
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        let exit = supervise(&mut stuck, &stale, stall_after).await;
+        assert!(matches!(exit, LoopExit::Stalled(silent) if silent > stall_after));
+        assert!(stuck.await.unwrap_err().is_cancelled());
+    }
+
+    #[tokio::test]
+    async fn heartbeat_needs_the_model_session() {
+        let model: SharedScorer = std::sync::Arc::new(Mutex::new(Box::new(MockScorer(Some(0.5)))));
+        let started = Instant::now();
+        let heartbeat = std::sync::Arc::new(std::sync::Mutex::new(started));
+        let mut probe = None;
+
+        // A session held by a stuck inference: no beat gets through
+        let session = model.clone().lock_owned().await;
+        for _ in 0..3 {
+            probe_session(&model, &heartbeat, &mut probe);
+            tokio::task::yield_now().await;
+        }
+        assert_eq!(*heartbeat.lock().unwrap(), started);
+
+        // Once released, the pending probe beats
+        drop(session);
+        probe.take().unwrap().await.unwrap();
+        assert!(*heartbeat.lock().unwrap() > started);
+    }
+
+    #[tokio::test]
+    async fn score_above_threshold_is_a_detection() {
+        let (skipped, messages, stats) =
+            score_swap(MockScorer(Some(0.95)), AddressLists::default()).await;
+
+        // No client: detected but not trapped
+        assert_eq!(skipped, Some(SkipReason::ScoringOnly));
+        assert_eq!(stats.total_detected, 1);
+        assert!(messages.iter().any(|m| matches!(
+            m,
+            UiMessage::NewDetection(d) if d.confidence == 0.95
+        )));
+    }
+
+    #[tokio::test]
+    async fn score_below_threshold_is_safe() {
+        let (skipped, messages, stats) =
+            score_swap(MockScorer(Some(0.4)), AddressLists::default()).await;
+
+        assert_eq!(skipped, Some(SkipReason::BelowThreshold));
+        assert_eq!(stats.total_detected, 0);
+        assert!(!messages
+            .iter()
+            .any(|m| matches!(m, UiMessage::NewDetection(_))));
+    }
+
+    #[tokio::test]
+    async fn failed_inference_is_never_safe() {
+        let (skipped, messages, _) = score_swap(MockScorer(None), AddressLists::default()).await;
+
+        assert_eq!(skipped, Some(SkipReason::InferenceFailed));
+        assert!(messages
+            .iter()
+            .any(|m| matches!(m, UiMessage::InferenceFailed(_))));
+    }
+
+    #[tokio::test]
+    async fn lists_override_the_model() {
+        let mut lists = AddressLists::default();
+        lists.add(ListKind::Allow, &pending_tx().from).unwrap();
+        let (skipped, _, _) = score_swap(MockScorer(Some(0.99)), lists).await;
+        assert_eq!(skipped, Some(SkipReason::Allowlisted));
+
+        let mut lists = AddressLists::default();
+        lists.add(ListKind::Deny, &pending_tx().from).unwrap();
+        let (skipped, _, stats) = score_swap(MockScorer(Some(0.1)), lists).await;
+        assert_eq!(skipped, Some(SkipReason::ScoringOnly));
+        assert_eq!(stats.total_detected, 1);
+    }
+
+    #[test]
+    fn coalesces_a_senders_detections_within_a_block() {
+        let mut coalescer = BlockCoalescer::default();
+
+        let claim = || true;
+
+        assert_eq!(
+            coalescer.offer("0xbot", 10, "0xa", 0.9, claim),
+            Coalesce::Lead
+        );
+        assert_eq!(
+            coalescer.offer("0xbot", 10, "0xb", 0.85, claim),
+            Coalesce::Coalesced
+        );
+        assert_eq!(
+            coalescer.offer("0xother", 10, "0xc", 0.8, claim),
+            Coalesce::Lead
+        );
+
+        // A more confident tx takes over while 0xa is still proving
+        assert_eq!(
+            coalescer.offer("0xbot", 10, "0xd", 0.97, || panic!("claim is inherited")),
+            Coalesce::TakeOver
+        );
+        assert!(!coalescer.commit("0xbot", 10, "0xa"));
+        assert!(coalescer.commit("0xbot", 10, "0xd"));
+
+        // Once submitting, nothing takes over
+        assert_eq!(
+            coalescer.offer("0xbot", 10, "0xe", 0.99, claim),
+            Coalesce::Coalesced
+        );
+        // A new block starts a new trap, if the ledger allows it
+        assert_eq!(
+            coalescer.offer("0xbot", 11, "0xf", 0.5, || false),
+            Coalesce::Claimed
+        );
+        assert_eq!(
+            coalescer.offer("0xbot", 11, "0xf", 0.5, claim),
+            Coalesce::Lead
+        );
+    }
+
+    #[test]
+    fn only_the_claim_owner_releases_it() {
+        let mut coalescer = BlockCoalescer::default();
+        coalescer.offer("0xbot", 10, "0xa", 0.9, || true);
+        coalescer.offer("0xbot", 10, "0xb", 0.95, || true);
+
+        // 0xa was replaced: its failure must not free 0xb's claim
+        assert!(!coalescer.release("0xbot", 10, "0xa"));
+        assert!(coalescer.release("0xbot", 10, "0xb"));
+
+        // With the leader gone, the next detection claims afresh
+        assert_eq!(
+            coalescer.offer("0xbot", 10, "0xc", 0.5, || false),
+            Coalesce::Claimed
+        );
+    }
+
+    #[test]
+    fn clamps_outliers_to_the_configured_sigma() {
+        let mut features = [120.0, 0.5, -9.0, 0.0, 5.0, -0.2];
+        assert!(clamp_features(&mut features.clone(), 0.0).is_empty());
+
+        let clamped = clamp_features(&mut features, 5.0);
+        assert_eq!(clamped, vec!["gas_price_gwei", "gas_usage_ratio"]);
+        assert_eq!(features, [5.0, 0.5, -5.0, 0.0, 5.0, -0.2]);
+    }
+
+    #[test]
+    fn hold_window_sees_companions_and_replacements() {
+        let start = Instant::now();
+        let mut activity = RecentActivity::default();
+        activity.record("0xbot", 7, start);
+        activity.record("0xbot", 8, start + Duration::from_millis(100));
+        activity.record("0xother", 7, start + Duration::from_millis(150));
+        assert_eq!(
+            activity.after("0xbot", 7, start),
+            HoldContext {
+                replaced: false,
+                companions: 1
+            }
+        );
+
+        activity.record("0xbot", 7, start + Duration::from_millis(200));
+        assert!(activity.after("0xbot", 7, start).replaced);
+
+        // Old entries age out
+        activity.record("0xnew", 1, start + ACTIVITY_RETENTION * 2);
+        assert_eq!(activity.seen.len(), 1);
+    }
+
+    #[test]
+    fn companions_lower_the_threshold_after_a_hold() {
+        let settings = ProcessorSettings {
+            confidence_threshold: 0.8,
+            ..Default::default()
+        };
+        let near_miss = settings.decide(0.77, None, DetectionReason::GenericMEV, false, false);
+        assert!(!near_miss.trap);
+
+        let alone = settings.after_hold(near_miss.clone(), None, 0);
+        assert!(!alone.trap);
+        let with_legs = settings.after_hold(near_miss, None, 2);
+        assert!(with_legs.trap);
+        assert_eq!(with_legs.decided_by, DecisionModifier::Companions);
+
+        // A sender average that dropped during the hold can still veto
+        let held = settings.decide(0.9, Some(0.85), DetectionReason::GenericMEV, false, false);
+        assert!(!settings.after_hold(held, Some(0.6), 1).trap);
+    }
+}
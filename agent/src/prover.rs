@@ -0,0 +1,95 @@
+//! # Prover Pool
+//!
+//! Dedicated OS threads for EZKL work. Each proof blocks for seconds; running
+//! it here instead of on Tokio's blocking pool keeps scoring responsive no
+//! matter how many detections queue up behind the prover.
+//!
+//! Thread budget: Tokio keeps one worker per core for scoring and RPC I/O;
+//! the pool adds `PROVER_THREADS` threads, each driving one `ezkl` process
+//! at a time (and `ezkl` itself proves on all cores). Jobs beyond that wait
+//! in the queue, so raise it only on machines with cores to spare.
+
+use crate::error::SentinelError;
+use std::panic::AssertUnwindSafe;
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use tokio::sync::oneshot;
+
+type Job = Box<dyn FnOnce() + Send>;
+
+pub struct ProverPool {
+    jobs: mpsc::Sender<Job>,
+    threads: usize,
+}
+
+impl ProverPool {
+    /// Start `threads` prover threads (at least one)
+    pub fn new(threads: usize) -> std::io::Result<Self> {
+        let threads = threads.max(1);
+        let (jobs, queue) = mpsc::channel::<Job>();
+        let queue = Arc::new(Mutex::new(queue));
+        for i in 0..threads {
+            let queue = queue.clone();
+            std::thread::Builder::new()
+                .name(format!("prover-{}", i))
+                .spawn(move || loop {
+                    // Guard dropped before the job runs, so idle threads can take the next one
+                    let job = match queue.lock().unwrap_or_else(|e| e.into_inner()).recv() {
+                        Ok(job) => job,
+                        Err(_) => break, // Pool dropped
+                    };
+                    // A panicking job must not shrink the pool
+                    let _ = std::panic::catch_unwind(AssertUnwindSafe(job));
+                })?;
+        }
+        Ok(Self { jobs, threads })
+    }
+
+    pub fn threads(&self) -> usize {
+        self.threads
+    }
+
+    /// Run `job` on a prover thread and wait for its result
+    pub async fn run<T, F>(&self, job: F) -> Result<T, SentinelError>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> T + Send + 'static,
+    {
+        let unavailable = |reason: &str| SentinelError::ProverUnavailable {
+            command: "pool".to_string(),
+            reason: reason.to_string(),
+        };
+        let (done, result) = oneshot::channel();
+        self.jobs
+            .send(Box::new(move || {
+                let _ = done.send(job());
+            }))
+            .map_err(|_| unavailable("prover threads stopped"))?;
+        result.await.map_err(|_| unavailable("prover job panicked"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn runs_jobs_off_runtime_and_survives_panics() {
+        let pool = ProverPool::new(1).unwrap();
+
+        let name = pool
+            .run(|| std::thread::current().name().map(str::to_string))
+            .await
+            .unwrap();
+        assert_eq!(name.as_deref(), Some("prover-0"));
+
+        let panicked = pool.run(|| -> u32 { panic!("bad witness") }).await;
+        assert!(matches!(
+            panicked,
+            Err(SentinelError::ProverUnavailable { .. })
+        ));
+
+        // The single thread is still serving jobs
+        assert_eq!(pool.run(|| 2 + 2).await.unwrap(), 4);
+    }
+}
@@ -0,0 +1,179 @@
+//! # Private Submission
+//!
+//! Sends trap transactions to a Flashbots-style relay with
+//! `eth_sendPrivateTransaction`, so the bots being trapped never see them in
+//! the public mempool. Requests carry an `X-Flashbots-Signature` from a
+//! reputation key that holds no funds.
+
+use crate::error::SentinelError;
+use alloy::eips::eip2718::Encodable2718;
+use alloy::network::{EthereumWallet, TransactionBuilder};
+use alloy::primitives::{keccak256, TxHash};
+use alloy::providers::fillers::{CachedNonceManager, NonceManager};
+use alloy::providers::Provider;
+use alloy::rpc::types::TransactionRequest;
+use alloy::signers::local::PrivateKeySigner;
+use alloy::signers::SignerSync;
+use std::str::FromStr;
+
+/// Blocks the relay may try to include a trap before it is dropped
+pub const PRIVATE_TX_MAX_BLOCKS: u64 = 25;
+
+/// Private transaction relay plus what is needed to sign for it locally
+pub struct PrivateRelay {
+    url: String,
+    http: reqwest::Client,
+    /// Signs relay requests (`X-Flashbots-Signature`); never sends transactions
+    auth: PrivateKeySigner,
+    wallet: EthereumWallet,
+    /// Shared with the provider's nonce filler, so public and private
+    /// submissions never hand out the same nonce
+    nonces: CachedNonceManager,
+}
+
+impl PrivateRelay {
+    pub fn new(
+        url: String,
+        auth: PrivateKeySigner,
+        wallet: EthereumWallet,
+        nonces: CachedNonceManager,
+    ) -> Self {
+        Self {
+            url,
+            http: reqwest::Client::new(),
+            auth,
+            wallet,
+            nonces,
+        }
+    }
+
+    /// Fill nonce, chain id, fees and gas limit for `request`.
+    ///
+    /// The result can still go to the public provider unchanged, so a
+    /// fallback reuses the nonce instead of leaving a gap.
+    pub async fn fill<P>(
+        &self,
+        provider: &P,
+        request: TransactionRequest,
+    ) -> Result<TransactionRequest, SentinelError>
+    where
+        P: Provider<alloy::pubsub::PubSubFrontend, alloy::network::Ethereum>,
+    {
+        let from = request
+            .from
+            .ok_or_else(|| SentinelError::Submission("request has no sender".to_string()))?;
+        let chain_id = provider.get_chain_id().await?;
+        let fees = provider.estimate_eip1559_fees(None).await?;
+        let mut request = request
+            .with_chain_id(chain_id)
+            .with_max_fee_per_gas(fees.max_fee_per_gas)
+            .with_max_priority_fee_per_gas(fees.max_priority_fee_per_gas);
+        let gas = provider.estimate_gas(&request).await?;
+        request.set_gas_limit(gas);
+        // Taken last: a failed estimate must not burn a nonce
+        let nonce = self.nonces.get_next_nonce(provider, from).await?;
+        Ok(request.with_nonce(nonce))
+    }
+
+    /// Sign a filled request and hand it to the relay, valid up to `max_block`
+    pub async fn send(
+        &self,
+        request: TransactionRequest,
+        max_block: u64,
+    ) -> Result<TxHash, SentinelError> {
+        let envelope = request
+            .build(&self.wallet)
+            .await
+            .map_err(|e| SentinelError::Submission(format!("signing failed: {}", e)))?;
+        let body = request_body(&envelope.encoded_2718(), max_block);
+
+        let response: serde_json::Value = self
+            .http
+            .post(&self.url)
+            .header("Content-Type", "application/json")
+            .header(
+                "X-Flashbots-Signature",
+                signature_header(&self.auth, &body)?,
+            )
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| SentinelError::RpcDisconnected(format!("private relay: {}", e)))?
+            .json()
+            .await
+            .map_err(|e| SentinelError::Submission(format!("private relay: {}", e)))?;
+
+        if let Some(error) = response.get("error") {
+            return Err(SentinelError::Submission(format!(
+                "private relay rejected trap: {}",
+                error
+            )));
+        }
+        response["result"]
+            .as_str()
+            .and_then(|hash| TxHash::from_str(hash).ok())
+            .ok_or_else(|| {
+                SentinelError::Submission(format!("private relay returned {}", response))
+            })
+    }
+}
+
+fn request_body(raw_tx: &[u8], max_block: u64) -> String {
+    serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "eth_sendPrivateTransaction",
+        "params": [{
+            "tx": format!("0x{}", hex::encode(raw_tx)),
+            "maxBlockNumber": format!("0x{:x}", max_block),
+        }],
+    })
+    .to_string()
+}
+
+/// `<address>:<EIP-191 signature of the hex keccak of the body>`
+fn signature_header(auth: &PrivateKeySigner, body: &str) -> Result<String, SentinelError> {
+    let digest = format!("0x{}", hex::encode(keccak256(body.as_bytes())));
+    let signature = auth
+        .sign_message_sync(digest.as_bytes())
+        .map_err(|e| SentinelError::Submission(format!("relay auth signing failed: {}", e)))?;
+    Ok(format!(
+        "{}:0x{}",
+        auth.address(),
+        hex::encode(signature.as_bytes())
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signs_the_exact_request_body() {
+        let auth = PrivateKeySigner::random();
+        let body = request_body(&[0x02, 0xf8], 0x1234);
+
+        let parsed: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(parsed["method"], "eth_sendPrivateTransaction");
+        assert_eq!(parsed["params"][0]["tx"], "0x02f8");
+        assert_eq!(parsed["params"][0]["maxBlockNumber"], "0x1234");
+
+        let header = signature_header(&auth, &body).unwrap();
+        let (address, signature) = header.split_once(':').unwrap();
+        assert_eq!(address, auth.address().to_string());
+
+        let signature = alloy::primitives::PrimitiveSignature::try_from(
+            hex::decode(signature.trim_start_matches("0x"))
+                .unwrap()
+                .as_slice(),
+        )
+        .unwrap();
+        let digest = format!("0x{}", hex::encode(keccak256(body.as_bytes())));
+        assert_eq!(
+            signature
+                .recover_address_from_msg(digest.as_bytes())
+                .unwrap(),
+            auth.address()
+        );
+    }
+}
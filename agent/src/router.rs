@@ -0,0 +1,159 @@
+//! # Router Calldata Decoding
+//!
+//! Best-effort decoding of Uniswap V4 PoolManager calldata so the agent knows
+//! which pool a pending transaction is touching, plus ERC-20 `approve` calls
+//! that grant the router or PoolManager an allowance (bot setup).
+
+use crate::types::{PoolKeyInfo, TxTarget};
+use alloy::primitives::Address;
+use alloy::sol;
+use alloy::sol_types::SolCall;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+// Uniswap V4 PoolManager entry points that carry a `PoolKey`
+sol! {
+    struct PoolKey {
+        address currency0;
+        address currency1;
+        uint24 fee;
+        int24 tickSpacing;
+        address hooks;
+    }
+
+    struct SwapParams {
+        bool zeroForOne;
+        int256 amountSpecified;
+        uint160 sqrtPriceLimitX96;
+    }
+
+    struct ModifyLiquidityParams {
+        int24 tickLower;
+        int24 tickUpper;
+        int256 liquidityDelta;
+        bytes32 salt;
+    }
+
+    function unlock(bytes data) external returns (bytes result);
+    function swap(PoolKey key, SwapParams params, bytes hookData) external returns (int256 swapDelta);
+    function modifyLiquidity(PoolKey key, ModifyLiquidityParams params, bytes hookData) external returns (int256 callerDelta, int256 feesAccrued);
+    function initialize(PoolKey key, uint160 sqrtPriceX96) external returns (int24 tick);
+    function donate(PoolKey key, uint256 amount0, uint256 amount1, bytes hookData) external returns (int256 delta);
+
+    // ERC-20
+    function approve(address spender, uint256 amount) external returns (bool);
+}
+
+/// Maximum `unlock` nesting we follow before giving up
+const MAX_UNLOCK_DEPTH: usize = 2;
+
+/// Decode the `PoolKey` from calldata sent directly to the V4 PoolManager.
+///
+/// `unlock(bytes)` payloads are router-defined; we only follow them when the
+/// inner bytes are themselves a PoolManager call.
+pub fn decode_pool_manager_call(input: &[u8]) -> Option<PoolKeyInfo> {
+    decode_with_depth(input, 0)
+}
+
+fn decode_with_depth(input: &[u8], depth: usize) -> Option<PoolKeyInfo> {
+    let selector: [u8; 4] = input.get(..4)?.try_into().ok()?;
+
+    let key = match selector {
+        swapCall::SELECTOR => swapCall::abi_decode(input, true).ok()?.key,
+        modifyLiquidityCall::SELECTOR => modifyLiquidityCall::abi_decode(input, true).ok()?.key,
+        initializeCall::SELECTOR => initializeCall::abi_decode(input, true).ok()?.key,
+        donateCall::SELECTOR => donateCall::abi_decode(input, true).ok()?.key,
+        unlockCall::SELECTOR if depth < MAX_UNLOCK_DEPTH => {
+            let inner = unlockCall::abi_decode(input, true).ok()?.data;
+            return decode_with_depth(&inner, depth + 1);
+        }
+        _ => return None,
+    };
+
+    Some(PoolKeyInfo {
+        currency0: key.currency0.to_string(),
+        currency1: key.currency1.to_string(),
+        fee: key.fee.to::<u32>(),
+        tick_spacing: key.tickSpacing.as_i32(),
+        hooks: key.hooks.to_string(),
+    })
+}
+
+/// Spender of an ERC-20 `approve(address,uint256)` call, if `input` is one
+pub fn decode_approve_spender(input: &[u8]) -> Option<Address> {
+    if input.get(..4)? != approveCall::SELECTOR {
+        return None;
+    }
+    Some(approveCall::abi_decode(input, true).ok()?.spender)
+}
+
+/// Senders that recently approved a monitored contract, forgotten after `window`.
+///
+/// Bots typically approve the router right before their first swap; a swap
+/// from a fresh approver is context the single-transaction model can't see.
+#[derive(Debug)]
+pub struct ApprovalTracker {
+    window: Duration,
+    approvals: HashMap<String, (Instant, TxTarget)>,
+}
+
+impl ApprovalTracker {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            approvals: HashMap::new(),
+        }
+    }
+
+    /// Remember that `owner` approved `spender` at `at`
+    pub fn record(&mut self, owner: &str, spender: TxTarget, at: Instant) {
+        self.approvals.insert(owner.to_lowercase(), (at, spender));
+    }
+
+    /// Time since `owner`'s approval and what it approved, if still within
+    /// the window at `now`. Expired entries are dropped.
+    pub fn recent(&mut self, owner: &str, now: Instant) -> Option<(Duration, TxTarget)> {
+        let window = self.window;
+        self.approvals
+            .retain(|_, (at, _)| now.saturating_duration_since(*at) <= window);
+        self.approvals
+            .get(&owner.to_lowercase())
+            .map(|(at, spender)| (now.saturating_duration_since(*at), *spender))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::U256;
+
+    #[test]
+    fn decodes_approve_spender() {
+        let spender = Address::repeat_byte(0x42);
+        let input = approveCall {
+            spender,
+            amount: U256::MAX,
+        }
+        .abi_encode();
+
+        assert_eq!(decode_approve_spender(&input), Some(spender));
+        assert_eq!(decode_approve_spender(&input[..4]), None);
+        assert_eq!(decode_approve_spender(&[0xde, 0xad, 0xbe, 0xef]), None);
+    }
+
+    #[test]
+    fn approvals_expire_after_window() {
+        let start = Instant::now();
+        let mut tracker = ApprovalTracker::new(Duration::from_secs(60));
+        tracker.record("0xAbC", TxTarget::Router, start);
+
+        let seen = tracker.recent("0xabc", start + Duration::from_secs(10));
+        assert_eq!(seen, Some((Duration::from_secs(10), TxTarget::Router)));
+        assert_eq!(tracker.recent("0xdef", start), None);
+        assert_eq!(
+            tracker.recent("0xabc", start + Duration::from_secs(61)),
+            None
+        );
+        assert!(tracker.approvals.is_empty());
+    }
+}
@@ -0,0 +1,167 @@
+//! # Trap Schedule
+//!
+//! `TRAP_SCHEDULE` limits on-chain submissions to supervised hours, e.g.
+//! `09:00-17:00,20:00-22:00` in `TRAP_SCHEDULE_TZ` (a UTC offset such as
+//! `+02:00`). A window may wrap midnight (`22:00-06:00`). Outside every
+//! window the agent keeps scanning and logging; detections are dropped, or
+//! held until the next window opens with `TRAP_SCHEDULE_FLUSH` (which needs
+//! `PIPELINE_DEADLINE_MS` or `SKIP_MINED` to expire what was mined meanwhile).
+
+use crate::types::{UiMessage, UiSender};
+use chrono::{DateTime, FixedOffset, NaiveTime, Timelike, Utc};
+use eyre::{Result, WrapErr};
+use std::fmt;
+use std::time::Duration;
+use tracing::info;
+
+const DAY_SECS: i64 = 24 * 60 * 60;
+
+/// How often the header's trap window state is refreshed
+const WATCH_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrapSchedule {
+    /// Start (inclusive) and end (exclusive) of each window, local time
+    windows: Vec<(NaiveTime, NaiveTime)>,
+    offset: FixedOffset,
+}
+
+impl TrapSchedule {
+    /// Parse `HH:MM-HH:MM` windows (comma-separated) and a `UTC` / `±HH:MM` zone
+    pub fn parse(windows: &str, tz: &str) -> Result<Self> {
+        let offset = match tz.trim() {
+            "" | "UTC" | "utc" | "Z" => FixedOffset::east_opt(0).expect("zero offset"),
+            other => other
+                .parse()
+                .map_err(|_| eyre::eyre!("Invalid TRAP_SCHEDULE_TZ: {}", other))?,
+        };
+        let windows = windows
+            .split(',')
+            .map(str::trim)
+            .filter(|w| !w.is_empty())
+            .map(|window| {
+                let (start, end) = window
+                    .split_once('-')
+                    .ok_or_else(|| eyre::eyre!("Invalid TRAP_SCHEDULE window: {}", window))?;
+                let time = |t: &str| {
+                    NaiveTime::parse_from_str(t.trim(), "%H:%M")
+                        .wrap_err_with(|| format!("Invalid TRAP_SCHEDULE window: {}", window))
+                };
+                Ok((time(start)?, time(end)?))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        if windows.is_empty() {
+            return Err(eyre::eyre!("TRAP_SCHEDULE has no windows"));
+        }
+        Ok(Self { windows, offset })
+    }
+
+    /// Whether traps may be submitted at `now`
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        let time = now.with_timezone(&self.offset).time();
+        self.windows
+            .iter()
+            .any(|&(start, end)| match start.cmp(&end) {
+                std::cmp::Ordering::Less => start <= time && time < end,
+                std::cmp::Ordering::Greater => time >= start || time < end,
+                // Same start and end: the whole day
+                std::cmp::Ordering::Equal => true,
+            })
+    }
+
+    /// Time from `now` until a window opens (zero while one is open)
+    pub fn until_active(&self, now: DateTime<Utc>) -> Duration {
+        if self.is_active(now) {
+            return Duration::ZERO;
+        }
+        let now_secs = now
+            .with_timezone(&self.offset)
+            .time()
+            .num_seconds_from_midnight() as i64;
+        let wait = self
+            .windows
+            .iter()
+            .map(|(start, _)| {
+                (start.num_seconds_from_midnight() as i64 - now_secs).rem_euclid(DAY_SECS)
+            })
+            .min()
+            .unwrap_or(0);
+        Duration::from_secs(wait as u64)
+    }
+}
+
+impl fmt::Display for TrapSchedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let windows: Vec<String> = self
+            .windows
+            .iter()
+            .map(|(start, end)| format!("{}-{}", start.format("%H:%M"), end.format("%H:%M")))
+            .collect();
+        write!(f, "{} (UTC{})", windows.join(","), self.offset)
+    }
+}
+
+/// Report the trap window state to the UI at startup and on every change
+pub fn spawn_watch(schedule: TrapSchedule, ui_sender: UiSender) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(WATCH_INTERVAL);
+        let mut last = None;
+        loop {
+            interval.tick().await;
+            let active = schedule.is_active(Utc::now());
+            if last == Some(active) {
+                continue;
+            }
+            if last.is_some() {
+                let state = if active { "opened" } else { "closed" };
+                info!("Trap window {} ({})", state, schedule);
+                let _ = ui_sender.send(UiMessage::Log(format!("Trap window {}", state)));
+            }
+            let _ = ui_sender.send(UiMessage::TrapWindow(active));
+            last = Some(active);
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(rfc3339: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(rfc3339).unwrap().into()
+    }
+
+    #[test]
+    fn windows_follow_the_offset_and_wrap_midnight() {
+        let schedule = TrapSchedule::parse("09:00-17:00, 22:00-02:00", "+02:00").unwrap();
+        assert_eq!(schedule.to_string(), "09:00-17:00,22:00-02:00 (UTC+02:00)");
+
+        // 07:30 UTC is 09:30 local
+        assert!(schedule.is_active(at("2024-05-01T07:30:00Z")));
+        assert!(!schedule.is_active(at("2024-05-01T15:00:00Z")));
+        // Past midnight local, still in the wrapped window
+        assert!(schedule.is_active(at("2024-05-01T23:30:00Z")));
+
+        // 17:00 local: the evening window opens in five hours
+        assert_eq!(
+            schedule.until_active(at("2024-05-01T15:00:00Z")),
+            Duration::from_secs(5 * 3600)
+        );
+        // 02:00 local: the morning window opens in seven hours
+        assert_eq!(
+            schedule.until_active(at("2024-05-02T00:00:00Z")),
+            Duration::from_secs(7 * 3600)
+        );
+        assert_eq!(
+            schedule.until_active(at("2024-05-01T07:30:00Z")),
+            Duration::ZERO
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_schedules() {
+        assert!(TrapSchedule::parse("9-17", "UTC").is_err());
+        assert!(TrapSchedule::parse("09:00-17:00", "Europe/Paris").is_err());
+        assert!(TrapSchedule::parse(" , ", "UTC").is_err());
+    }
+}
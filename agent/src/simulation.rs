@@ -0,0 +1,162 @@
+//! # Shadow Submissions
+//!
+//! With `SIMULATION_RPC_URL` set (an Anvil fork, Tenderly or similar), every
+//! trap submission is mirrored there as a gas estimate of the same request,
+//! from the same signer. Estimating executes the call without sending or
+//! signing anything, so the fork spends no gas. The simulated outcome is
+//! compared with the real receipt and discrepancies are logged: a revert on
+//! one side only points at a verifier, ABI or state mismatch.
+
+use crate::error::SentinelError;
+use crate::network::TrapReceipt;
+use crate::processor::is_revert_error;
+use alloy::providers::{Provider, ProviderBuilder, RootProvider, WsConnect};
+use alloy::pubsub::PubSubFrontend;
+use alloy::rpc::types::TransactionRequest;
+use alloy::transports::TransportResult;
+use eyre::{Result, WrapErr};
+use std::time::Duration;
+
+/// A simulation slower than this counts as failed; it never holds up the trap
+const SIMULATION_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Gas used may differ from the simulated estimate by this fraction
+const GAS_TOLERANCE: f64 = 0.25;
+
+/// What the simulation endpoint made of a trap request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Simulated {
+    Success {
+        gas: u128,
+    },
+    Reverted(String),
+    /// Endpoint unreachable or too slow: nothing to compare
+    Unavailable(String),
+}
+
+/// Connection to the simulation endpoint
+pub struct Simulator {
+    provider: RootProvider<PubSubFrontend>,
+}
+
+impl Simulator {
+    pub async fn connect(url: &str) -> Result<Self> {
+        let provider = ProviderBuilder::new()
+            .on_ws(WsConnect::new(url))
+            .await
+            .wrap_err_with(|| format!("Failed to connect to SIMULATION_RPC_URL {}", url))?;
+        Ok(Self { provider })
+    }
+
+    /// Execute `request` on the simulation endpoint without sending it
+    pub async fn simulate(&self, request: &TransactionRequest) -> Simulated {
+        match tokio::time::timeout(SIMULATION_TIMEOUT, self.provider.estimate_gas(request)).await {
+            Ok(estimate) => simulated(estimate),
+            Err(_) => Simulated::Unavailable(format!("timed out after {:?}", SIMULATION_TIMEOUT)),
+        }
+    }
+}
+
+/// Only a revert reported by the node says anything about the request
+fn simulated(estimate: TransportResult<u64>) -> Simulated {
+    match estimate {
+        Ok(gas) => Simulated::Success { gas: gas as u128 },
+        Err(e) if is_revert_error(&e) => Simulated::Reverted(e.to_string()),
+        Err(e) => Simulated::Unavailable(e.to_string()),
+    }
+}
+
+/// How the real submission disagrees with its simulation, if it does
+pub fn discrepancy(
+    simulated: &Simulated,
+    real: &Result<TrapReceipt, SentinelError>,
+) -> Option<String> {
+    match (simulated, real) {
+        (Simulated::Unavailable(_), _) => None,
+        (Simulated::Success { gas }, Ok(receipt)) if receipt.success => {
+            let drift = (receipt.gas_used as f64 - *gas as f64).abs() / (*gas).max(1) as f64;
+            (drift > GAS_TOLERANCE).then(|| {
+                format!(
+                    "gas used {} vs simulated {} ({:+.0}%)",
+                    receipt.gas_used,
+                    gas,
+                    (receipt.gas_used as f64 / (*gas).max(1) as f64 - 1.0) * 100.0
+                )
+            })
+        }
+        (Simulated::Success { .. }, Ok(receipt)) => Some(format!(
+            "reverted on-chain ({}) but succeeded in simulation",
+            receipt.hash
+        )),
+        (Simulated::Success { .. }, Err(e)) => Some(format!(
+            "failed on-chain ({}) but succeeded in simulation",
+            e
+        )),
+        (Simulated::Reverted(reason), Ok(receipt)) if receipt.success => Some(format!(
+            "succeeded on-chain ({}) but reverted in simulation: {}",
+            receipt.hash, reason
+        )),
+        (Simulated::Reverted(_), _) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn receipt(success: bool, gas_used: u128) -> Result<TrapReceipt, SentinelError> {
+        Ok(TrapReceipt {
+            hash: "0xabc".to_string(),
+            success,
+            gas_used,
+            effective_gas_price: 1,
+        })
+    }
+
+    #[test]
+    fn reports_outcome_and_gas_mismatches() {
+        let ok = Simulated::Success { gas: 500_000 };
+        assert_eq!(discrepancy(&ok, &receipt(true, 450_000)), None);
+        assert!(discrepancy(&ok, &receipt(true, 800_000))
+            .unwrap()
+            .starts_with("gas used 800000 vs simulated 500000"));
+        assert!(discrepancy(&ok, &receipt(false, 500_000))
+            .unwrap()
+            .starts_with("reverted on-chain"));
+
+        let reverted = Simulated::Reverted("InvalidProof()".to_string());
+        assert!(discrepancy(&reverted, &receipt(true, 500_000))
+            .unwrap()
+            .ends_with("InvalidProof()"));
+        assert_eq!(discrepancy(&reverted, &receipt(false, 500_000)), None);
+        let failed = Err(SentinelError::Submission("nonce too low".to_string()));
+        assert_eq!(discrepancy(&reverted, &failed), None);
+
+        let down = Simulated::Unavailable("timed out".to_string());
+        assert_eq!(discrepancy(&down, &receipt(false, 0)), None);
+    }
+
+    #[test]
+    fn only_node_reverts_count_as_reverted() {
+        let error = |code: i64, message: &str| {
+            let payload = serde_json::json!({ "code": code, "message": message });
+            Err(alloy::transports::RpcError::ErrorResp(
+                serde_json::from_value(payload).unwrap(),
+            ))
+        };
+
+        assert_eq!(simulated(Ok(21_000)), Simulated::Success { gas: 21_000 });
+        assert!(matches!(
+            simulated(error(3, "execution reverted: InvalidProof()")),
+            Simulated::Reverted(reason) if reason.contains("InvalidProof()")
+        ));
+        assert!(matches!(
+            simulated(error(-32000, "header not found")),
+            Simulated::Unavailable(_)
+        ));
+        assert!(matches!(
+            simulated(Err(alloy::transports::TransportErrorKind::backend_gone())),
+            Simulated::Unavailable(_)
+        ));
+    }
+}
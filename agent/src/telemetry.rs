@@ -0,0 +1,76 @@
+//! # OpenTelemetry Export
+//!
+//! With `OTLP_ENDPOINT` set, tracing spans are also exported over OTLP/HTTP,
+//! so a collector (Jaeger, Tempo, ...) can show each transaction's pipeline
+//! as one trace: gas estimate, features, inference, proof and submission
+//! nest under the `process_transaction` span. The file log is unchanged.
+
+use eyre::{Result, WrapErr};
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry::KeyValue;
+use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+use opentelemetry_sdk::trace::TracerProvider;
+use opentelemetry_sdk::Resource;
+use tracing::Subscriber;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+const SERVICE_NAME: &str = "beetrap-sentinel";
+
+/// Flushes buffered spans when dropped at the end of `main`
+pub struct Telemetry {
+    provider: TracerProvider,
+}
+
+impl Drop for Telemetry {
+    fn drop(&mut self) {
+        if let Err(e) = self.provider.shutdown() {
+            eprintln!("OpenTelemetry shutdown failed: {}", e);
+        }
+    }
+}
+
+/// Tracing layer exporting spans to `endpoint`, plus the guard that flushes them
+pub fn layer<S>(endpoint: &str) -> Result<(impl Layer<S>, Telemetry)>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    let exporter = SpanExporter::builder()
+        .with_http()
+        .with_endpoint(traces_url(endpoint))
+        .build()
+        .wrap_err_with(|| format!("Failed to build OTLP exporter for {}", endpoint))?;
+    let provider = TracerProvider::builder()
+        .with_batch_exporter(exporter, opentelemetry_sdk::runtime::Tokio)
+        .with_resource(Resource::new([KeyValue::new("service.name", SERVICE_NAME)]))
+        .build();
+    let layer = tracing_opentelemetry::layer().with_tracer(provider.tracer(SERVICE_NAME));
+    Ok((layer, Telemetry { provider }))
+}
+
+/// Collectors listen on `/v1/traces`; accept the bare base URL as well
+fn traces_url(endpoint: &str) -> String {
+    let endpoint = endpoint.trim_end_matches('/');
+    if endpoint.ends_with("/v1/traces") {
+        endpoint.to_string()
+    } else {
+        format!("{}/v1/traces", endpoint)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn appends_the_traces_path_once() {
+        assert_eq!(
+            traces_url("http://localhost:4318"),
+            "http://localhost:4318/v1/traces"
+        );
+        assert_eq!(
+            traces_url("http://collector:4318/v1/traces/"),
+            "http://collector:4318/v1/traces"
+        );
+    }
+}
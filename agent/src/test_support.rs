@@ -0,0 +1,51 @@
+//! # Test Support
+//!
+//! An in-process JSON-RPC node for tests that need a provider without a
+//! running chain.
+
+use alloy::providers::RootProvider;
+use alloy::pubsub::PubSubFrontend;
+
+/// A node answering JSON-RPC methods from `reply`; anything it has no
+/// answer for is an error
+#[derive(Clone)]
+pub struct MockNode(pub fn(&str) -> Option<serde_json::Value>);
+
+impl alloy::pubsub::PubSubConnect for MockNode {
+    fn is_local(&self) -> bool {
+        true
+    }
+
+    async fn connect(&self) -> alloy::transports::TransportResult<alloy::pubsub::ConnectionHandle> {
+        let (handle, mut interface) = alloy::pubsub::ConnectionHandle::new();
+        let reply = self.0;
+        tokio::spawn(async move {
+            while let Some(request) = interface.recv_from_frontend().await {
+                let request: serde_json::Value = serde_json::from_str(request.get()).unwrap();
+                let method = request["method"].as_str().unwrap_or_default();
+                let response = match reply(method) {
+                    Some(result) => serde_json::json!({
+                        "jsonrpc": "2.0", "id": request["id"], "result": result
+                    }),
+                    None => serde_json::json!({
+                        "jsonrpc": "2.0", "id": request["id"],
+                        "error": {"code": -32000, "message": "unavailable"}
+                    }),
+                };
+                let item = serde_json::from_str(&response.to_string()).unwrap();
+                if interface.send_to_frontend(item).is_err() {
+                    break;
+                }
+            }
+        });
+        Ok(handle)
+    }
+}
+
+pub async fn mock_provider(node: MockNode) -> RootProvider<PubSubFrontend> {
+    let client = alloy::rpc::client::ClientBuilder::default()
+        .pubsub(node)
+        .await
+        .unwrap();
+    RootProvider::new(client)
+}
@@ -4,6 +4,7 @@
 
 use chrono::{DateTime, Utc};
 use eyre::Result;
+use std::collections::{HashMap, VecDeque};
 use std::fmt;
 use std::time::{Duration, Instant};
 
@@ -22,10 +23,12 @@ pub struct Config {
     pub chain_id: u64,
     /// Private key for signing (hex, with or without 0x prefix)
     pub private_key: String,
-    /// Uniswap v4 PoolManager address
-    pub pool_manager_address: String,
-    /// Uniswap Universal Router address
-    pub universal_router_address: String,
+    /// Pool of signing keys round-robined across submissions (defaults to `private_key`)
+    pub private_keys: Vec<String>,
+    /// Uniswap v4 PoolManager addresses (comma-separated in the env)
+    pub pool_manager_addresses: Vec<String>,
+    /// Uniswap Universal Router addresses (comma-separated in the env)
+    pub universal_router_addresses: Vec<String>,
     /// BeeTrapHook contract address
     pub hook_address: String,
     /// Agent NFT contract address
@@ -40,6 +43,171 @@ pub struct Config {
     pub demo_mode: bool,
     /// Use mock transaction data instead of real RPC (for testing real ONNX)
     pub use_mock_data: bool,
+    /// Log file rotation period ("daily", "hourly" or "never")
+    pub log_rotation: String,
+    /// Delete rotated log files older than this many days (0 = keep forever)
+    pub log_retention_days: u64,
+    /// Reset per-block detection counters whenever a new block is seen
+    pub reset_on_new_block: bool,
+    /// Window for coalescing pending hashes into one JSON-RPC batch (0 = no batching)
+    pub tx_batch_window_ms: u64,
+    /// Recent block hashes kept to detect reorgs (0 = off)
+    pub reorg_window_blocks: usize,
+    /// Append-only audit log of submitted traps (disabled when unset)
+    pub audit_log_path: Option<String>,
+    /// Read policy (threshold, model path/hash) from the AgentNFT tokenURI
+    pub onchain_config: bool,
+    /// Model hash the loaded ONNX file must match (set from on-chain policy)
+    pub expected_model_hash: Option<[u8; 32]>,
+    /// Largest proof (in bytes) the client will submit in one transaction
+    pub max_proof_bytes: usize,
+    /// Most public inputs the client will submit with one proof (0 = no limit)
+    pub max_public_inputs: usize,
+    /// Submit traps from startup; otherwise warm standby until armed in the TUI
+    pub start_armed: bool,
+    /// File-backed set of submitted/in-flight bot addresses (in-memory when unset)
+    pub submission_ledger_path: Option<String>,
+    /// JSON ABI (or forge artifact) for the hook's trap function; compiled binding when unset
+    pub hook_abi_path: Option<String>,
+    /// Flashbots-style relay for `eth_sendPrivateTransaction` (public mempool when unset)
+    pub private_tx_url: Option<String>,
+    /// Key that signs relay requests (reputation only; random when unset)
+    pub private_tx_auth_key: Option<String>,
+    /// Seconds between gas-price polls on the execution chain
+    pub gas_poll_interval_secs: u64,
+    /// Failed execution polls in a row before falling back to scoring only (0 = never)
+    pub execution_degrade_after: u32,
+    /// `is_predator` checks after a successful submission before warning
+    pub post_verify_attempts: u32,
+    /// Delay between post-verification checks (ms)
+    pub post_verify_delay_ms: u64,
+    /// Detections below this transaction value (wei) are logged but not proven
+    pub min_trap_value_wei: u128,
+    /// Feature extraction plugin (see `features::by_name`)
+    pub feature_extractor: String,
+    /// Per-reason confidence thresholds; unlisted reasons use `confidence_threshold`
+    pub reason_thresholds: HashMap<DetectionReason, f32>,
+    /// Per-reason multipliers on the savings credited to a trap (unlisted = 1.0)
+    pub reason_impact_weights: HashMap<DetectionReason, f64>,
+    /// Candidate ONNX model scored alongside the primary for A/B comparison
+    pub shadow_model_path: Option<String>,
+    /// Fraction of transactions (0.0 - 1.0) scored by the shadow model
+    pub shadow_sample_rate: f32,
+    /// Order of witness values expected by the on-chain verifier
+    pub public_input_layout: PublicInputLayout,
+    /// Public-input count the deployed verifier expects (validated before submit)
+    pub expected_public_inputs: Option<usize>,
+    /// Drop mempool rows older than this from the TUI (0 = keep)
+    pub ui_tx_max_age_secs: u64,
+    /// Capture mouse clicks in the TUI (disables native text selection)
+    pub mouse_capture: bool,
+    /// Most recent detections kept in the TUI (oldest dropped first)
+    pub ui_max_detections: usize,
+    /// Append every detection to this JSON Lines file
+    pub detections_jsonl: Option<String>,
+    /// Rotate the detections file once it reaches this size (0 = never)
+    pub detections_jsonl_max_bytes: u64,
+    /// Named pipe that gets the same JSON line per detection (`mkfifo` first)
+    pub fifo_path: Option<String>,
+    /// Capture each transaction's signed EIP-2718 bytes for detection records
+    pub detections_raw_tx: bool,
+    /// Attach the normalized feature vector to detection logs and records
+    pub detection_features: bool,
+    /// Look up each detected sender's transaction count on the mempool RPC
+    pub detection_sender_history: bool,
+    /// Check for a receipt on the mempool RPC before proving a detection
+    pub skip_mined: bool,
+    /// Decimal places for ETH amounts in the TUI and line mode
+    pub eth_precision: usize,
+    /// Built-in theme name or path to a TOML theme file
+    pub ui_theme: String,
+    /// Probabilities in the TUI as percent, raw 0-1 or log-odds (`p` toggles)
+    pub score_display: ScoreDisplay,
+    /// Denominator of the headline efficiency figure
+    pub efficiency_basis: EfficiencyBasis,
+    /// Warn and stop trapping when a signer balance drops below this (wei)
+    pub min_wallet_balance_wei: u128,
+    /// Seconds between signer balance checks
+    pub balance_poll_interval_secs: u64,
+    /// Model output holding class probabilities (None = auto-detect)
+    pub probability_output_index: Option<usize>,
+    /// Class whose probability is the predator score
+    pub probability_class_index: usize,
+    /// Extra attempts per EZKL stage after a transient failure
+    pub ezkl_retries: u32,
+    /// Log every decision trace and show it in the insight panel
+    pub decision_trace: bool,
+    /// Score and display only: no wallet, execution RPC, proofs or submissions
+    pub scoring_only: bool,
+    /// Detections in the first N scanned transactions are logged, not trapped
+    pub warmup_txs: u64,
+    /// Abandon a detection this long after mempool receipt (0 = no deadline)
+    pub pipeline_deadline_ms: u64,
+    /// Threads reserved for EZKL proving, separate from the scoring runtime
+    pub prover_threads: usize,
+    /// Weight of the newest score in each sender's moving average (0 = off)
+    pub score_ema_alpha: f32,
+    /// Senders whose moving average is remembered
+    pub score_ema_max_addresses: usize,
+    /// Count transactions with empty calldata without scoring them
+    pub skip_plain_transfers: bool,
+    /// Mark EIP-1559 txs tipping less than this safe without scoring (0 = off)
+    pub min_priority_fee_gwei: f64,
+    /// Transactions with more calldata than this are not scored (0 = no limit)
+    pub max_input_bytes: usize,
+    /// Clamp normalized features to ±this many standard deviations (0 = off)
+    pub feature_clamp_sigma: f32,
+    /// Hold detections this long for companion txs before trapping (0 = off)
+    pub trap_hold_ms: u64,
+    /// How long funding transfers link addresses into clusters (0 = off)
+    pub cluster_window_secs: u64,
+    /// Funding links kept for clustering; the oldest go first
+    pub cluster_max_links: usize,
+    /// OTLP/HTTP collector that receives tracing spans (None = no export)
+    pub otlp_endpoint: Option<String>,
+    /// Cap on `assets/prove` in MB; oldest artifact sets are evicted (0 = no cap)
+    pub prove_dir_max_mb: u64,
+    /// What the model's score means: a probability or an expected profit
+    pub model_task: ModelTask,
+    /// Expected MEV profit (monitored chain's native currency) that traps in regression mode
+    pub profit_threshold_eth: f32,
+    /// Senders never flagged, one address per line (edited from the TUI)
+    pub allowlist_path: Option<String>,
+    /// Senders always flagged once scored, one address per line
+    pub denylist_path: Option<String>,
+    /// ONNX Runtime execution provider for inference (resolved at startup)
+    pub inference_provider: InferenceProvider,
+    /// Score at which a detection traps; detections scoring between the
+    /// threshold and this are submitted flag-only (0 = every detection traps)
+    pub trap_score: f32,
+    /// Seconds without a processor heartbeat before the loop is restarted (0 = off)
+    pub processor_watchdog_secs: u64,
+    /// CSV of every scored transaction's raw features and outcome, plus TUI labels
+    pub dataset_csv: Option<String>,
+    /// Gas pricing of the monitored chain (auto = detect from block headers)
+    pub fee_model: FeeModel,
+    /// Rule baseline: router/pool manager swaps tipping at least this are suspicious (0 = off)
+    pub baseline_priority_fee_gwei: f64,
+    /// Scored transactions per baseline comparison window
+    pub baseline_window_txs: u64,
+    /// Rule flags in a window with no model detection that warn of degradation
+    pub baseline_min_flags: u64,
+    /// Address the read-only ops API (`GET /config`) listens on (None = off)
+    pub api_addr: Option<String>,
+    /// Copy each successful proof's artifacts to `assets/prove/latest.*`
+    pub publish_latest_proof: bool,
+    /// Minimum spacing of on-chain submissions in ms, token-bucket paced (0 = off)
+    pub submit_min_interval_ms: u64,
+    /// Submissions allowed back to back before pacing applies
+    pub submit_burst: u32,
+    /// Submissions waiting for pacing before further ones are dropped
+    pub submit_queue_max: usize,
+    /// Fork/simulation endpoint every submission is mirrored to as a gas estimate
+    pub simulation_rpc_url: Option<String>,
+    /// Local time windows in which traps are submitted (None = always)
+    pub trap_schedule: Option<crate::schedule::TrapSchedule>,
+    /// Hold detections made outside the schedule until the next window opens
+    pub trap_schedule_flush: bool,
 }
 
 impl Config {
@@ -51,6 +219,28 @@ impl Config {
             std::env::var("RPC_URL").unwrap_or_else(|_| "ws://localhost:8545".to_string());
         let execution_rpc_url = std::env::var("EXECUTION_RPC_URL").unwrap_or(rpc_url.clone());
 
+        let private_key = std::env::var("PRIVATE_KEY")
+            .unwrap_or_else(|_| {
+                "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80".to_string()
+            })
+            .trim_start_matches("0x")
+            .to_string();
+
+        // Optional signer pool: PRIVATE_KEYS=key1,key2,...
+        let mut private_keys: Vec<String> = std::env::var("PRIVATE_KEYS")
+            .unwrap_or_default()
+            .split(',')
+            .map(|k| k.trim().trim_start_matches("0x").to_string())
+            .filter(|k| !k.is_empty())
+            .collect();
+        if private_keys.is_empty() {
+            private_keys.push(private_key.clone());
+        }
+
+        let model_task: ModelTask = std::env::var("MODEL_TASK")
+            .unwrap_or_else(|_| "classification".to_string())
+            .parse()?;
+
         Ok(Self {
             rpc_url,
             execution_rpc_url,
@@ -58,14 +248,14 @@ impl Config {
                 .unwrap_or_else(|_| "31337".to_string())
                 .parse()
                 .unwrap_or(31337),
-            private_key: std::env::var("PRIVATE_KEY")
-                .unwrap_or_else(|_| {
-                    "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80".to_string()
-                })
-                .trim_start_matches("0x")
-                .to_string(),
-            pool_manager_address: std::env::var("POOL_MANAGER_ADDRESS").unwrap_or_default(),
-            universal_router_address: std::env::var("UNIVERSAL_ROUTER_ADDRESS").unwrap_or_default(),
+            private_key,
+            private_keys,
+            pool_manager_addresses: parse_address_list(
+                &std::env::var("POOL_MANAGER_ADDRESS").unwrap_or_default(),
+            ),
+            universal_router_addresses: parse_address_list(
+                &std::env::var("UNIVERSAL_ROUTER_ADDRESS").unwrap_or_default(),
+            ),
             hook_address: std::env::var("HOOK_ADDRESS").unwrap_or_default(),
             agent_nft_address: std::env::var("AGENT_NFT_ADDRESS").unwrap_or_default(),
             agent_nft_id: std::env::var("AGENT_NFT_ID")
@@ -84,8 +274,690 @@ impl Config {
             use_mock_data: std::env::var("USE_MOCK_DATA")
                 .map(|v| v == "1" || v.to_lowercase() == "true")
                 .unwrap_or(false),
+            log_rotation: std::env::var("LOG_ROTATION")
+                .unwrap_or_else(|_| "daily".to_string())
+                .to_lowercase(),
+            log_retention_days: std::env::var("LOG_RETENTION_DAYS")
+                .unwrap_or_else(|_| "7".to_string())
+                .parse()
+                .unwrap_or(7),
+            reset_on_new_block: std::env::var("RESET_ON_NEW_BLOCK")
+                .map(|v| v == "1" || v.to_lowercase() == "true")
+                .unwrap_or(true),
+            tx_batch_window_ms: std::env::var("TX_BATCH_WINDOW_MS")
+                .unwrap_or_else(|_| "50".to_string())
+                .parse()
+                .unwrap_or(50),
+            reorg_window_blocks: std::env::var("REORG_WINDOW_BLOCKS")
+                .unwrap_or_else(|_| "64".to_string())
+                .parse()
+                .unwrap_or(64),
+            audit_log_path: std::env::var("AUDIT_LOG_PATH")
+                .ok()
+                .filter(|p| !p.is_empty()),
+            onchain_config: std::env::var("ONCHAIN_CONFIG")
+                .map(|v| v == "1" || v.to_lowercase() == "true")
+                .unwrap_or(false),
+            expected_model_hash: None,
+            max_proof_bytes: std::env::var("MAX_PROOF_BYTES")
+                .unwrap_or_else(|_| "100000".to_string())
+                .parse()
+                .unwrap_or(100_000),
+            max_public_inputs: std::env::var("MAX_PUBLIC_INPUTS")
+                .unwrap_or_else(|_| "256".to_string())
+                .parse()
+                .unwrap_or(256),
+            start_armed: std::env::var("START_ARMED")
+                .map(|v| v == "1" || v.to_lowercase() == "true")
+                .unwrap_or(false),
+            submission_ledger_path: std::env::var("SUBMISSION_LEDGER_PATH")
+                .map(|p| Some(p).filter(|p| !p.is_empty()))
+                .unwrap_or_else(|_| Some("assets/submitted_bots.txt".to_string())),
+            hook_abi_path: std::env::var("HOOK_ABI_PATH")
+                .ok()
+                .filter(|p| !p.is_empty()),
+            private_tx_url: std::env::var("PRIVATE_TX_URL")
+                .ok()
+                .filter(|u| !u.is_empty()),
+            private_tx_auth_key: std::env::var("PRIVATE_TX_AUTH_KEY")
+                .ok()
+                .filter(|k| !k.is_empty()),
+            gas_poll_interval_secs: std::env::var("GAS_POLL_INTERVAL_SECS")
+                .unwrap_or_else(|_| "15".to_string())
+                .parse()
+                .unwrap_or(15),
+            execution_degrade_after: std::env::var("EXECUTION_DEGRADE_AFTER")
+                .unwrap_or_else(|_| "3".to_string())
+                .parse()
+                .unwrap_or(3),
+            post_verify_attempts: std::env::var("POST_VERIFY_ATTEMPTS")
+                .unwrap_or_else(|_| "5".to_string())
+                .parse()
+                .unwrap_or(5),
+            post_verify_delay_ms: std::env::var("POST_VERIFY_DELAY_MS")
+                .unwrap_or_else(|_| "2000".to_string())
+                .parse()
+                .unwrap_or(2000),
+            min_trap_value_wei: std::env::var("MIN_TRAP_VALUE_WEI")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()
+                .unwrap_or(0),
+            feature_extractor: std::env::var("FEATURE_EXTRACTOR")
+                .unwrap_or_else(|_| "standard".to_string())
+                .to_lowercase(),
+            reason_thresholds: parse_reason_thresholds(
+                &std::env::var("REASON_THRESHOLDS").unwrap_or_default(),
+                model_task,
+            ),
+            reason_impact_weights: parse_reason_weights(
+                &std::env::var("REASON_IMPACT_WEIGHTS").unwrap_or_default(),
+            ),
+            shadow_model_path: std::env::var("SHADOW_MODEL_PATH")
+                .ok()
+                .filter(|p| !p.is_empty()),
+            shadow_sample_rate: std::env::var("SHADOW_SAMPLE_RATE")
+                .ok()
+                .and_then(|v| v.parse::<f32>().ok())
+                .unwrap_or(1.0)
+                .clamp(0.0, 1.0),
+            public_input_layout: std::env::var("PUBLIC_INPUT_LAYOUT")
+                .unwrap_or_else(|_| "inputs,outputs".to_string())
+                .parse()?,
+            expected_public_inputs: std::env::var("EXPECTED_PUBLIC_INPUTS")
+                .ok()
+                .and_then(|v| v.parse().ok()),
+            ui_tx_max_age_secs: std::env::var("UI_TX_MAX_AGE_SECS")
+                .unwrap_or_else(|_| "120".to_string())
+                .parse()
+                .unwrap_or(120),
+            mouse_capture: std::env::var("TUI_MOUSE")
+                .map(|v| v == "1" || v.to_lowercase() == "true")
+                .unwrap_or(true),
+            ui_max_detections: std::env::var("UI_MAX_DETECTIONS")
+                .unwrap_or_else(|_| "100".to_string())
+                .parse()
+                .unwrap_or(100),
+            detections_jsonl: std::env::var("DETECTIONS_JSONL")
+                .ok()
+                .filter(|p| !p.is_empty()),
+            detections_jsonl_max_bytes: std::env::var("DETECTIONS_JSONL_MAX_BYTES")
+                .unwrap_or_else(|_| "50000000".to_string())
+                .parse()
+                .unwrap_or(50_000_000),
+            fifo_path: std::env::var("FIFO_PATH").ok().filter(|p| !p.is_empty()),
+            detections_raw_tx: std::env::var("DETECTIONS_RAW_TX")
+                .map(|v| v == "1" || v.to_lowercase() == "true")
+                .unwrap_or(false),
+            detection_features: std::env::var("DETECTION_FEATURES")
+                .map(|v| v == "1" || v.to_lowercase() == "true")
+                .unwrap_or(false),
+            detection_sender_history: std::env::var("DETECTION_SENDER_HISTORY")
+                .map(|v| v == "1" || v.to_lowercase() == "true")
+                .unwrap_or(false),
+            skip_mined: std::env::var("SKIP_MINED")
+                .map(|v| v == "1" || v.to_lowercase() == "true")
+                .unwrap_or(false),
+            eth_precision: std::env::var("ETH_DECIMALS")
+                .ok()
+                .and_then(|v| v.parse::<usize>().ok())
+                .unwrap_or(4)
+                .min(18),
+            ui_theme: std::env::var("UI_THEME").unwrap_or_else(|_| "default".to_string()),
+            score_display: std::env::var("SCORE_DISPLAY")
+                .unwrap_or_else(|_| "percent".to_string())
+                .parse()?,
+            efficiency_basis: std::env::var("EFFICIENCY_BASIS")
+                .unwrap_or_else(|_| "observed".to_string())
+                .parse()?,
+            min_wallet_balance_wei: std::env::var("MIN_WALLET_BALANCE_WEI")
+                .unwrap_or_else(|_| "10000000000000000".to_string())
+                .parse()
+                .unwrap_or(10_000_000_000_000_000),
+            balance_poll_interval_secs: std::env::var("BALANCE_POLL_INTERVAL_SECS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .unwrap_or(30),
+            probability_output_index: std::env::var("PROBABILITY_OUTPUT_INDEX")
+                .ok()
+                .and_then(|v| v.parse().ok()),
+            probability_class_index: std::env::var("PROBABILITY_CLASS_INDEX")
+                .unwrap_or_else(|_| "1".to_string())
+                .parse()
+                .unwrap_or(1),
+            ezkl_retries: std::env::var("EZKL_RETRIES")
+                .unwrap_or_else(|_| "2".to_string())
+                .parse()
+                .unwrap_or(2),
+            decision_trace: std::env::var("DECISION_TRACE")
+                .map(|v| v == "1" || v.to_lowercase() == "true")
+                .unwrap_or(false),
+            scoring_only: std::env::var("SCORING_ONLY")
+                .map(|v| v == "1" || v.to_lowercase() == "true")
+                .unwrap_or(false),
+            warmup_txs: std::env::var("WARMUP_TXS")
+                .unwrap_or_else(|_| "50".to_string())
+                .parse()
+                .unwrap_or(50),
+            pipeline_deadline_ms: std::env::var("PIPELINE_DEADLINE_MS")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()
+                .unwrap_or(0),
+            prover_threads: std::env::var("PROVER_THREADS")
+                .ok()
+                .and_then(|v| v.parse::<usize>().ok())
+                .unwrap_or(1)
+                .max(1),
+            score_ema_alpha: std::env::var("SCORE_EMA_ALPHA")
+                .ok()
+                .and_then(|v| v.parse::<f32>().ok())
+                .filter(|a| (0.0..=1.0).contains(a))
+                .unwrap_or(0.0),
+            score_ema_max_addresses: std::env::var("SCORE_EMA_MAX_ADDRESSES")
+                .unwrap_or_else(|_| "10000".to_string())
+                .parse()
+                .unwrap_or(10000),
+            skip_plain_transfers: std::env::var("SKIP_PLAIN_TRANSFERS")
+                .map(|v| v == "1" || v.to_lowercase() == "true")
+                .unwrap_or(true),
+            min_priority_fee_gwei: std::env::var("MIN_PRIORITY_FEE_GWEI")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()
+                .unwrap_or(0.0),
+            max_input_bytes: std::env::var("MAX_INPUT_BYTES")
+                .unwrap_or_else(|_| "65536".to_string())
+                .parse()
+                .unwrap_or(65536),
+            feature_clamp_sigma: std::env::var("FEATURE_CLAMP_SIGMA")
+                .ok()
+                .and_then(|v| v.parse::<f32>().ok())
+                .filter(|s| *s >= 0.0)
+                .unwrap_or(0.0),
+            // Capped: past a couple of seconds the attack has landed anyway
+            trap_hold_ms: std::env::var("TRAP_HOLD_MS")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse::<u64>()
+                .unwrap_or(0)
+                .min(2000),
+            cluster_window_secs: std::env::var("CLUSTER_WINDOW_SECS")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()
+                .unwrap_or(0),
+            cluster_max_links: std::env::var("CLUSTER_MAX_LINKS")
+                .unwrap_or_else(|_| "10000".to_string())
+                .parse()
+                .unwrap_or(10_000),
+            otlp_endpoint: std::env::var("OTLP_ENDPOINT")
+                .ok()
+                .filter(|v| !v.is_empty()),
+            prove_dir_max_mb: std::env::var("PROVE_DIR_MAX_MB")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()
+                .unwrap_or(0),
+            model_task,
+            profit_threshold_eth: std::env::var("PROFIT_THRESHOLD_ETH")
+                .ok()
+                .and_then(|v| v.parse::<f32>().ok())
+                .filter(|t| *t >= 0.0)
+                .unwrap_or(0.05),
+            allowlist_path: std::env::var("ALLOWLIST_PATH")
+                .ok()
+                .filter(|p| !p.is_empty()),
+            denylist_path: std::env::var("DENYLIST_PATH")
+                .ok()
+                .filter(|p| !p.is_empty()),
+            inference_provider: std::env::var("INFERENCE_PROVIDER")
+                .unwrap_or_else(|_| "cpu".to_string())
+                .parse()?,
+            trap_score: std::env::var("TRAP_SCORE")
+                .ok()
+                .and_then(|v| v.parse::<f32>().ok())
+                .filter(|t| *t >= 0.0)
+                .unwrap_or(0.0),
+            processor_watchdog_secs: std::env::var("PROCESSOR_WATCHDOG_SECS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .unwrap_or(30),
+            dataset_csv: std::env::var("DATASET_CSV").ok().filter(|p| !p.is_empty()),
+            fee_model: std::env::var("FEE_MODEL")
+                .unwrap_or_else(|_| "auto".to_string())
+                .parse()?,
+            baseline_priority_fee_gwei: std::env::var("BASELINE_PRIORITY_FEE_GWEI")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()
+                .unwrap_or(0.0),
+            baseline_window_txs: std::env::var("BASELINE_WINDOW_TXS")
+                .unwrap_or_else(|_| "500".to_string())
+                .parse()
+                .unwrap_or(500),
+            baseline_min_flags: std::env::var("BASELINE_MIN_FLAGS")
+                .unwrap_or_else(|_| "10".to_string())
+                .parse()
+                .unwrap_or(10),
+            api_addr: std::env::var("API_ADDR").ok().filter(|a| !a.is_empty()),
+            publish_latest_proof: std::env::var("PUBLISH_LATEST_PROOF")
+                .map(|v| v == "1" || v.to_lowercase() == "true")
+                .unwrap_or(false),
+            submit_min_interval_ms: std::env::var("SUBMIT_MIN_INTERVAL_MS")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()
+                .unwrap_or(0),
+            submit_burst: std::env::var("SUBMIT_BURST")
+                .unwrap_or_else(|_| "1".to_string())
+                .parse()
+                .unwrap_or(1),
+            submit_queue_max: std::env::var("SUBMIT_QUEUE_MAX")
+                .unwrap_or_else(|_| "32".to_string())
+                .parse()
+                .unwrap_or(32),
+            simulation_rpc_url: std::env::var("SIMULATION_RPC_URL")
+                .ok()
+                .filter(|u| !u.is_empty()),
+            trap_schedule: match std::env::var("TRAP_SCHEDULE") {
+                Ok(windows) if !windows.trim().is_empty() => {
+                    Some(crate::schedule::TrapSchedule::parse(
+                        &windows,
+                        &std::env::var("TRAP_SCHEDULE_TZ").unwrap_or_else(|_| "UTC".to_string()),
+                    )?)
+                }
+                _ => None,
+            },
+            trap_schedule_flush: std::env::var("TRAP_SCHEDULE_FLUSH")
+                .map(|v| v == "1" || v.to_lowercase() == "true")
+                .unwrap_or(false),
         })
     }
+
+    /// Base trap threshold in the model task's unit
+    pub fn decision_threshold(&self) -> f32 {
+        match self.model_task {
+            ModelTask::Classification => self.confidence_threshold,
+            ModelTask::Regression => self.profit_threshold_eth,
+        }
+    }
+}
+
+/// Which witness sections make up the verifier's public inputs, and in what order
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PublicInputLayout {
+    /// EZKL default: model inputs followed by outputs
+    #[default]
+    InputsThenOutputs,
+    OutputsThenInputs,
+    InputsOnly,
+    OutputsOnly,
+}
+
+impl std::str::FromStr for PublicInputLayout {
+    type Err = eyre::Report;
+
+    /// Comma-separated section names, e.g. `inputs,outputs` or `outputs`
+    fn from_str(s: &str) -> Result<Self> {
+        let sections: Vec<String> = s
+            .split(',')
+            .map(|p| p.trim().to_lowercase())
+            .filter(|p| !p.is_empty())
+            .collect();
+        match sections.join(",").as_str() {
+            "inputs,outputs" => Ok(Self::InputsThenOutputs),
+            "outputs,inputs" => Ok(Self::OutputsThenInputs),
+            "inputs" => Ok(Self::InputsOnly),
+            "outputs" => Ok(Self::OutputsOnly),
+            _ => Err(eyre::eyre!("Invalid PUBLIC_INPUT_LAYOUT: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for PublicInputLayout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InputsThenOutputs => write!(f, "inputs,outputs"),
+            Self::OutputsThenInputs => write!(f, "outputs,inputs"),
+            Self::InputsOnly => write!(f, "inputs"),
+            Self::OutputsOnly => write!(f, "outputs"),
+        }
+    }
+}
+
+/// How the model's output is read
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ModelTask {
+    /// Predator probability (0.0 - 1.0) against `CONFIDENCE_THRESHOLD`
+    #[default]
+    Classification,
+    /// Expected MEV profit in native currency against `PROFIT_THRESHOLD_ETH`
+    Regression,
+}
+
+impl ModelTask {
+    /// What the score is called in the UI
+    pub fn score_label(&self) -> &'static str {
+        match self {
+            Self::Classification => "Predator Probability",
+            Self::Regression => "Expected Profit",
+        }
+    }
+}
+
+impl std::str::FromStr for ModelTask {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "classification" => Ok(Self::Classification),
+            "regression" => Ok(Self::Regression),
+            _ => Err(eyre::eyre!("Invalid MODEL_TASK: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for ModelTask {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Classification => write!(f, "classification"),
+            Self::Regression => write!(f, "regression"),
+        }
+    }
+}
+
+/// How predator probabilities are shown in the TUI
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ScoreDisplay {
+    /// `93.4%`
+    #[default]
+    Percent,
+    /// `0.9341`
+    Raw,
+    /// Log-odds, `ln(p / (1 - p))`: `+2.650`
+    LogOdds,
+}
+
+impl ScoreDisplay {
+    /// Next mode for the TUI toggle
+    pub fn next(self) -> Self {
+        match self {
+            Self::Percent => Self::Raw,
+            Self::Raw => Self::LogOdds,
+            Self::LogOdds => Self::Percent,
+        }
+    }
+
+    /// A probability in this representation
+    pub fn probability(&self, p: f32) -> String {
+        match self {
+            Self::Percent => format!("{:.1}%", p * 100.0),
+            Self::Raw => format!("{:.4}", p),
+            Self::LogOdds => {
+                // 0 and 1 would be infinite
+                let p = p.clamp(1e-6, 1.0 - 1e-6) as f64;
+                format!("{:+.3}", (p / (1.0 - p)).ln())
+            }
+        }
+    }
+
+    /// A change in probability. Log-odds deltas depend on the base score, so
+    /// they are shown raw.
+    pub fn probability_delta(&self, delta: f32) -> String {
+        match self {
+            Self::Percent => format!("{:+.1}%", delta * 100.0),
+            Self::Raw | Self::LogOdds => format!("{:+.4}", delta),
+        }
+    }
+}
+
+impl std::str::FromStr for ScoreDisplay {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "percent" => Ok(Self::Percent),
+            "raw" => Ok(Self::Raw),
+            "odds" | "log-odds" => Ok(Self::LogOdds),
+            _ => Err(eyre::eyre!("Invalid SCORE_DISPLAY: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for ScoreDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Percent => write!(f, "percent"),
+            Self::Raw => write!(f, "raw"),
+            Self::LogOdds => write!(f, "log-odds"),
+        }
+    }
+}
+
+/// What the efficiency figure divides trapped transactions by
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EfficiencyBasis {
+    /// Every hash the mempool listener saw, including ones dropped under load
+    #[default]
+    Observed,
+    /// Transactions that reached the model
+    Scanned,
+}
+
+impl std::str::FromStr for EfficiencyBasis {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "observed" => Ok(Self::Observed),
+            "scanned" => Ok(Self::Scanned),
+            _ => Err(eyre::eyre!("Invalid EFFICIENCY_BASIS: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for EfficiencyBasis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Observed => write!(f, "observed"),
+            Self::Scanned => write!(f, "scanned"),
+        }
+    }
+}
+
+/// ONNX Runtime execution provider the model runs on
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum InferenceProvider {
+    #[default]
+    Cpu,
+    /// NVIDIA GPUs (build with `--features cuda`)
+    Cuda,
+    /// Apple Neural Engine / GPU (build with `--features coreml`)
+    CoreMl,
+}
+
+impl std::str::FromStr for InferenceProvider {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "cpu" => Ok(Self::Cpu),
+            "cuda" => Ok(Self::Cuda),
+            "coreml" => Ok(Self::CoreMl),
+            _ => Err(eyre::eyre!("Invalid INFERENCE_PROVIDER: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for InferenceProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Cpu => write!(f, "cpu"),
+            Self::Cuda => write!(f, "cuda"),
+            Self::CoreMl => write!(f, "coreml"),
+        }
+    }
+}
+
+/// What a submission does to the bot on-chain, by confidence tier
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TrapAction {
+    /// `markAsPredatorWithProof(status = true)`: swaps from the bot pay the
+    /// trap fee
+    #[default]
+    Trap,
+    /// `recordProof`: the proof is verified and recorded (`ProofVerified`)
+    /// but the bot's status is left as it is
+    Flag,
+}
+
+impl TrapAction {
+    /// `status` argument of a `HOOK_ABI_PATH` trap call
+    pub fn status(&self) -> bool {
+        *self == Self::Trap
+    }
+}
+
+/// How the monitored chain prices gas
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FeeModel {
+    /// Follow the block headers: no base fee means legacy pricing
+    #[default]
+    Auto,
+    Eip1559,
+    /// Pre-London or non-EIP-1559 chains: the gas price is the whole bid
+    Legacy,
+}
+
+impl std::str::FromStr for FeeModel {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "auto" => Ok(Self::Auto),
+            "eip1559" => Ok(Self::Eip1559),
+            "legacy" => Ok(Self::Legacy),
+            _ => Err(eyre::eyre!("Invalid FEE_MODEL: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for FeeModel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Auto => write!(f, "auto"),
+            Self::Eip1559 => write!(f, "eip1559"),
+            Self::Legacy => write!(f, "legacy"),
+        }
+    }
+}
+
+impl fmt::Display for TrapAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Trap => write!(f, "trap"),
+            Self::Flag => write!(f, "flag-only"),
+        }
+    }
+}
+
+/// Analyst verdict on the model's call for one transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReviewLabel {
+    TruePositive,
+    FalsePositive,
+    TrueNegative,
+    FalseNegative,
+}
+
+impl ReviewLabel {
+    /// Label for confirming (`confirmed`) or refuting the model's verdict
+    pub fn from_review(flagged: bool, confirmed: bool) -> Self {
+        match (flagged, confirmed) {
+            (true, true) => Self::TruePositive,
+            (true, false) => Self::FalsePositive,
+            (false, true) => Self::TrueNegative,
+            (false, false) => Self::FalseNegative,
+        }
+    }
+
+    /// Table tag
+    pub fn short(&self) -> &'static str {
+        match self {
+            Self::TruePositive => "TP",
+            Self::FalsePositive => "FP",
+            Self::TrueNegative => "TN",
+            Self::FalseNegative => "FN",
+        }
+    }
+}
+
+impl std::str::FromStr for ReviewLabel {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim() {
+            "true_positive" => Ok(Self::TruePositive),
+            "false_positive" => Ok(Self::FalsePositive),
+            "true_negative" => Ok(Self::TrueNegative),
+            "false_negative" => Ok(Self::FalseNegative),
+            _ => Err(eyre::eyre!("Invalid label: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for ReviewLabel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TruePositive => write!(f, "true_positive"),
+            Self::FalsePositive => write!(f, "false_positive"),
+            Self::TrueNegative => write!(f, "true_negative"),
+            Self::FalseNegative => write!(f, "false_negative"),
+        }
+    }
+}
+
+/// Parse `Reason=threshold` pairs, e.g. `HighGasFrontrun=0.7,GenericMEV=0.85`.
+/// Thresholds are probabilities (0.0 - 1.0) for a classification model and
+/// expected profit (≥ 0) for a regression model. Entries that are malformed
+/// or out of range for `task` are dropped with a warning.
+pub fn parse_reason_thresholds(s: &str, task: ModelTask) -> HashMap<DetectionReason, f32> {
+    s.split(',')
+        .map(str::trim)
+        .filter(|pair| !pair.is_empty())
+        .filter_map(|pair| {
+            let parsed = pair.split_once('=').and_then(|(reason, value)| {
+                let reason: DetectionReason = reason.trim().parse().ok()?;
+                let threshold: f32 = value.trim().parse().ok()?;
+                let in_range = match task {
+                    ModelTask::Classification => (0.0..=1.0).contains(&threshold),
+                    ModelTask::Regression => threshold.is_finite() && threshold >= 0.0,
+                };
+                in_range.then_some((reason, threshold))
+            });
+            if parsed.is_none() {
+                tracing::warn!(
+                    "Ignoring REASON_THRESHOLDS entry {:?} (malformed or out of range for a {:?} model)",
+                    pair,
+                    task
+                );
+            }
+            parsed
+        })
+        .collect()
+}
+
+/// Parse `Reason=multiplier` pairs, e.g. `SandwichPattern=2,GenericMEV=0.5`.
+/// Malformed or negative entries are ignored.
+pub fn parse_reason_weights(s: &str) -> HashMap<DetectionReason, f64> {
+    s.split(',')
+        .filter_map(|pair| {
+            let (reason, value) = pair.split_once('=')?;
+            let weight: f64 = value.trim().parse().ok()?;
+            (weight.is_finite() && weight >= 0.0).then_some((reason.parse().ok()?, weight))
+        })
+        .collect()
+}
+
+/// Split a comma-separated address list, e.g. two routers in one env var
+pub fn parse_address_list(s: &str) -> Vec<String> {
+    s.split(',')
+        .map(|a| a.trim().to_string())
+        .filter(|a| !a.is_empty())
+        .collect()
 }
 
 // ═══════════════════════════════════════════════════════════════════════════
@@ -119,6 +991,193 @@ pub struct PendingTransaction {
     pub received_at: Instant,
     /// Chain ID
     pub chain_id: u64,
+    /// Decoded V4 pool (only for calls sent directly to the PoolManager)
+    pub pool_key: Option<PoolKeyInfo>,
+    /// Which monitored contract the transaction is addressed to
+    pub target: TxTarget,
+    /// Monitored contract this ERC-20 `approve` grants an allowance to
+    pub approves: Option<TxTarget>,
+    /// Address of the monitored contract matched (called or approved)
+    pub target_address: Option<String>,
+    /// EIP-4844 max fee per blob gas in wei (`Some` only for type-3 blob txs)
+    pub max_fee_per_blob_gas: Option<u128>,
+    /// Number of blobs carried (0 for non-blob txs)
+    pub blob_count: usize,
+    /// Sender nonce
+    pub nonce: u64,
+    /// Set when this re-broadcasts an earlier nonce from the same sender with a higher bid
+    pub gas_escalation: Option<GasEscalation>,
+    /// Signed EIP-2718 encoding, captured only with `DETECTIONS_RAW_TX`
+    pub raw: Option<Vec<u8>>,
+}
+
+impl PendingTransaction {
+    /// Type-3 (EIP-4844) blob transaction
+    pub fn is_blob(&self) -> bool {
+        self.max_fee_per_blob_gas.is_some()
+    }
+
+    /// Value transfer to an address with no calldata (no swap to front-run)
+    pub fn is_plain_transfer(&self) -> bool {
+        self.input.is_empty() && self.to.is_some()
+    }
+
+    /// Calldata longer than `max_bytes` (0 = no limit)
+    pub fn input_exceeds(&self, max_bytes: usize) -> bool {
+        max_bytes > 0 && self.input.len() > max_bytes
+    }
+
+    /// EIP-1559 tip below `floor_wei`. Legacy txs bid through their gas
+    /// price alone, so they never count as low-tip.
+    pub fn tips_below(&self, floor_wei: u128) -> bool {
+        self.max_priority_fee_per_gas
+            .is_some_and(|tip| tip < floor_wei)
+    }
+}
+
+/// Same sender and nonce seen again with a higher fee bid (a replacement)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GasEscalation {
+    /// Times this nonce has been re-broadcast with a higher bid
+    pub replacements: u32,
+    /// Fee bid (gas price or max fee) of the first broadcast seen, in wei
+    pub first_bid: u128,
+    /// Fee bid of the latest broadcast, in wei
+    pub bid: u128,
+}
+
+impl GasEscalation {
+    /// Bid increase over the first broadcast, in percent
+    pub fn bump_pct(&self) -> f64 {
+        (self.bid as f64 / self.first_bid.max(1) as f64 - 1.0) * 100.0
+    }
+}
+
+/// Blocks replaced by a chain reorganization
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Reorg {
+    /// Lowest block number whose earlier header is no longer canonical
+    pub first_block: u64,
+    /// Blocks replaced, counted up to the highest head seen
+    pub depth: u64,
+}
+
+impl fmt::Display for GasEscalation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} replacement(s), {:.2} -> {:.2} Gwei (+{:.0}%)",
+            self.replacements,
+            self.first_bid as f64 / 1e9,
+            self.bid as f64 / 1e9,
+            self.bump_pct()
+        )
+    }
+}
+
+/// Moving average of a sender's predator scores
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AddressScore {
+    pub smoothed: f32,
+    /// Scored transactions folded into `smoothed`
+    pub samples: u32,
+}
+
+impl fmt::Display for AddressScore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:.1}% over {} tx(s)",
+            self.smoothed * 100.0,
+            self.samples
+        )
+    }
+}
+
+/// Proof size and execution-chain fee of one submitted trap
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TrapCost {
+    pub proof_bytes: usize,
+    pub gas_used: u128,
+    /// Fee paid for the trap transaction, in wei
+    pub cost_wei: u128,
+}
+
+impl TrapCost {
+    pub fn proof_kb(&self) -> f64 {
+        self.proof_bytes as f64 / 1024.0
+    }
+
+    /// Fee in the execution chain's native currency
+    pub fn cost_native(&self) -> f64 {
+        self.cost_wei as f64 / 1e18
+    }
+}
+
+/// Monitored contract a transaction was sent to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TxTarget {
+    PoolManager,
+    Router,
+    Other,
+}
+
+impl fmt::Display for TxTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::PoolManager => write!(f, "PoolMgr"),
+            Self::Router => write!(f, "Router"),
+            Self::Other => write!(f, "Other"),
+        }
+    }
+}
+
+/// Uniswap V4 `PoolKey` decoded from PoolManager calldata
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoolKeyInfo {
+    pub currency0: String,
+    pub currency1: String,
+    /// Fee tier in hundredths of a bip (3000 = 0.30%)
+    pub fee: u32,
+    pub tick_spacing: i32,
+    pub hooks: String,
+}
+
+impl fmt::Display for PoolKeyInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}.../{}... ({:.2}%)",
+            &self.currency0[0..6],
+            &self.currency1[0..6],
+            self.fee as f64 / 10_000.0
+        )
+    }
+}
+
+/// Shown in place of an address or hash that failed to parse upstream
+pub const UNKNOWN: &str = "unknown";
+
+/// `0x` followed by exactly `digits` hex digits
+fn is_hex_id(s: &str, digits: usize) -> bool {
+    s.len() == digits + 2 && s.starts_with("0x") && s[2..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
+pub fn is_address(s: &str) -> bool {
+    is_hex_id(s, 40)
+}
+
+pub fn is_tx_hash(s: &str) -> bool {
+    is_hex_id(s, 64)
+}
+
+/// `0xabcd...`, or `unknown` for a malformed address
+pub fn short_address(address: &str) -> String {
+    if is_address(address) {
+        format!("{}...", &address[..6])
+    } else {
+        UNKNOWN.to_string()
+    }
 }
 
 /// Summarized transaction for UI display
@@ -126,12 +1185,27 @@ pub struct PendingTransaction {
 pub struct TransactionSummary {
     pub hash: String, // Full hash for linking
     pub short_hash: String,
+    pub from: String, // Full sender, for allow/deny list edits
     pub from_short: String,
     pub to_short: String,
     pub value_eth: f64,
     pub gas_gwei: f64,
     pub suspicious: bool,
     pub probability: Option<f32>, // Added: Store AI Score
+    pub pool: Option<String>,     // Decoded V4 pool, if any
+    pub target: TxTarget,
+    pub target_address: Option<String>, // Which monitored contract matched
+    pub received_at: Instant,           // When the indexer saw it (for age/pruning)
+    pub blob_fee_gwei: Option<f64>,     // Max fee per blob gas; Some only for blob txs
+    pub context_note: Option<String>,   // Behavioral context from earlier transactions
+    pub decision: Option<DecisionTrace>, // How the trap decision was reached
+    pub gas_escalation: Option<GasEscalation>, // Replacement of an earlier broadcast
+    pub inference_failed: bool,         // Model could not score it (not a safe verdict)
+    pub address_score: Option<AddressScore>, // Sender's moving average, if enabled
+    pub unscored: Option<SkipReason>,   // Skipped before inference (transfer, low tip)
+    pub trap_cost: Option<TrapCost>,    // Proof size and fee, once trapped
+    pub label: Option<ReviewLabel>,     // Analyst verdict on the model's call
+    pub cluster: Option<Vec<String>>,   // Linked addresses that swapped in the same block
 }
 
 /// Feature vector extracted from a transaction for AI inference
@@ -166,6 +1240,14 @@ impl FeatureVector {
     }
 }
 
+/// Probability change attributed to one model input
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeatureContribution {
+    pub feature: &'static str,
+    /// Probability delta per +1σ of this feature (positive = more predator-like)
+    pub delta: f32,
+}
+
 // ═══════════════════════════════════════════════════════════════════════════
 //                          DETECTION TYPES
 // ═══════════════════════════════════════════════════════════════════════════
@@ -185,10 +1267,92 @@ pub struct Detection {
     pub latency: Duration,
     /// Reason for detection
     pub reason: DetectionReason,
+    /// Signed transaction bytes, 0x-hex (with `DETECTIONS_RAW_TX`)
+    pub raw_tx: Option<String>,
+    /// Chain head when detected
+    pub block: u64,
+    /// A reorg replaced `block` or an earlier one since: re-verify the trap
+    pub reorged: bool,
+    /// Normalized model input (with `DETECTION_FEATURES`)
+    pub features: Option<[f32; 6]>,
 }
 
-/// Reason for MEV detection
+/// Which step of the decision path settled a transaction's outcome
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecisionModifier {
+    /// `CONFIDENCE_THRESHOLD`
+    GlobalThreshold,
+    /// `REASON_THRESHOLDS` override for the classified reason
+    ReasonThreshold,
+    /// Threshold lowered for a sender that just approved the router/PoolManager
+    ApprovalContext,
+    /// Threshold lowered for a replacement broadcast with a higher bid
+    GasEscalation,
+    /// Flagged, but below `MIN_TRAP_VALUE_WEI`
+    ValueFloor,
+    /// Threshold lowered after more txs from the sender arrived during `TRAP_HOLD_MS`
+    Companions,
+    /// Sender is on the denylist: flagged whatever the score
+    Denylist,
+    /// Threshold lowered for a swap coordinated with another address in its cluster
+    Cluster,
+}
+
+impl fmt::Display for DecisionModifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::GlobalThreshold => write!(f, "global threshold"),
+            Self::ReasonThreshold => write!(f, "reason threshold"),
+            Self::ApprovalContext => write!(f, "approval context"),
+            Self::GasEscalation => write!(f, "gas escalation"),
+            Self::ValueFloor => write!(f, "value floor"),
+            Self::Companions => write!(f, "companion txs"),
+            Self::Denylist => write!(f, "denylist"),
+            Self::Cluster => write!(f, "address cluster"),
+        }
+    }
+}
+
+/// Per-transaction record of how the trap decision was reached
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecisionTrace {
+    /// Model output as read from the configured probability output
+    pub score: f32,
+    /// Sender's moving average, compared instead of `score` when enabled
+    pub smoothed: Option<f32>,
+    pub reason: DetectionReason,
+    /// Global threshold before any modifier
+    pub base_threshold: f32,
+    /// Threshold actually compared against `score`
+    pub threshold: f32,
+    /// Step that produced the final decision
+    pub decided_by: DecisionModifier,
+    /// Proceeds to proving/trapping
+    pub trap: bool,
+}
+
+impl fmt::Display for DecisionTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} [{}]: score {:.4}",
+            if self.trap { "TRAP" } else { "PASS" },
+            self.reason,
+            self.score
+        )?;
+        if let Some(smoothed) = self.smoothed {
+            write!(f, " (sender avg {:.4})", smoothed)?;
+        }
+        write!(
+            f,
+            " vs threshold {:.4} (base {:.4}), decided by {}",
+            self.threshold, self.base_threshold, self.decided_by
+        )
+    }
+}
+
+/// Reason for MEV detection
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum DetectionReason {
     HighGasFrontrun,
     SandwichPattern,
@@ -207,6 +1371,99 @@ impl fmt::Display for DetectionReason {
     }
 }
 
+impl std::str::FromStr for DetectionReason {
+    type Err = eyre::Report;
+
+    /// Accepts the variant name, case-insensitively (e.g. `HighGasFrontrun`)
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "highgasfrontrun" => Ok(Self::HighGasFrontrun),
+            "sandwichpattern" => Ok(Self::SandwichPattern),
+            "genericmev" => Ok(Self::GenericMEV),
+            "knownbotpattern" => Ok(Self::KnownBotPattern),
+            other => Err(eyre::eyre!("Unknown detection reason: {}", other)),
+        }
+    }
+}
+
+/// Why a scanned transaction did not end in an on-chain trap
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkipReason {
+    /// Model confidence stayed below the configured threshold
+    BelowThreshold,
+    /// Detected, but the transaction value is under the trap value floor
+    BelowValueFloor,
+    /// Address is already marked as a predator on-chain
+    AlreadyTrapped,
+    /// EZKL pipeline or proof artifact parsing failed
+    ProofFailed,
+    /// On-chain submission was rejected or errored
+    SubmitFailed,
+    /// EIP-4844 blob transaction; the model was not trained on its fee structure
+    BlobTransaction,
+    /// Signer wallet cannot cover the minimum balance or an estimated trap
+    InsufficientBalance,
+    /// Detected while running with `SCORING_ONLY` (no client to trap with)
+    ScoringOnly,
+    /// Detected within the first `WARMUP_TXS` transactions after startup
+    Warmup,
+    /// `PIPELINE_DEADLINE_MS` passed before the trap could be submitted
+    TooSlow,
+    /// The model failed to produce a score, even after a retry
+    InferenceFailed,
+    /// Empty calldata: a plain ETH transfer, not scored
+    PlainTransfer,
+    /// Priority fee under `MIN_PRIORITY_FEE_GWEI`: no frontrunning bid, not scored
+    LowPriorityFee,
+    /// Same sender as a more confident detection in this block, which carries the trap
+    Coalesced,
+    /// Re-sent with the same nonce during the trap hold; the replacement is scored instead
+    Replaced,
+    /// Sender is on the allowlist: never flagged, not scored
+    Allowlisted,
+    /// Calldata over `MAX_INPUT_BYTES` (e.g. a bundle), not scored
+    OversizedInput,
+    /// Proven, but dropped with `SUBMIT_QUEUE_MAX` submissions already waiting
+    SubmitQueueFull,
+    /// Detected outside `TRAP_SCHEDULE` and not held for the next window
+    OutsideSchedule,
+    /// Proven in warm standby: submissions withheld until armed
+    Disarmed,
+    /// Detected while the execution RPC was down (`EXECUTION_DEGRADE_AFTER`)
+    ExecutionDegraded,
+    /// Already mined when its trap was about to be proven (`SKIP_MINED`)
+    AlreadyMined,
+}
+
+impl fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BelowThreshold => write!(f, "Below Threshold"),
+            Self::BelowValueFloor => write!(f, "Below Value Floor"),
+            Self::AlreadyTrapped => write!(f, "Already Trapped"),
+            Self::ProofFailed => write!(f, "Proof Failed"),
+            Self::SubmitFailed => write!(f, "Submit Failed"),
+            Self::BlobTransaction => write!(f, "Blob Transaction"),
+            Self::InsufficientBalance => write!(f, "Insufficient Balance"),
+            Self::ScoringOnly => write!(f, "Scoring Only"),
+            Self::Warmup => write!(f, "Warmup"),
+            Self::TooSlow => write!(f, "Too Slow"),
+            Self::InferenceFailed => write!(f, "Inference Failed"),
+            Self::PlainTransfer => write!(f, "Plain Transfer"),
+            Self::LowPriorityFee => write!(f, "Low Priority Fee"),
+            Self::Coalesced => write!(f, "Coalesced"),
+            Self::Replaced => write!(f, "Replaced"),
+            Self::Allowlisted => write!(f, "Allowlisted"),
+            Self::OversizedInput => write!(f, "Oversized Input"),
+            Self::SubmitQueueFull => write!(f, "Submit Queue Full"),
+            Self::OutsideSchedule => write!(f, "Outside Schedule"),
+            Self::Disarmed => write!(f, "Disarmed"),
+            Self::ExecutionDegraded => write!(f, "Execution Degraded"),
+            Self::AlreadyMined => write!(f, "Already Mined"),
+        }
+    }
+}
+
 /// A signed detection ready for on-chain submission
 #[derive(Debug, Clone)]
 pub struct SignedDetection {
@@ -226,22 +1483,202 @@ pub struct NetworkStatus {
     pub chain_id: u64,
     pub gas_price: u128,
     pub connected: bool,
+    /// Listener dropped and is waiting to retry
+    pub reconnecting: bool,
     pub block_number: u64,
 }
 
 /// Sentinel statistics
 #[derive(Debug, Clone, Default)]
 pub struct SentinelStats {
+    pub total_observed: u64, // Hashes seen by the listener, sampled or not
     pub total_scanned: u64,
     pub total_detected: u64,
     pub total_trapped: u64, // AKA blocked
     pub uptime_secs: u64,
     pub zk_proofs_generated: u64, // Add this as well
     // Economic Impact
+    pub eth_saved: f64,           // In the monitored chain's native currency
+    pub gas_saved_wei: u128,      // Fees of trapped transactions
+    pub trap_gas_spent_wei: u128, // Fees we paid submitting traps (execution chain)
+    pub efficiency_boost: f32,    // Per EFFICIENCY_BASIS
+    pub history_saved: Vec<f64>,  // Cumulative ETH saved per trap, oldest first
+    pub saved_by_reason: HashMap<DetectionReason, ReasonSavings>, // Breakdown of the two above
+    // Pipeline Outcomes
+    pub skipped: SkipCounts,
+    pub gas_estimate_reverts: u64,
+    // Block Window
+    pub stats_block: u64,
+    pub detections_in_block: u64,
+    // Shadow Model (A/B)
+    pub shadow_compared: u64,
+    pub shadow_agreed: u64,
+}
+
+impl SentinelStats {
+    /// Share of observed hashes that reached the model (1.0 before any were seen)
+    pub fn sampling_rate(&self) -> f64 {
+        if self.total_observed == 0 {
+            return 1.0;
+        }
+        (self.total_scanned as f64 / self.total_observed as f64).min(1.0)
+    }
+
+    /// Trapped transactions as a percentage of `basis`. Against observed
+    /// traffic, this is the scanned rate scaled by the sampling rate.
+    pub fn efficiency(&self, basis: EfficiencyBasis) -> f32 {
+        if self.total_scanned == 0 {
+            return 0.0;
+        }
+        let scanned = self.total_trapped as f64 / self.total_scanned as f64 * 100.0;
+        match basis {
+            EfficiencyBasis::Scanned => scanned as f32,
+            EfficiencyBasis::Observed => (scanned * self.sampling_rate()) as f32,
+        }
+    }
+}
+
+/// RPC calls whose failures feed the TUI's RPC health line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RpcCall {
+    /// `eth_getTransactionByHash` on the mempool RPC
+    Fetch,
+    /// Gas estimate of a pending transaction on the execution RPC
+    Estimate,
+    /// Trap submission on the execution RPC
+    Submit,
+}
+
+impl RpcCall {
+    pub const ALL: [RpcCall; 3] = [RpcCall::Fetch, RpcCall::Estimate, RpcCall::Submit];
+
+    /// Endpoint the call goes to
+    pub fn endpoint(&self) -> &'static str {
+        match self {
+            Self::Fetch => "Mempool",
+            Self::Estimate | Self::Submit => "Exec",
+        }
+    }
+}
+
+impl fmt::Display for RpcCall {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Fetch => write!(f, "fetch"),
+            Self::Estimate => write!(f, "estimate"),
+            Self::Submit => write!(f, "submit"),
+        }
+    }
+}
+
+/// Failed RPC calls per kind: totals since the last reset, plus how many
+/// fell in the last `RpcHealth::WINDOW`
+#[derive(Debug, Clone, Default)]
+pub struct RpcHealth {
+    totals: [u64; 3],
+    recent: VecDeque<(Instant, RpcCall)>,
+}
+
+impl RpcHealth {
+    pub const WINDOW: Duration = Duration::from_secs(60);
+
+    pub fn record(&mut self, call: RpcCall, now: Instant) {
+        self.totals[call as usize] += 1;
+        self.recent.push_back((now, call));
+        while self
+            .recent
+            .front()
+            .is_some_and(|(at, _)| now.saturating_duration_since(*at) > Self::WINDOW)
+        {
+            self.recent.pop_front();
+        }
+    }
+
+    pub fn total(&self, call: RpcCall) -> u64 {
+        self.totals[call as usize]
+    }
+
+    /// Failures of `call` within `WINDOW` of `now`
+    pub fn recent(&self, call: RpcCall, now: Instant) -> usize {
+        self.recent
+            .iter()
+            .filter(|(at, c)| *c == call && now.saturating_duration_since(*at) <= Self::WINDOW)
+            .count()
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// Weighted savings credited to traps of one detection reason
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ReasonSavings {
+    pub trapped: u64,
     pub eth_saved: f64,
-    pub gas_saved: u128,
-    pub efficiency_boost: f32,
-    pub history_saved: Vec<u64>,
+    pub gas_saved_wei: u128,
+}
+
+/// Per-reason counters for transactions that were not trapped
+#[derive(Debug, Clone, Default)]
+pub struct SkipCounts {
+    pub below_threshold: u64,
+    pub below_value_floor: u64,
+    pub already_trapped: u64,
+    pub proof_failed: u64,
+    pub submit_failed: u64,
+    pub blob: u64,
+    pub low_balance: u64,
+    pub scoring_only: u64,
+    pub warmup: u64,
+    pub too_slow: u64,
+    pub inference_failed: u64,
+    pub plain_transfer: u64,
+    /// Inferences saved by the priority fee pre-filter
+    pub low_priority_fee: u64,
+    /// Proofs and traps saved by coalescing a sender's detections per block
+    pub coalesced: u64,
+    pub replaced: u64,
+    pub allowlisted: u64,
+    /// Gas estimates, inferences and proofs saved by the input size limit
+    pub oversized_input: u64,
+    /// Proven detections dropped by submission pacing
+    pub submit_queue_full: u64,
+    /// Detected outside `TRAP_SCHEDULE` and not held for the next window
+    pub outside_schedule: u64,
+    pub disarmed: u64,
+    pub execution_degraded: u64,
+    pub already_mined: u64,
+}
+
+impl SkipCounts {
+    /// Increment the counter matching `reason`
+    pub fn record(&mut self, reason: SkipReason) {
+        match reason {
+            SkipReason::BelowThreshold => self.below_threshold += 1,
+            SkipReason::BelowValueFloor => self.below_value_floor += 1,
+            SkipReason::AlreadyTrapped => self.already_trapped += 1,
+            SkipReason::ProofFailed => self.proof_failed += 1,
+            SkipReason::SubmitFailed => self.submit_failed += 1,
+            SkipReason::BlobTransaction => self.blob += 1,
+            SkipReason::InsufficientBalance => self.low_balance += 1,
+            SkipReason::ScoringOnly => self.scoring_only += 1,
+            SkipReason::Warmup => self.warmup += 1,
+            SkipReason::TooSlow => self.too_slow += 1,
+            SkipReason::InferenceFailed => self.inference_failed += 1,
+            SkipReason::PlainTransfer => self.plain_transfer += 1,
+            SkipReason::LowPriorityFee => self.low_priority_fee += 1,
+            SkipReason::Coalesced => self.coalesced += 1,
+            SkipReason::Replaced => self.replaced += 1,
+            SkipReason::Allowlisted => self.allowlisted += 1,
+            SkipReason::OversizedInput => self.oversized_input += 1,
+            SkipReason::SubmitQueueFull => self.submit_queue_full += 1,
+            SkipReason::OutsideSchedule => self.outside_schedule += 1,
+            SkipReason::Disarmed => self.disarmed += 1,
+            SkipReason::ExecutionDegraded => self.execution_degraded += 1,
+            SkipReason::AlreadyMined => self.already_mined += 1,
+        }
+    }
 }
 
 // ═══════════════════════════════════════════════════════════════════════════
@@ -265,7 +1702,72 @@ pub enum UiMessage {
     LatencyUpdate(u64),
     ConfidenceUpdate(String, f32), // Changed: Hash + Score
     ProcessingUpdate(ProcessingStage),
-    Log(String), // New: Operation Log
+    Log(String),                                         // New: Operation Log
+    ExecutionGasUpdate(u128), // Gas price (wei) on the chain we submit to
+    ExecutionNetworkUpdate(NetworkStatus), // Connection/head of the chain we submit to
+    FeatureImportance(String, Vec<FeatureContribution>), // Hash + ranked contributors
+    WalletBalanceUpdate(u128, bool), // Lowest signer balance (wei), below minimum?
+    ContextNote(String, String), // Hash + behavioral note for the insight panel
+    DecisionTrace(String, DecisionTrace), // Hash + resolved decision path
+    WarmupProgress(u64, u64), // Scanned so far, warmup length
+    TrapWindow(bool),         // Inside a TRAP_SCHEDULE window
+    Reorg(Reorg),             // Headers seen earlier were replaced
+    RpcError(RpcCall),        // An RPC call failed (not a revert)
+    ExecutionDegraded(bool),  // Execution RPC down: scoring only until it returns
+    SenderHistory(String, u64), // Detected sender + its transaction count
+    Cluster(String, Vec<String>), // Hash + sender's cluster, sender first
+    InferenceFailed(String),  // Hash the model could not score
+    AddressScore(String, AddressScore), // Hash + sender's moving average after it
+    Unscored(String, SkipReason), // Hash skipped before inference, and why
+    TrapCost(String, TrapCost), // Hash + what trapping it cost
+    StartupSummary(Vec<String>), // Resolved config, shown briefly at launch
+}
+
+/// Sending half of the UI channel.
+///
+/// After `q` the UI receiver is dropped while background tasks are still
+/// running until they are aborted. Sends then go to the log instead of
+/// vanishing: the first one warns, detections and operation logs keep
+/// their lines, and the rest drop to debug.
+#[derive(Debug, Clone)]
+pub struct UiSender {
+    inner: tokio::sync::mpsc::UnboundedSender<UiMessage>,
+    closed: std::sync::Arc<std::sync::atomic::AtomicBool>,
+}
+
+impl UiSender {
+    pub fn new(inner: tokio::sync::mpsc::UnboundedSender<UiMessage>) -> Self {
+        Self {
+            inner,
+            closed: Default::default(),
+        }
+    }
+
+    /// Deliver `msg` to the UI, or log it if the UI has exited. Returns
+    /// whether the UI received it.
+    pub fn send(&self, msg: UiMessage) -> bool {
+        let Err(lost) = self.inner.send(msg) else {
+            return true;
+        };
+        if !self.closed.swap(true, std::sync::atomic::Ordering::Relaxed) {
+            tracing::warn!("UI has exited; UI messages now go to the log only");
+        }
+        match lost.0 {
+            UiMessage::Log(line) => tracing::info!("[UI LOG] {}", line),
+            UiMessage::NewDetection(d) => tracing::info!(
+                "[DETECTED] Bot: {} (Confidence: {:.4})",
+                d.bot_address,
+                d.confidence
+            ),
+            other => tracing::debug!("UI gone, dropped {:?}", other),
+        }
+        false
+    }
+
+    /// The UI receiver is gone; skip work that only feeds the UI
+    pub fn is_closed(&self) -> bool {
+        self.inner.is_closed()
+    }
 }
 
 /// Helper enum for ZK processing stages state updates
@@ -298,4 +1800,140 @@ pub struct AppState {
     pub logs_area: (u16, u16, u16, u16),
     pub logs: Vec<String>, // New: Operation Logs
     pub status_message: Option<(String, std::time::Instant)>, // UI Feedback (Message, Time)
+    pub layout: PanelLayout,
+    pub execution_gas_price: Option<u128>, // wei, polled from the execution RPC
+    pub execution_network: Option<NetworkStatus>, // None until polled (or scoring-only)
+    pub explanation: Option<(String, Vec<FeatureContribution>)>, // Hash + ranked contributors
+    pub units: crate::chains::Units,       // Currencies and decimals for amounts
+    pub model_task: ModelTask,             // Scores are probabilities or expected profits
+    pub score_display: ScoreDisplay,       // How probabilities are shown
+    pub warmup: Option<(u64, u64)>,        // Scanned so far, warmup length
+    pub trap_window: Option<bool>,         // None = no schedule
+    pub reorgs: u64,                       // Reorgs seen by the indexer
+    pub rpc_health: RpcHealth,             // Failed RPC calls, reset with `r`
+    pub execution_degraded: bool,          // Execution RPC down, scoring only
+    pub sender_tx_counts: HashMap<String, u64>, // Detected senders (lowercase) by transaction count
+    pub armed: Option<std::sync::Arc<std::sync::atomic::AtomicBool>>, // None = scoring only
+    pub arm_requested: Option<std::time::Instant>, // First `A` press, awaiting confirmation
+    pub wallet_balance: Option<(u128, bool)>, // Lowest signer balance (wei), below minimum?
+    pub startup_summary: Option<(Vec<String>, std::time::Instant)>, // Lines + when received
+    pub labels: HashMap<String, ReviewLabel>, // Analyst labels by tx hash, persisted in DATASET_CSV
+}
+
+/// Dashboard panels that can be hidden or shown full-screen
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Panel {
+    Mempool,
+    Insight,
+    Economic,
+    Logs,
+}
+
+impl Panel {
+    /// Full-screen cycle order
+    pub const ALL: [Panel; 4] = [Panel::Mempool, Panel::Insight, Panel::Economic, Panel::Logs];
+}
+
+/// Which panels `ui()` lays out; the mempool table is never hidden
+#[derive(Debug, Clone, Default)]
+pub struct PanelLayout {
+    pub hide_insight: bool,
+    pub hide_economic: bool,
+    pub hide_logs: bool,
+    /// When set, this panel takes the whole area below the header
+    pub fullscreen: Option<Panel>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shortens_only_well_formed_addresses() {
+        assert_eq!(
+            short_address(&format!("0xAbCd{}", "0".repeat(36))),
+            "0xAbCd..."
+        );
+        assert_eq!(short_address("0x12"), UNKNOWN);
+        assert_eq!(short_address(&format!("0xzz{}", "0".repeat(38))), UNKNOWN);
+        assert!(!is_tx_hash(&format!("0x{}", "0".repeat(40))));
+    }
+
+    #[test]
+    fn ui_sender_reports_a_closed_ui() {
+        let (inner, rx) = tokio::sync::mpsc::unbounded_channel();
+        let sender = UiSender::new(inner);
+        assert!(sender.send(UiMessage::Log("up".to_string())));
+        assert!(!sender.is_closed());
+
+        drop(rx);
+        assert!(sender.is_closed());
+        assert!(!sender.send(UiMessage::Log("after q".to_string())));
+    }
+
+    #[test]
+    fn efficiency_accounts_for_sampling() {
+        let mut stats = SentinelStats {
+            total_scanned: 200,
+            total_trapped: 4,
+            ..Default::default()
+        };
+        // Nothing observed yet: both bases agree
+        assert_eq!(stats.sampling_rate(), 1.0);
+        assert_eq!(stats.efficiency(EfficiencyBasis::Observed), 2.0);
+
+        // A quarter of the mempool reached the model
+        stats.total_observed = 800;
+        assert_eq!(stats.sampling_rate(), 0.25);
+        assert_eq!(stats.efficiency(EfficiencyBasis::Scanned), 2.0);
+        assert_eq!(stats.efficiency(EfficiencyBasis::Observed), 0.5);
+
+        assert_eq!(
+            "Scanned".parse::<EfficiencyBasis>().unwrap(),
+            EfficiencyBasis::Scanned
+        );
+        assert!("sampled".parse::<EfficiencyBasis>().is_err());
+    }
+
+    #[test]
+    fn score_display_modes_agree_on_the_score() {
+        let modes: Vec<String> = [
+            ScoreDisplay::Percent,
+            ScoreDisplay::Raw,
+            ScoreDisplay::LogOdds,
+        ]
+        .iter()
+        .map(|d| d.probability(0.9))
+        .collect();
+        assert_eq!(modes, ["90.0%", "0.9000", "+2.197"]);
+        // Certainty stays finite
+        assert!(ScoreDisplay::LogOdds.probability(1.0).starts_with("+13.8"));
+        assert_eq!(ScoreDisplay::Percent.probability_delta(-0.05), "-5.0%");
+        assert_eq!(ScoreDisplay::LogOdds.next(), ScoreDisplay::Percent);
+        assert_eq!(
+            "odds".parse::<ScoreDisplay>().unwrap(),
+            ScoreDisplay::LogOdds
+        );
+        assert!("ratio".parse::<ScoreDisplay>().is_err());
+    }
+
+    #[test]
+    fn rpc_health_counts_totals_and_the_last_minute() {
+        let start = Instant::now();
+        let mut health = RpcHealth::default();
+        health.record(RpcCall::Fetch, start);
+        health.record(RpcCall::Submit, start);
+        let later = start + Duration::from_secs(90);
+        health.record(RpcCall::Fetch, later);
+
+        assert_eq!(health.total(RpcCall::Fetch), 2);
+        assert_eq!(health.recent(RpcCall::Fetch, later), 1);
+        assert_eq!(health.total(RpcCall::Submit), 1);
+        assert_eq!(health.recent(RpcCall::Submit, later), 0);
+        assert_eq!(health.total(RpcCall::Estimate), 0);
+
+        health.reset();
+        assert_eq!(health.total(RpcCall::Fetch), 0);
+        assert_eq!(health.recent(RpcCall::Fetch, later), 0);
+    }
 }
@@ -1,9 +1,16 @@
-use crate::types::AppState;
+use super::theme::Theme;
+use crate::types::{AppState, Detection, Panel, Reorg};
 use ratatui::widgets::TableState;
+use std::sync::atomic::Ordering;
+use std::time::{Duration, Instant};
+
+/// Second `A` press needed within this long to arm
+pub const ARM_CONFIRM_WINDOW: Duration = Duration::from_secs(5);
 
 pub struct App {
     pub state: AppState,
     pub table_state: TableState,
+    pub theme: Theme,
 }
 
 impl App {
@@ -11,6 +18,7 @@ impl App {
         let app = Self {
             state: AppState::default(),
             table_state: TableState::default(),
+            theme: Theme::default(),
         };
         // Verify state init
         app
@@ -48,9 +56,251 @@ impl App {
         self.table_state.select(None);
     }
 
+    /// Show/hide a side panel
+    pub fn toggle_panel(&mut self, panel: Panel) {
+        let layout = &mut self.state.layout;
+        match panel {
+            Panel::Insight => layout.hide_insight = !layout.hide_insight,
+            Panel::Economic => layout.hide_economic = !layout.hide_economic,
+            Panel::Logs => layout.hide_logs = !layout.hide_logs,
+            Panel::Mempool => {}
+        }
+    }
+
+    /// Cycle full-screen: off -> Mempool -> Insight -> Economic -> Logs -> off
+    pub fn cycle_fullscreen(&mut self) {
+        let layout = &mut self.state.layout;
+        layout.fullscreen = match layout.fullscreen {
+            None => Some(Panel::ALL[0]),
+            Some(current) => Panel::ALL
+                .iter()
+                .position(|p| *p == current)
+                .and_then(|i| Panel::ALL.get(i + 1).copied()),
+        };
+    }
+
+    /// Drop rows older than `max_age` at `now`. Rows are newest-first, so
+    /// stale ones form the tail; a selection past the new end is cleared.
+    pub fn prune_stale(&mut self, max_age: Duration, now: Instant) {
+        let txs = &mut self.state.recent_transactions;
+        if let Some(first_stale) = txs
+            .iter()
+            .position(|t| now.saturating_duration_since(t.received_at) > max_age)
+        {
+            txs.truncate(first_stale);
+            if self
+                .table_state
+                .selected()
+                .is_some_and(|i| i >= first_stale)
+            {
+                self.table_state.select(None);
+            }
+        }
+    }
+
+    /// Whether submissions are enabled; `None` without a client (scoring only)
+    pub fn armed(&self) -> Option<bool> {
+        self.state
+            .armed
+            .as_ref()
+            .map(|armed| armed.load(Ordering::Relaxed))
+    }
+
+    /// Handle the arm key at `now`: standing down is immediate, arming needs
+    /// a second press within `ARM_CONFIRM_WINDOW`. Returns the status to show.
+    pub fn toggle_armed(&mut self, now: Instant) -> Option<String> {
+        let armed = self.state.armed.clone()?;
+        if armed.load(Ordering::Relaxed) {
+            armed.store(false, Ordering::Relaxed);
+            self.state.arm_requested = None;
+            tracing::warn!("Agent disarmed from the TUI: submissions withheld");
+            return Some("SAFE: submissions withheld".to_string());
+        }
+        match self.state.arm_requested.take() {
+            Some(at) if now.saturating_duration_since(at) <= ARM_CONFIRM_WINDOW => {
+                armed.store(true, Ordering::Relaxed);
+                tracing::warn!("Agent ARMED from the TUI: detections will be trapped");
+                Some("ARMED: detections will be trapped".to_string())
+            }
+            _ => {
+                self.state.arm_requested = Some(now);
+                Some(format!(
+                    "Press A again within {}s to arm",
+                    ARM_CONFIRM_WINDOW.as_secs()
+                ))
+            }
+        }
+    }
+
+    /// Record a detection newest-first, dropping the oldest beyond `cap`
+    pub fn push_detection(&mut self, detection: Detection, cap: usize) {
+        let detections = &mut self.state.recent_detections;
+        detections.insert(0, detection);
+        detections.truncate(cap.max(1));
+    }
+
+    /// Flag detections made at or after a reorged block; returns the newly
+    /// flagged transaction hashes
+    pub fn flag_reorg(&mut self, reorg: Reorg) -> Vec<String> {
+        self.state.reorgs += 1;
+        self.state
+            .recent_detections
+            .iter_mut()
+            .filter(|d| !d.reorged && d.block >= reorg.first_block)
+            .map(|d| {
+                d.reorged = true;
+                d.tx_hash.clone()
+            })
+            .collect()
+    }
+
     pub fn select_index(&mut self, index: usize) {
         if index < self.state.recent_transactions.len() {
             self.table_state.select(Some(index));
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{TransactionSummary, TxTarget};
+    use std::time::Instant;
+
+    fn row(hash: &str, received_at: Instant) -> TransactionSummary {
+        TransactionSummary {
+            hash: hash.to_string(),
+            short_hash: hash.to_string(),
+            from: String::new(),
+            from_short: String::new(),
+            to_short: String::new(),
+            value_eth: 0.0,
+            gas_gwei: 0.0,
+            suspicious: false,
+            probability: None,
+            pool: None,
+            target: TxTarget::Other,
+            target_address: None,
+            received_at,
+            blob_fee_gwei: None,
+            context_note: None,
+            decision: None,
+            gas_escalation: None,
+            inference_failed: false,
+            address_score: None,
+            unscored: None,
+            trap_cost: None,
+            label: None,
+            cluster: None,
+        }
+    }
+
+    #[test]
+    fn prunes_stale_tail_and_clears_dangling_selection() {
+        let mut app = App::new();
+        // Ages 1s, 30s and 300s as of `now`
+        let start = Instant::now();
+        let now = start + Duration::from_secs(300);
+        app.state.recent_transactions = vec![
+            row("a", start + Duration::from_secs(299)),
+            row("b", start + Duration::from_secs(270)),
+            row("c", start),
+        ];
+
+        app.select_index(1);
+        app.prune_stale(Duration::from_secs(60), now);
+        assert_eq!(app.state.recent_transactions.len(), 2);
+        assert_eq!(app.table_state.selected(), Some(1));
+
+        app.prune_stale(Duration::from_secs(10), now);
+        assert_eq!(app.state.recent_transactions.len(), 1);
+        assert_eq!(app.table_state.selected(), None);
+    }
+
+    #[test]
+    fn caps_detections_keeping_newest() {
+        let mut app = App::new();
+        for n in 0..5 {
+            app.push_detection(
+                Detection {
+                    bot_address: format!("0x{}", n),
+                    tx_hash: format!("0x{}", n),
+                    confidence: 0.95,
+                    detected_at: chrono::Utc::now(),
+                    latency: Duration::from_millis(1),
+                    reason: crate::types::DetectionReason::GenericMEV,
+                    raw_tx: None,
+                    block: n,
+                    reorged: false,
+                    features: None,
+                },
+                3,
+            );
+        }
+
+        let hashes: Vec<_> = app
+            .state
+            .recent_detections
+            .iter()
+            .map(|d| d.tx_hash.as_str())
+            .collect();
+        assert_eq!(hashes, ["0x4", "0x3", "0x2"]);
+    }
+
+    #[test]
+    fn reorg_flags_detections_from_the_replaced_block() {
+        let mut app = App::new();
+        for block in [10, 11, 12] {
+            app.push_detection(
+                Detection {
+                    bot_address: "0xb0".to_string(),
+                    tx_hash: format!("0x{}", block),
+                    confidence: 0.95,
+                    detected_at: chrono::Utc::now(),
+                    latency: Duration::from_millis(1),
+                    reason: crate::types::DetectionReason::GenericMEV,
+                    raw_tx: None,
+                    block,
+                    reorged: false,
+                    features: None,
+                },
+                10,
+            );
+        }
+
+        let reorg = Reorg {
+            first_block: 11,
+            depth: 2,
+        };
+        assert_eq!(app.flag_reorg(reorg), ["0x12", "0x11"]);
+        // Already flagged: not reported twice
+        assert!(app.flag_reorg(reorg).is_empty());
+        assert_eq!(app.state.reorgs, 2);
+        assert!(!app.state.recent_detections[2].reorged);
+    }
+
+    #[test]
+    fn arming_needs_confirmation_but_standing_down_does_not() {
+        let mut app = App::new();
+        let start = Instant::now();
+        assert_eq!(app.toggle_armed(start), None);
+
+        let switch = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+        app.state.armed = Some(switch.clone());
+        assert!(app
+            .toggle_armed(start)
+            .unwrap()
+            .starts_with("Press A again"));
+        assert_eq!(app.armed(), Some(false));
+
+        // Too late: the second press starts a new confirmation
+        let late = start + ARM_CONFIRM_WINDOW + Duration::from_secs(1);
+        app.toggle_armed(late);
+        assert_eq!(app.armed(), Some(false));
+        app.toggle_armed(late + Duration::from_secs(1));
+        assert!(switch.load(Ordering::Relaxed));
+
+        app.toggle_armed(late + Duration::from_secs(2));
+        assert_eq!(app.armed(), Some(false));
+    }
+}
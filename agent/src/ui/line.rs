@@ -0,0 +1,80 @@
+//! Line-mode output: one compact, timestamped stdout line per significant
+//! event, for tmux panes, CI and `tee` (no alternate screen).
+
+use crate::chains::Units;
+use crate::types::UiMessage;
+use chrono::Local;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::UnboundedReceiver;
+
+/// Minimum spacing between stats snapshot lines
+const STATS_INTERVAL: Duration = Duration::from_secs(10);
+
+pub async fn run_line_mode(mut rx: UnboundedReceiver<UiMessage>, units: Units) {
+    let mut last_stats: Option<Instant> = None;
+    let mut connected: Option<bool> = None;
+
+    while let Some(msg) = rx.recv().await {
+        let ts = Local::now().format("%H:%M:%S");
+        match msg {
+            UiMessage::NewDetection(d) => {
+                println!(
+                    "{} DETECT {} bot={} p={:.4} ({})",
+                    ts, d.tx_hash, d.bot_address, d.confidence, d.reason
+                );
+            }
+            UiMessage::Log(s) => println!("{} LOG {}", ts, s),
+            UiMessage::StartupSummary(lines) => {
+                for line in lines {
+                    println!("{} CONFIG {}", ts, line);
+                }
+            }
+            UiMessage::InferenceFailed(hash) => println!("{} INFER_FAIL {}", ts, hash),
+            UiMessage::SenderHistory(address, count) => println!(
+                "{} HISTORY {} txs={} ({})",
+                ts,
+                address,
+                count,
+                crate::history::activity_label(count)
+            ),
+            UiMessage::TrapCost(hash, cost) => println!(
+                "{} TRAP_COST {} {}",
+                ts,
+                hash,
+                super::format_trap_cost(&cost, &units)
+            ),
+            UiMessage::StatsUpdate(stats)
+                if last_stats.is_none_or(|t| t.elapsed() >= STATS_INTERVAL) =>
+            {
+                last_stats = Some(Instant::now());
+                println!(
+                    "{} STATS observed={} scanned={} detected={} trapped={} proofs={} saved={}",
+                    ts,
+                    stats.total_observed,
+                    stats.total_scanned,
+                    stats.total_detected,
+                    stats.total_trapped,
+                    stats.zk_proofs_generated,
+                    units.monitored_amount(stats.eth_saved)
+                );
+            }
+            // Only report connectivity transitions, not every block
+            UiMessage::NetworkUpdate(status) if connected != Some(status.connected) => {
+                connected = Some(status.connected);
+                println!(
+                    "{} NET {} block=#{}",
+                    ts,
+                    if status.connected {
+                        "ONLINE"
+                    } else if status.reconnecting {
+                        "RECONNECTING"
+                    } else {
+                        "OFFLINE"
+                    },
+                    status.block_number
+                );
+            }
+            _ => {}
+        }
+    }
+}
@@ -1,6 +1,13 @@
 pub mod app;
+pub mod line;
+pub mod theme;
 
-use crate::types::UiMessage;
+use crate::chains::Units;
+use crate::lists::{ListKind, SharedLists};
+use crate::types::{
+    is_address, is_tx_hash, short_address, EfficiencyBasis, ModelTask, Panel, ReviewLabel, RpcCall,
+    ScoreDisplay, SentinelStats, SkipReason, TrapCost, UiMessage, UNKNOWN,
+};
 use app::App;
 use chrono::Local;
 use crossterm::{
@@ -12,31 +19,68 @@ use eyre::Result;
 use ratatui::{
     backend::CrosstermBackend,
     layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Cell, List, ListItem, Paragraph, Row, Sparkline, Table, Wrap},
+    widgets::{
+        Block, Borders, Cell, Clear, List, ListItem, Paragraph, Row, Sparkline, Table, Wrap,
+    },
     Frame, Terminal,
 };
+use std::sync::{atomic::AtomicBool, Arc};
 use std::{io, time::Duration};
-use tokio::sync::mpsc::UnboundedReceiver;
+use theme::Theme;
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+
+/// How long the startup summary stays over the dashboard
+const STARTUP_SUMMARY_DURATION: Duration = Duration::from_secs(8);
 
+#[allow(clippy::too_many_arguments)]
 pub async fn run_tui(
     mut rx: UnboundedReceiver<UiMessage>,
+    explain_sender: UnboundedSender<String>,
+    lists: SharedLists,
+    dataset_csv: Option<String>,
     confidence_threshold: f32,
+    model_task: ModelTask,
+    tx_max_age: Option<Duration>,
+    mouse_capture: bool,
+    units: Units,
+    max_detections: usize,
+    theme: Theme,
+    score_display: ScoreDisplay,
+    arm_switch: Option<Arc<AtomicBool>>,
 ) -> Result<()> {
     // Setup Terminal
+    install_panic_hook(mouse_capture);
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+    execute!(stdout, EnterAlternateScreen)?;
+    // Without capture the terminal keeps native text selection (e.g. in tmux)
+    if mouse_capture {
+        execute!(stdout, EnableMouseCapture)?;
+    }
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
     // Create App State
     let mut app = App::new();
+    app.state.units = units;
+    app.state.model_task = model_task;
+    app.state.score_display = score_display;
+    app.state.armed = arm_switch;
+    app.theme = theme;
+    if let Some(path) = dataset_csv.as_deref() {
+        match crate::dataset::load_labels(path) {
+            Ok(labels) => app.state.labels = labels,
+            Err(e) => tracing::warn!("Failed to load labels: {:?}", e),
+        }
+    }
+    let started = std::time::Instant::now();
 
     // Main Loop
     let tick_rate = Duration::from_millis(100);
     let mut last_tick = std::time::Instant::now();
+    let mut last_explain_request: Option<String> = None;
 
     loop {
         terminal.draw(|f| ui(f, &mut app))?;
@@ -44,7 +88,8 @@ pub async fn run_tui(
         // Handle Channel Messages (Non-blocking check)
         while let Ok(msg) = rx.try_recv() {
             match msg {
-                UiMessage::NewTransaction(tx) => {
+                UiMessage::NewTransaction(mut tx) => {
+                    tx.label = app.state.labels.get(&tx.hash).copied();
                     app.state.recent_transactions.insert(0, tx);
                     if app.state.recent_transactions.len() > 100 {
                         app.state.recent_transactions.pop();
@@ -56,7 +101,7 @@ pub async fn run_tui(
                     }
                 }
                 UiMessage::NewDetection(d) => {
-                    app.state.recent_detections.insert(0, d);
+                    app.push_detection(d, max_detections);
                 }
                 UiMessage::NetworkUpdate(status) => {
                     app.state.network = status;
@@ -82,10 +127,10 @@ pub async fn run_tui(
                             tx.suspicious = true;
                             // Add to operation log
                             let log_msg = format!(
-                                "{} [MATCH] Bot Detected: {} ({:.1}%)",
+                                "{} [MATCH] Bot Detected: {} ({})",
                                 Local::now().format("%H:%M:%S"),
                                 tx.short_hash,
-                                c * 100.0
+                                format_score(c, model_task, app.state.score_display, &units)
                             );
                             app.state.logs.push(log_msg);
                         } else {
@@ -106,12 +151,157 @@ pub async fn run_tui(
                 UiMessage::LatencyUpdate(l) => {
                     app.state.latency_ms = l;
                 }
+                UiMessage::ExecutionGasUpdate(price) => {
+                    app.state.execution_gas_price = Some(price);
+                }
+                UiMessage::ContextNote(hash, note) => {
+                    if let Some(tx) = app
+                        .state
+                        .recent_transactions
+                        .iter_mut()
+                        .find(|t| t.hash == hash)
+                    {
+                        tx.context_note = Some(note);
+                    }
+                }
+                UiMessage::AddressScore(hash, score) => {
+                    if let Some(tx) = app
+                        .state
+                        .recent_transactions
+                        .iter_mut()
+                        .find(|t| t.hash == hash)
+                    {
+                        tx.address_score = Some(score);
+                    }
+                }
+                UiMessage::DecisionTrace(hash, trace) => {
+                    if let Some(tx) = app
+                        .state
+                        .recent_transactions
+                        .iter_mut()
+                        .find(|t| t.hash == hash)
+                    {
+                        tx.decision = Some(trace);
+                    }
+                }
+                UiMessage::ExecutionNetworkUpdate(status) => {
+                    app.state.execution_network = Some(status);
+                }
+                UiMessage::ExecutionDegraded(degraded) => {
+                    app.state.execution_degraded = degraded;
+                }
+                UiMessage::Unscored(hash, reason) => {
+                    if let Some(tx) = app
+                        .state
+                        .recent_transactions
+                        .iter_mut()
+                        .find(|t| t.hash == hash)
+                    {
+                        tx.unscored = Some(reason);
+                    }
+                }
+                UiMessage::TrapCost(hash, cost) => {
+                    if let Some(tx) = app
+                        .state
+                        .recent_transactions
+                        .iter_mut()
+                        .find(|t| t.hash == hash)
+                    {
+                        tx.trap_cost = Some(cost);
+                    }
+                }
+                UiMessage::StartupSummary(lines) => {
+                    app.state.startup_summary = Some((lines, std::time::Instant::now()));
+                }
+                UiMessage::WarmupProgress(seen, total) => {
+                    app.state.warmup = Some((seen, total));
+                }
+                UiMessage::TrapWindow(active) => {
+                    app.state.trap_window = Some(active);
+                }
+                UiMessage::RpcError(call) => {
+                    app.state.rpc_health.record(call, std::time::Instant::now());
+                }
+                UiMessage::Cluster(hash, members) => {
+                    if let Some(tx) = app
+                        .state
+                        .recent_transactions
+                        .iter_mut()
+                        .find(|t| t.hash == hash)
+                    {
+                        tx.cluster = Some(members);
+                    }
+                }
+                UiMessage::SenderHistory(address, count) => {
+                    app.state
+                        .sender_tx_counts
+                        .insert(address.to_lowercase(), count);
+                }
+                UiMessage::Reorg(reorg) => {
+                    let flagged = app.flag_reorg(reorg);
+                    let mut msg = format!(
+                        "Reorg from block #{} (depth {})",
+                        reorg.first_block, reorg.depth
+                    );
+                    if !flagged.is_empty() {
+                        tracing::warn!(
+                            "Detections to re-verify after reorg at #{}: {}",
+                            reorg.first_block,
+                            flagged.join(", ")
+                        );
+                        msg.push_str(&format!(": {} detection(s) to re-verify", flagged.len()));
+                    }
+                    app.state
+                        .logs
+                        .push(format!("{} {}", Local::now().format("%H:%M:%S"), msg));
+                    if app.state.logs.len() > 50 {
+                        app.state.logs.remove(0);
+                    }
+                }
+                UiMessage::WalletBalanceUpdate(balance, low) => {
+                    app.state.wallet_balance = Some((balance, low));
+                }
+                UiMessage::FeatureImportance(hash, contributions) => {
+                    app.state.explanation = Some((hash, contributions));
+                }
+                UiMessage::InferenceFailed(hash) => {
+                    if let Some(tx) = app
+                        .state
+                        .recent_transactions
+                        .iter_mut()
+                        .find(|t| t.hash == hash)
+                    {
+                        tx.inference_failed = true;
+                        app.state.logs.push(format!(
+                            "{} [ERROR] Inference failed: {}",
+                            Local::now().format("%H:%M:%S"),
+                            tx.short_hash
+                        ));
+                    }
+                }
                 UiMessage::ProcessingUpdate(_) => {
                     // TODO: Add logs handling for processing stages
                 }
             }
         }
 
+        // Keep the live view live: age out rows that were mined or dropped
+        if let Some(max_age) = tx_max_age {
+            app.prune_stale(max_age, std::time::Instant::now());
+        }
+
+        // Explanations are on-demand: ask once the selected tx has been scored
+        if let Some(tx) = app
+            .table_state
+            .selected()
+            .and_then(|i| app.state.recent_transactions.get(i))
+            .filter(|tx| tx.probability.is_some())
+            .filter(|tx| last_explain_request.as_deref() != Some(tx.hash.as_str()))
+        {
+            last_explain_request = Some(tx.hash.clone());
+            let _ = explain_sender.send(tx.hash.clone());
+        }
+
         // Handle Inputs
         let timeout = tick_rate
             .checked_sub(last_tick.elapsed())
@@ -126,6 +316,39 @@ pub async fn run_tui(
                     KeyCode::Down => app.next(),
                     KeyCode::Up => app.previous(),
                     KeyCode::Esc => app.unselect(),
+                    // Layout: 1/2/3 toggle side panels, f cycles full-screen
+                    KeyCode::Char('1') => app.toggle_panel(Panel::Insight),
+                    KeyCode::Char('2') => app.toggle_panel(Panel::Economic),
+                    KeyCode::Char('3') => app.toggle_panel(Panel::Logs),
+                    KeyCode::Char('f') => app.cycle_fullscreen(),
+                    // Arm (confirmed with a second press) or stand down
+                    KeyCode::Char('A') => {
+                        if let Some(msg) = app.toggle_armed(std::time::Instant::now()) {
+                            app.state.status_message = Some((msg, std::time::Instant::now()));
+                        }
+                    }
+                    // Start the RPC health counters over
+                    KeyCode::Char('r') => {
+                        app.state.rpc_health.reset();
+                        app.state.status_message = Some((
+                            "RPC error counters reset".to_string(),
+                            std::time::Instant::now(),
+                        ));
+                    }
+                    // Probabilities as percent, raw 0-1 or log-odds
+                    KeyCode::Char('p') => {
+                        app.state.score_display = app.state.score_display.next();
+                        app.state.status_message = Some((
+                            format!("Scores shown as {}", app.state.score_display),
+                            std::time::Instant::now(),
+                        ));
+                    }
+                    // Allow/deny the selected row's sender, persisted to the list file
+                    KeyCode::Char('a') => edit_list(&mut app, &lists, ListKind::Allow),
+                    KeyCode::Char('d') => edit_list(&mut app, &lists, ListKind::Deny),
+                    // Confirm (t) or refute (n) the selected row's verdict for retraining
+                    KeyCode::Char('t') => label_selected(&mut app, dataset_csv.as_deref(), true),
+                    KeyCode::Char('n') => label_selected(&mut app, dataset_csv.as_deref(), false),
                     KeyCode::Enter => {}
                     _ => {}
                 },
@@ -184,26 +407,70 @@ pub async fn run_tui(
     }
 
     // Restore Terminal
-    disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
-    terminal.show_cursor()?;
+    restore_terminal(mouse_capture)?;
+
+    // Recap on the normal screen so it survives the alternate screen teardown
+    println!(
+        "{}",
+        session_summary(&app.state.stats, started.elapsed(), &units)
+    );
 
     Ok(())
 }
 
+/// One-line recap of the session printed after the TUI exits
+fn session_summary(stats: &SentinelStats, uptime: Duration, units: &Units) -> String {
+    let secs = uptime.as_secs();
+    format!(
+        "BeeTrap session: {} observed | {} scanned | {} detected | {} trapped | {} ZK proofs | {} saved | uptime {}h{:02}m{:02}s",
+        stats.total_observed,
+        stats.total_scanned,
+        stats.total_detected,
+        stats.total_trapped,
+        stats.zk_proofs_generated,
+        units.monitored_amount(stats.eth_saved),
+        secs / 3600,
+        (secs / 60) % 60,
+        secs % 60
+    )
+}
+
+/// Undo raw mode, the alternate screen and (if enabled) mouse capture
+fn restore_terminal(mouse_capture: bool) -> io::Result<()> {
+    disable_raw_mode()?;
+    if mouse_capture {
+        execute!(io::stdout(), DisableMouseCapture)?;
+    }
+    execute!(io::stdout(), LeaveAlternateScreen, crossterm::cursor::Show)
+}
+
+/// Restore the terminal before the panic message prints, then defer to the
+/// previous hook. Covers panics on any thread, including spawned tasks.
+fn install_panic_hook(mouse_capture: bool) {
+    let original = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let _ = restore_terminal(mouse_capture);
+        original(info);
+    }));
+}
+
 fn ui(f: &mut Frame, app: &mut App) {
+    let theme = app.theme;
     // 1. Layouts
     //     .split(f.area()); // Fixed deprecated size()
+    let layout = app.state.layout.clone();
+    let show_bottom = !layout.hide_economic || !layout.hide_logs;
+
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Length(3),
             Constraint::Min(0),
-            Constraint::Length(12),
+            Constraint::Length(if show_bottom && layout.fullscreen.is_none() {
+                12
+            } else {
+                0
+            }),
         ])
         .split(f.area());
 
@@ -211,68 +478,65 @@ fn ui(f: &mut Frame, app: &mut App) {
     let main_area = chunks[1];
     let bottom_area = chunks[2];
 
-    let main_chunks = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
-        .split(main_area);
-
-    let left_panel = main_chunks[0]; // Tx Table
-    let right_panel = main_chunks[1]; // AI Insight
+    // 2. Header
+    render_header(f, app, header_area);
 
-    let bottom_chunks = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
-        .split(bottom_area);
+    // Panels not drawn this frame must not keep stale click areas
+    app.state.table_area = (0, 0, 0, 0);
+    app.state.ai_insight_area = (0, 0, 0, 0);
+    app.state.logs_area = (0, 0, 0, 0);
 
-    let stats_panel = bottom_chunks[0]; // Economic Impact
-    let logs_panel = bottom_chunks[1]; // System Logs
+    if let Some(panel) = layout.fullscreen {
+        // Full-screen: one panel owns everything below the header
+        match panel {
+            Panel::Mempool => render_tx_table(f, app, main_area),
+            Panel::Insight => render_ai_insight(f, app, main_area),
+            Panel::Economic => render_economic_impact(f, app, main_area),
+            Panel::Logs => render_logs(f, app, main_area),
+        }
+    } else {
+        let main_chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(if layout.hide_insight {
+                [Constraint::Percentage(100), Constraint::Percentage(0)]
+            } else {
+                [Constraint::Percentage(50), Constraint::Percentage(50)]
+            })
+            .split(main_area);
 
-    // 2. Header
-    render_header(f, app, header_area);
+        let left_panel = main_chunks[0]; // Tx Table
+        let right_panel = main_chunks[1]; // AI Insight
 
-    // 3. Tx Table (Left)
-    render_tx_table(f, app, left_panel);
+        let bottom_chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(match (layout.hide_economic, layout.hide_logs) {
+                (false, true) => [Constraint::Percentage(100), Constraint::Percentage(0)],
+                (true, false) => [Constraint::Percentage(0), Constraint::Percentage(100)],
+                _ => [Constraint::Percentage(50), Constraint::Percentage(50)],
+            })
+            .split(bottom_area);
 
-    // 4. AI Insight (Right)
-    render_ai_insight(f, app, right_panel);
+        let stats_panel = bottom_chunks[0]; // Economic Impact
+        let logs_panel = bottom_chunks[1]; // System Logs
 
-    // 5. Economic Impact (Bottom Left)
-    render_economic_impact(f, app, stats_panel);
+        // 3. Tx Table (Left)
+        render_tx_table(f, app, left_panel);
 
-    // 6. Logs (Bottom Right)
-    app.state.logs_area = (
-        logs_panel.x,
-        logs_panel.y,
-        logs_panel.width,
-        logs_panel.height,
-    );
-    let logs: Vec<ListItem> = app
-        .state
-        .logs
-        .iter()
-        .rev() // Show newest at top? Or render normally and auto-scroll? Usually logs are new at bottom.
-        // If we use List, we can reverse to show newest at top if we want.
-        // Let's show newest at top for visibility.
-        .map(|m| {
-            let content = Line::from(Span::raw(m));
-            ListItem::new(content)
-        })
-        .collect();
+        // 4. AI Insight (Right)
+        if !layout.hide_insight {
+            render_ai_insight(f, app, right_panel);
+        }
 
-    // 6. Logs (Bottom Right)
-    app.state.logs_area = (
-        logs_panel.x,
-        logs_panel.y,
-        logs_panel.width,
-        logs_panel.height,
-    );
+        // 5. Economic Impact (Bottom Left)
+        if !layout.hide_economic {
+            render_economic_impact(f, app, stats_panel);
+        }
 
-    let logs_list = List::new(logs).block(
-        Block::default()
-            .borders(Borders::ALL)
-            .title("Operation Logs"),
-    );
-    f.render_widget(logs_list, logs_panel);
+        // 6. Logs (Bottom Right)
+        if !layout.hide_logs {
+            render_logs(f, app, logs_panel);
+        }
+    }
 
     // 7. Status Message Overlay (Centered at bottom of header or top of main)
     if let Some((msg, time)) = &app.state.status_message {
@@ -280,13 +544,43 @@ fn ui(f: &mut Frame, app: &mut App) {
             let area = centered_rect(60, 3, f.area());
             let block = Block::default()
                 .borders(Borders::ALL)
-                .style(Style::default().bg(Color::Blue).fg(Color::White));
+                .style(Style::default().bg(theme.overlay).fg(theme.text));
             let p = Paragraph::new(msg.clone())
                 .block(block)
                 .alignment(ratatui::layout::Alignment::Center);
             f.render_widget(p, area);
         }
     }
+
+    // 8. Startup Summary Overlay (first seconds after launch)
+    if let Some((lines, received)) = &app.state.startup_summary {
+        if received.elapsed() < STARTUP_SUMMARY_DURATION {
+            let full = f.area();
+            let width = (lines.iter().map(|l| l.chars().count()).max().unwrap_or(0) as u16 + 4)
+                .min(full.width);
+            let height = (lines.len() as u16 + 2).min(full.height);
+            let area = Rect::new(
+                full.x + (full.width - width) / 2,
+                full.y + (full.height - height) / 2,
+                width,
+                height,
+            );
+            let p = Paragraph::new(
+                lines
+                    .iter()
+                    .map(|l| Line::from(l.as_str()))
+                    .collect::<Vec<_>>(),
+            )
+            .block(
+                Block::default()
+                    .title(" Active Configuration ")
+                    .borders(Borders::ALL),
+            )
+            .style(Style::default().bg(theme.overlay).fg(theme.text));
+            f.render_widget(Clear, area);
+            f.render_widget(p, area);
+        }
+    }
 }
 
 // Helper for centering
@@ -310,16 +604,39 @@ fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
         .split(popup_layout[1])[1]
 }
 
+fn render_logs(f: &mut Frame, app: &mut App, area: Rect) {
+    app.state.logs_area = (area.x, area.y, area.width, area.height);
+
+    let logs: Vec<ListItem> = app
+        .state
+        .logs
+        .iter()
+        .rev() // Show newest at top? Or render normally and auto-scroll? Usually logs are new at bottom.
+        // If we use List, we can reverse to show newest at top if we want.
+        // Let's show newest at top for visibility.
+        .map(|m| {
+            let content = Line::from(Span::raw(m));
+            ListItem::new(content)
+        })
+        .collect();
+
+    let logs_list = List::new(logs).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Operation Logs"),
+    );
+    f.render_widget(logs_list, area);
+}
+
 fn render_header(f: &mut Frame, app: &App, area: Rect) {
-    let status_color = if app.state.network.connected {
-        Color::Green
-    } else {
-        Color::Red
-    };
-    let status_text = if app.state.network.connected {
-        "ONLINE"
+    let theme = app.theme;
+    let network = &app.state.network;
+    let (status_text, status_color) = if network.connected {
+        ("ONLINE", theme.good)
+    } else if network.reconnecting {
+        ("RECONNECTING", theme.warning)
     } else {
-        "OFFLINE"
+        ("OFFLINE", theme.danger)
     };
 
     let time = Local::now().format("%H:%M:%S").to_string();
@@ -328,7 +645,7 @@ fn render_header(f: &mut Frame, app: &App, area: Rect) {
         Span::styled(
             "BeesTrap - MAV DEFENSE AGENT",
             Style::default()
-                .fg(Color::Cyan)
+                .fg(theme.title)
                 .add_modifier(Modifier::BOLD),
         ),
         Span::raw(" | "),
@@ -337,11 +654,95 @@ fn render_header(f: &mut Frame, app: &App, area: Rect) {
             Style::default().fg(status_color),
         ),
         Span::raw(" | "),
+        match app.state.warmup {
+            Some((seen, total)) if seen < total => Span::styled(
+                format!("WARMING UP ({}/{})", seen, total),
+                Style::default()
+                    .fg(theme.warning)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            _ if app.state.execution_degraded => Span::styled(
+                "EXEC DEGRADED (scoring only)",
+                Style::default()
+                    .fg(theme.text)
+                    .bg(theme.danger)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            _ if app.state.trap_window == Some(false) => Span::styled(
+                "PAUSED (schedule)",
+                Style::default()
+                    .fg(theme.warning)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            _ if app.armed() == Some(false) => Span::styled(
+                "SAFE",
+                Style::default()
+                    .fg(theme.warning)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            _ => Span::styled(
+                "ARMED",
+                Style::default().fg(theme.good).add_modifier(Modifier::BOLD),
+            ),
+        },
+        Span::raw(" | "),
+        Span::styled(
+            match app.state.reorgs {
+                0 => format!("Block: #{}", app.state.network.block_number),
+                n => format!(
+                    "Block: #{} ({} reorg{})",
+                    app.state.network.block_number,
+                    n,
+                    if n == 1 { "" } else { "s" }
+                ),
+            },
+            Style::default().fg(theme.accent),
+        ),
+        Span::raw(" | "),
+        match &app.state.execution_network {
+            Some(exec) if exec.connected => Span::styled(
+                format!(
+                    "Exec Chain {}: ONLINE #{}",
+                    exec.chain_id, exec.block_number
+                ),
+                Style::default().fg(theme.good),
+            ),
+            Some(exec) => Span::styled(
+                format!("Exec Chain {}: OFFLINE", exec.chain_id),
+                Style::default().fg(theme.danger),
+            ),
+            None => Span::styled("Exec Chain: --", Style::default().fg(theme.faint)),
+        },
+        Span::raw(" | "),
         Span::styled(
-            format!("Block: #{}", app.state.network.block_number),
-            Style::default().fg(Color::Yellow),
+            match app.state.execution_gas_price {
+                Some(price) => format!("Exec Gas: {}", app.state.units.execution_gas(price)),
+                None => "Exec Gas: --".to_string(),
+            },
+            Style::default().fg(theme.special),
         ),
         Span::raw(" | "),
+        match app.state.wallet_balance {
+            Some((balance, true)) => Span::styled(
+                format!(
+                    "LOW BALANCE: {}",
+                    app.state.units.execution_amount(balance as f64 / 1e18)
+                ),
+                Style::default()
+                    .fg(theme.text)
+                    .bg(theme.danger)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Some((balance, false)) => Span::styled(
+                format!(
+                    "Wallet: {}",
+                    app.state.units.execution_amount(balance as f64 / 1e18)
+                ),
+                Style::default().fg(theme.good),
+            ),
+            None => Span::raw("Wallet: --"),
+        },
+        Span::raw(" | "),
         Span::raw(time),
     ];
 
@@ -349,7 +750,7 @@ fn render_header(f: &mut Frame, app: &App, area: Rect) {
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::Cyan)),
+                .border_style(Style::default().fg(theme.title)),
         )
         .alignment(ratatui::layout::Alignment::Center);
 
@@ -357,16 +758,30 @@ fn render_header(f: &mut Frame, app: &App, area: Rect) {
 }
 
 fn render_tx_table(f: &mut Frame, app: &mut App, area: Rect) {
+    let theme = app.theme;
     // Store area for click detection
     app.state.table_area = (area.x, area.y, area.width, area.height);
 
-    let header_cells = ["Time", "Hash", "Value", "Gas", "Status"]
+    let header_cells = ["Age", "Hash", "Target", "Value", "Gas", "Status"]
         .iter()
-        .map(|h| Cell::from(*h).style(Style::default().fg(Color::Yellow)));
+        .map(|h| Cell::from(*h).style(Style::default().fg(theme.accent)));
     let header = Row::new(header_cells).height(1).bottom_margin(1);
 
+    let units = app.state.units;
     let rows = app.state.recent_transactions.iter().map(|tx| {
-        let status_text = if let Some(prob) = tx.probability {
+        let status_text = if tx.blob_fee_gwei.is_some() {
+            "BLOB (unscored)"
+        } else if tx.unscored == Some(SkipReason::PlainTransfer) {
+            "TRANSFER (unscored)"
+        } else if tx.unscored == Some(SkipReason::LowPriorityFee) {
+            "SAFE (low tip)"
+        } else if tx.unscored == Some(SkipReason::Allowlisted) {
+            "SAFE (allowlist)"
+        } else if tx.unscored == Some(SkipReason::OversizedInput) {
+            "OVERSIZED (unscored)"
+        } else if tx.inference_failed {
+            "INFERENCE FAILED"
+        } else if let Some(prob) = tx.probability {
             if prob >= 0.0 {
                 // Just checked it exists
                 if tx.suspicious {
@@ -381,26 +796,50 @@ fn render_tx_table(f: &mut Frame, app: &mut App, area: Rect) {
             "Pending"
         };
 
-        let status_color = if let Some(_) = tx.probability {
+        let status_color = if tx.blob_fee_gwei.is_some() {
+            theme.special
+        } else if tx.unscored == Some(SkipReason::PlainTransfer) {
+            theme.faint
+        } else if tx.unscored == Some(SkipReason::OversizedInput) {
+            theme.warning
+        } else if tx.unscored.is_some() {
+            theme.good
+        } else if tx.inference_failed {
+            theme.warning
+        } else if let Some(_) = tx.probability {
             if tx.suspicious {
-                Color::Red
+                theme.danger
             } else {
-                Color::Green
+                theme.good
             }
         } else {
-            Color::White
+            theme.text
         };
 
+        let mut status_text = status_text.to_string();
+        if let (Some(prob), None) = (tx.probability, tx.unscored) {
+            status_text.push(' ');
+            status_text.push_str(&format_score(
+                prob,
+                app.state.model_task,
+                app.state.score_display,
+                &units,
+            ));
+        }
+        if let Some(label) = tx.label {
+            status_text.push_str(&format!(" [{}]", label.short()));
+        }
         let cells = vec![
-            Cell::from("00:00:00"), // TODO: Proper Time
+            Cell::from(format_age(tx.received_at.elapsed())),
             Cell::from(tx.short_hash.clone()),
-            Cell::from(format!("{:.4} E", tx.value_eth)),
+            Cell::from(tx.target.to_string()),
+            Cell::from(units.monitored_amount(tx.value_eth)),
             Cell::from(format!("{:.0}", tx.gas_gwei)),
             Cell::from(status_text).style(Style::default().fg(status_color)),
         ];
         Row::new(cells)
             .height(1)
-            .style(Style::default().fg(Color::Gray))
+            .style(Style::default().fg(theme.muted))
     });
 
     let t = Table::new(
@@ -408,7 +847,8 @@ fn render_tx_table(f: &mut Frame, app: &mut App, area: Rect) {
         [
             Constraint::Length(10),
             Constraint::Length(12),
-            Constraint::Length(10),
+            Constraint::Length(8),
+            Constraint::Length((units.precision + units.monitored.symbol.len()) as u16 + 5),
             Constraint::Length(8),
             Constraint::Min(10),
         ],
@@ -425,7 +865,116 @@ fn render_tx_table(f: &mut Frame, app: &mut App, area: Rect) {
     f.render_stateful_widget(t, area, &mut app.table_state);
 }
 
+/// Bar height a sparkline point is scaled into
+const SPARKLINE_RESOLUTION: f64 = 1000.0;
+
+/// Scale ETH values onto `0..=SPARKLINE_RESOLUTION` relative to their max,
+/// returning the points and that max. Integer bars would otherwise flatten
+/// sub-unit savings to zero.
+fn sparkline_points(values: &[f64]) -> (Vec<u64>, f64) {
+    let max = values.iter().copied().fold(0.0, f64::max);
+    if max <= 0.0 {
+        return (vec![0; values.len()], 0.0);
+    }
+    let points = values
+        .iter()
+        .map(|v| (v.max(0.0) / max * SPARKLINE_RESOLUTION).round() as u64)
+        .collect();
+    (points, max)
+}
+
+/// Put the selected transaction's sender on `kind` and confirm in the status overlay
+fn edit_list(app: &mut App, lists: &SharedLists, kind: ListKind) {
+    let Some(from) = app
+        .table_state
+        .selected()
+        .and_then(|i| app.state.recent_transactions.get(i))
+        .map(|tx| tx.from.clone())
+    else {
+        return;
+    };
+    let result = lists
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .add(kind, &from);
+    let msg = match result {
+        Ok(true) => format!("Added {} to the {}", from, kind),
+        Ok(false) => format!("{} is already on the {}", from, kind),
+        Err(e) => format!("Failed to save the {}: {}", kind, e),
+    };
+    app.state
+        .logs
+        .push(format!("{} {}", Local::now().format("%H:%M:%S"), msg));
+    app.state.status_message = Some((msg, std::time::Instant::now()));
+}
+
+/// Label the selected row as a true/false positive/negative and persist
+/// it to `DATASET_CSV`
+fn label_selected(app: &mut App, dataset_csv: Option<&str>, confirmed: bool) {
+    let Some(tx) = app
+        .table_state
+        .selected()
+        .and_then(|i| app.state.recent_transactions.get_mut(i))
+    else {
+        return;
+    };
+    let label = ReviewLabel::from_review(tx.suspicious, confirmed);
+    let msg = match dataset_csv {
+        None => "Set DATASET_CSV to record labels".to_string(),
+        Some(path) => match crate::dataset::append_label(path, &tx.hash, &tx.from, label) {
+            Ok(()) => {
+                tx.label = Some(label);
+                app.state.labels.insert(tx.hash.clone(), label);
+                format!("Labelled {} as {}", tx.short_hash, label)
+            }
+            Err(e) => format!("Failed to save label: {}", e),
+        },
+    };
+    app.state
+        .logs
+        .push(format!("{} {}", Local::now().format("%H:%M:%S"), msg));
+    app.state.status_message = Some((msg, std::time::Instant::now()));
+}
+
+/// The full address, or `unknown` when it failed to parse upstream
+fn display_address(address: &str) -> &str {
+    if is_address(address) {
+        address
+    } else {
+        UNKNOWN
+    }
+}
+
+/// A model score: a probability in the chosen display, or a native amount
+/// for profit estimates
+fn format_score(score: f32, task: ModelTask, display: ScoreDisplay, units: &Units) -> String {
+    match task {
+        ModelTask::Classification => display.probability(score),
+        ModelTask::Regression => units.monitored_amount(score as f64),
+    }
+}
+
+/// `proof: 12.3KB, gas: 0.0040 ETH`, the fee in the execution chain's currency
+fn format_trap_cost(cost: &TrapCost, units: &Units) -> String {
+    format!(
+        "proof: {:.1}KB, gas: {}",
+        cost.proof_kb(),
+        units.execution_amount(cost.cost_native())
+    )
+}
+
+/// Compact age for the table: `42s`, `3m05s`
+fn format_age(age: Duration) -> String {
+    let secs = age.as_secs();
+    if secs < 60 {
+        format!("{}s", secs)
+    } else {
+        format!("{}m{:02}s", secs / 60, secs % 60)
+    }
+}
+
 fn render_ai_insight(f: &mut Frame, app: &mut App, area: Rect) {
+    let theme = app.theme;
     app.state.ai_insight_area = (area.x, area.y, area.width, area.height);
 
     let block = Block::default()
@@ -446,54 +995,240 @@ fn render_ai_insight(f: &mut Frame, app: &mut App, area: Rect) {
             // Uh oh, TransactionSummary only has visual info.
             // For now, let's just show what we have in summary + Mock confidence
 
-            let text = vec![
+            let mut text = vec![
                 Line::from(vec![
                     Span::raw("Hash: "),
-                    Span::styled(&tx.hash, Style::default().fg(Color::White)),
+                    Span::styled(&tx.hash, Style::default().fg(theme.text)),
+                ]),
+                Line::from(vec![
+                    Span::raw("From: "),
+                    Span::styled(
+                        display_address(&tx.from),
+                        Style::default().fg(if is_address(&tx.from) {
+                            theme.value
+                        } else {
+                            theme.warning
+                        }),
+                    ),
+                ]),
+                Line::from(vec![
+                    Span::raw("Target: "),
+                    Span::styled(
+                        match &tx.target_address {
+                            Some(addr) => format!("{} {}", tx.target, display_address(addr)),
+                            None => "-".to_string(),
+                        },
+                        Style::default().fg(theme.value),
+                    ),
                 ]),
                 Line::from(vec![
                     Span::raw("Value: "),
                     Span::styled(
-                        format!("{:.4} ETH", tx.value_eth),
-                        Style::default().fg(Color::Cyan),
+                        app.state.units.monitored_amount(tx.value_eth),
+                        Style::default().fg(theme.value),
                     ),
                 ]),
                 Line::from(vec![
                     Span::raw("Gas Price: "),
                     Span::styled(
                         format!("{:.2} Gwei", tx.gas_gwei),
-                        Style::default().fg(Color::Cyan),
+                        Style::default().fg(theme.value),
+                    ),
+                ]),
+                Line::from(vec![
+                    Span::raw("Blob Fee: "),
+                    Span::styled(
+                        tx.blob_fee_gwei
+                            .map_or("-".to_string(), |f| format!("{:.2} Gwei", f)),
+                        Style::default().fg(theme.value),
+                    ),
+                ]),
+                Line::from(vec![
+                    Span::raw("V4 Pool: "),
+                    Span::styled(
+                        tx.pool.as_deref().unwrap_or("-"),
+                        Style::default().fg(theme.value),
                     ),
                 ]),
                 Line::from(""),
                 Line::from(vec![
-                    Span::raw("Predator Probability: "),
+                    Span::raw(format!("{}: ", app.state.model_task.score_label())),
                     Span::styled(
                         if let Some(prob) = tx.probability {
-                            format!("{:.1}%", prob * 100.0)
+                            format_score(
+                                prob,
+                                app.state.model_task,
+                                app.state.score_display,
+                                &app.state.units,
+                            )
+                        } else if tx.inference_failed {
+                            "Inference failed (not scored)".to_string()
                         } else {
                             "Processing...".to_string()
                         },
                         Style::default()
-                            .fg(if tx.suspicious {
-                                Color::Red
+                            .fg(if tx.inference_failed {
+                                theme.warning
+                            } else if tx.suspicious {
+                                theme.danger
                             } else if tx.probability.is_some() {
-                                Color::Green
+                                theme.good
                             } else {
-                                Color::White
+                                theme.text
                             })
                             .add_modifier(Modifier::BOLD),
                     ),
                 ]),
-                Line::from(""),
                 Line::from(vec![
-                    Span::raw("Etherscan Link: "),
+                    Span::raw("Sender Avg: "),
                     Span::styled(
-                        format!("https://etherscan.io/tx/{}", tx.hash),
-                        Style::default().fg(Color::Blue),
+                        tx.address_score.map_or("-".to_string(), |a| a.to_string()),
+                        Style::default().fg(theme.value),
+                    ),
+                ]),
+                Line::from(vec![
+                    Span::raw("Cluster: "),
+                    Span::styled(
+                        tx.cluster.as_ref().map_or("-".to_string(), |members| {
+                            format!(
+                                "{} linked: {}",
+                                members.len(),
+                                members
+                                    .iter()
+                                    .map(|m| short_address(m))
+                                    .collect::<Vec<_>>()
+                                    .join(", ")
+                            )
+                        }),
+                        Style::default().fg(if tx.cluster.is_some() {
+                            theme.danger
+                        } else {
+                            theme.value
+                        }),
+                    ),
+                ]),
+                Line::from(vec![
+                    Span::raw("Sender Txs: "),
+                    Span::styled(
+                        app.state
+                            .sender_tx_counts
+                            .get(&tx.from.to_lowercase())
+                            .map_or("-".to_string(), |&count| {
+                                format!("{} ({})", count, crate::history::activity_label(count))
+                            }),
+                        Style::default().fg(theme.value),
+                    ),
+                ]),
+                Line::from(vec![
+                    Span::raw("Decision: "),
+                    Span::styled(
+                        tx.decision.as_ref().map_or("-".to_string(), |d| {
+                            format!(
+                                "{} vs {} ({})",
+                                format_score(
+                                    d.score,
+                                    app.state.model_task,
+                                    app.state.score_display,
+                                    &app.state.units
+                                ),
+                                format_score(
+                                    d.threshold,
+                                    app.state.model_task,
+                                    app.state.score_display,
+                                    &app.state.units
+                                ),
+                                d.decided_by
+                            )
+                        }),
+                        Style::default().fg(theme.value),
+                    ),
+                ]),
+                Line::from(vec![
+                    Span::raw("Context: "),
+                    Span::styled(
+                        tx.context_note.as_deref().unwrap_or("-"),
+                        Style::default().fg(if tx.context_note.is_some() {
+                            theme.warning
+                        } else {
+                            theme.faint
+                        }),
+                    ),
+                ]),
+                Line::from(vec![
+                    Span::raw("Gas Escalation: "),
+                    Span::styled(
+                        tx.gas_escalation.map_or("-".to_string(), |e| e.to_string()),
+                        Style::default().fg(if tx.gas_escalation.is_some() {
+                            theme.warning
+                        } else {
+                            theme.faint
+                        }),
+                    ),
+                ]),
+                Line::from(vec![
+                    Span::raw("Trap: "),
+                    Span::styled(
+                        tx.trap_cost
+                            .map_or("-".to_string(), |c| format_trap_cost(&c, &app.state.units)),
+                        Style::default().fg(if tx.trap_cost.is_some() {
+                            theme.value
+                        } else {
+                            theme.faint
+                        }),
                     ),
                 ]),
             ];
+            // A malformed hash would make a dead link
+            if is_tx_hash(&tx.hash) {
+                text.push(Line::from(""));
+                text.push(Line::from(vec![
+                    Span::raw("Etherscan Link: "),
+                    Span::styled(
+                        format!("https://etherscan.io/tx/{}", tx.hash),
+                        Style::default().fg(theme.link),
+                    ),
+                ]));
+            }
+
+            // Ranked contributors (Δscore per +1σ), once the processor has answered
+            if let Some((_, contributions)) = app
+                .state
+                .explanation
+                .as_ref()
+                .filter(|(hash, _)| *hash == tx.hash)
+            {
+                text.push(Line::from(""));
+                text.push(Line::from(Span::styled(
+                    match app.state.model_task {
+                        ModelTask::Classification => "Top Contributors (Δp per +1σ):",
+                        ModelTask::Regression => "Top Contributors (Δprofit per +1σ):",
+                    },
+                    Style::default().add_modifier(Modifier::BOLD),
+                )));
+                for c in contributions {
+                    text.push(Line::from(vec![
+                        Span::raw(format!("  {:<18}", c.feature)),
+                        Span::styled(
+                            match app.state.model_task {
+                                ModelTask::Classification => {
+                                    app.state.score_display.probability_delta(c.delta)
+                                }
+                                ModelTask::Regression => format!(
+                                    "{:+.*} {}",
+                                    app.state.units.precision,
+                                    c.delta,
+                                    app.state.units.monitored.symbol
+                                ),
+                            },
+                            Style::default().fg(if c.delta > 0.0 {
+                                theme.danger
+                            } else {
+                                theme.good
+                            }),
+                        ),
+                    ]));
+                }
+            }
 
             let p = Paragraph::new(text).wrap(Wrap { trim: true });
             f.render_widget(p, inner_area);
@@ -512,69 +1247,428 @@ fn render_ai_insight(f: &mut Frame, app: &mut App, area: Rect) {
 }
 
 fn render_economic_impact(f: &mut Frame, app: &App, area: Rect) {
+    let theme = app.theme;
     let inner_area = area.inner(ratatui::layout::Margin {
         vertical: 1,
         horizontal: 1,
     });
 
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([Constraint::Length(4), Constraint::Min(0)])
-        .split(inner_area);
-
     // Metrics
     let eth_saved = app.state.stats.eth_saved;
-    let gas_saved = app.state.stats.gas_saved;
+    let gas_saved_wei = app.state.stats.gas_saved_wei;
+    let units = app.state.units;
     let efficiency = app.state.stats.efficiency_boost;
+    let sampling = format!(
+        "(mempool {:.2}% | scanned {:.2}% | {:.0}% sampled)",
+        app.state.stats.efficiency(EfficiencyBasis::Observed),
+        app.state.stats.efficiency(EfficiencyBasis::Scanned),
+        app.state.stats.sampling_rate() * 100.0
+    );
+    let skipped = &app.state.stats.skipped;
+    let skipped = [
+        ("Safe", skipped.below_threshold),
+        ("Low Value", skipped.below_value_floor),
+        ("Trapped", skipped.already_trapped),
+        ("Proof", skipped.proof_failed),
+        ("Submit", skipped.submit_failed),
+        ("Blob", skipped.blob),
+        ("Balance", skipped.low_balance),
+        ("Scoring Only", skipped.scoring_only),
+        ("Warmup", skipped.warmup),
+        ("Too Slow", skipped.too_slow),
+        ("Inference", skipped.inference_failed),
+        ("Transfer", skipped.plain_transfer),
+        ("Low Tip", skipped.low_priority_fee),
+        ("Coalesced", skipped.coalesced),
+        ("Replaced", skipped.replaced),
+        ("Allowlist", skipped.allowlisted),
+        ("Oversized", skipped.oversized_input),
+        ("Queue Full", skipped.submit_queue_full),
+        ("Outside Schedule", skipped.outside_schedule),
+        ("Disarmed", skipped.disarmed),
+        ("Exec Down", skipped.execution_degraded),
+        ("Mined", skipped.already_mined),
+    ]
+    .into_iter()
+    .filter(|(_, count)| *count > 0)
+    .map(|(label, count)| format!("{} {}", label, count))
+    .collect::<Vec<_>>();
+
+    // Largest savings first; ties keep a stable order
+    let mut by_reason: Vec<_> = app.state.stats.saved_by_reason.iter().collect();
+    by_reason.sort_by(|(a, x), (b, y)| {
+        y.eth_saved
+            .total_cmp(&x.eth_saved)
+            .then_with(|| a.to_string().cmp(&b.to_string()))
+    });
+    let by_reason = if by_reason.is_empty() {
+        "-".to_string()
+    } else {
+        by_reason
+            .iter()
+            .map(|(reason, saved)| {
+                format!(
+                    "{} {} ({})",
+                    reason,
+                    units.monitored_amount(saved.eth_saved),
+                    saved.trapped
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(" | ")
+    };
 
-    let stats_text = vec![
+    let mut stats_text = vec![
         Line::from(vec![
-            Span::raw("ETH Saved: "),
+            Span::raw(format!("{} Saved: ", units.monitored.symbol)),
             Span::styled(
-                format!("Ξ {:.4}", eth_saved),
+                units.monitored_amount(eth_saved),
                 Style::default()
-                    .fg(Color::Yellow)
+                    .fg(theme.accent)
                     .add_modifier(Modifier::BOLD),
             ),
         ]),
         Line::from(vec![
             Span::raw("Gas Prevented: "),
             Span::styled(
-                format!("{} Gwei", gas_saved),
+                units.monitored_gas(gas_saved_wei),
                 Style::default()
-                    .fg(Color::Yellow)
+                    .fg(theme.accent)
                     .add_modifier(Modifier::BOLD),
             ),
         ]),
+        Line::from(vec![
+            Span::raw("Trap Gas Spent: "),
+            Span::styled(
+                units.execution_amount(app.state.stats.trap_gas_spent_wei as f64 / 1e18),
+                Style::default().fg(theme.warning),
+            ),
+        ]),
         Line::from(vec![
             Span::raw("Efficiency Boost: "),
             Span::styled(
-                format!("+{:.2}%", efficiency),
-                Style::default().fg(Color::Green),
+                format!("+{:.2}% ", efficiency),
+                Style::default().fg(theme.good),
             ),
+            Span::styled(sampling, Style::default().fg(theme.muted)),
+        ]),
+        Line::from(vec![
+            Span::raw("By Reason: "),
+            Span::styled(by_reason, Style::default().fg(theme.text)),
         ]),
     ];
+    // RPC failures per endpoint, with the last minute's share
+    let now = std::time::Instant::now();
+    let health = &app.state.rpc_health;
+    let recent_errors: usize = RpcCall::ALL.iter().map(|c| health.recent(*c, now)).sum();
+    stats_text.push(Line::from(vec![
+        Span::raw("RPC Errors: "),
+        Span::styled(
+            RpcCall::ALL
+                .iter()
+                .map(|call| match health.recent(*call, now) {
+                    0 => format!("{} {} {}", call.endpoint(), call, health.total(*call)),
+                    recent => format!(
+                        "{} {} {} ({}/min)",
+                        call.endpoint(),
+                        call,
+                        health.total(*call),
+                        recent
+                    ),
+                })
+                .collect::<Vec<_>>()
+                .join(" | "),
+            Style::default().fg(if recent_errors > 0 {
+                theme.warning
+            } else {
+                theme.muted
+            }),
+        ),
+    ]));
+
+    // Shadow model A/B agreement, only when a shadow model is running
+    let stats = &app.state.stats;
+    if stats.shadow_compared > 0 {
+        stats_text.push(Line::from(vec![
+            Span::raw("Shadow Agree: "),
+            Span::styled(
+                format!(
+                    "{:.1}% ({} diverged / {})",
+                    stats.shadow_agreed as f64 / stats.shadow_compared as f64 * 100.0,
+                    stats.shadow_compared - stats.shadow_agreed,
+                    stats.shadow_compared
+                ),
+                Style::default().fg(theme.special),
+            ),
+        ]));
+    }
+
+    // Only the reasons that fired, wrapped at separators under the first one.
+    // Last, so a long list never pushes the other metrics out of the panel.
+    const SKIPPED_LABEL: &str = "Skipped: ";
+    if skipped.is_empty() {
+        stats_text.push(Line::from(vec![
+            Span::raw(SKIPPED_LABEL),
+            Span::styled("none", Style::default().fg(theme.muted)),
+        ]));
+    }
+    for (i, row) in wrap_joined(
+        &skipped,
+        " | ",
+        (inner_area.width as usize).saturating_sub(SKIPPED_LABEL.len()),
+    )
+    .into_iter()
+    .enumerate()
+    {
+        let label = if i == 0 {
+            SKIPPED_LABEL.to_string()
+        } else {
+            " ".repeat(SKIPPED_LABEL.len())
+        };
+        stats_text.push(Line::from(vec![
+            Span::raw(label),
+            Span::styled(row, Style::default().fg(theme.muted)),
+        ]));
+    }
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(stats_text.len() as u16),
+            Constraint::Min(0),
+        ])
+        .split(inner_area);
 
     let p = Paragraph::new(stats_text);
     f.render_widget(p, chunks[0]);
 
-    // Sparkline
-    // Need u64 data. AppState has history_saved (Vec<u64>)
-    // Sparkline works with &[u64]
-    let data = &app.state.stats.history_saved;
-    // Convert to u64 if needed, usually they are u64.
+    // Sparkline: scaled to the data's own range, so both dust and whales show
+    let (data, max) = sparkline_points(&app.state.stats.history_saved);
+    let title = if max > 0.0 {
+        format!(
+            "Funds Saved Over Time (max {})",
+            units.monitored_amount(max)
+        )
+    } else {
+        "Funds Saved Over Time".to_string()
+    };
 
     let sparkline = Sparkline::default()
-        .block(Block::default().title("Funds Saved Over Time"))
-        .data(data)
-        .style(Style::default().fg(Color::Green));
+        .block(Block::default().title(title))
+        .data(&data)
+        .style(Style::default().fg(theme.good));
     f.render_widget(sparkline, chunks[1]);
 
     f.render_widget(
         Block::default()
             .title("VANGUARD ECONOMIC IMPACT")
             .borders(Borders::ALL)
-            .border_style(Style::default().fg(Color::Yellow)),
+            .border_style(Style::default().fg(theme.accent)),
         area,
     );
 }
+
+/// Join `items` with `sep` into rows of at most `width` characters, breaking
+/// only between items. An item wider than `width` gets a row of its own.
+fn wrap_joined(items: &[String], sep: &str, width: usize) -> Vec<String> {
+    let mut rows: Vec<String> = Vec::new();
+    for item in items {
+        match rows.last_mut() {
+            Some(row)
+                if row.chars().count() + sep.chars().count() + item.chars().count() <= width =>
+            {
+                row.push_str(sep);
+                row.push_str(item);
+            }
+            _ => rows.push(item.clone()),
+        }
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{TransactionSummary, TxTarget};
+    use ratatui::{backend::TestBackend, buffer::Buffer};
+    use std::time::Instant;
+
+    fn summary(hash: &str, probability: Option<f32>, suspicious: bool) -> TransactionSummary {
+        TransactionSummary {
+            hash: hash.to_string(),
+            short_hash: format!("{}...", &hash[0..8]),
+            from: format!("0xaaaa{}", "a".repeat(36)),
+            from_short: "0xaaaa...".to_string(),
+            to_short: "0xbbbb...".to_string(),
+            value_eth: 1.5,
+            gas_gwei: 42.0,
+            suspicious,
+            probability,
+            pool: None,
+            target: TxTarget::Router,
+            target_address: None,
+            received_at: Instant::now(),
+            blob_fee_gwei: None,
+            context_note: None,
+            decision: None,
+            gas_escalation: None,
+            inference_failed: false,
+            address_score: None,
+            unscored: None,
+            trap_cost: None,
+            label: None,
+            cluster: None,
+        }
+    }
+
+    fn row_text(buffer: &Buffer, y: u16) -> String {
+        (0..buffer.area.width)
+            .map(|x| buffer[(x, y)].symbol())
+            .collect()
+    }
+
+    fn render(app: &mut App) -> Buffer {
+        let mut terminal = Terminal::new(TestBackend::new(160, 40)).unwrap();
+        terminal.draw(|f| ui(f, app)).unwrap();
+        terminal.backend().buffer().clone()
+    }
+
+    #[test]
+    fn renders_header_mempool_rows_and_stats() {
+        let mut app = App::new();
+        app.state.units.precision = 4;
+        app.state.network.connected = true;
+        app.state.network.block_number = 19_000_000;
+        app.state.recent_transactions = vec![
+            summary(&format!("0xdead{}", "0".repeat(60)), Some(0.97), true),
+            summary(&format!("0xbeef{}", "0".repeat(60)), Some(0.12), false),
+        ];
+        app.state.recent_transactions[1].label = Some(ReviewLabel::TrueNegative);
+        app.state.stats.eth_saved = 0.25;
+        app.state.stats.saved_by_reason.insert(
+            crate::types::DetectionReason::SandwichPattern,
+            crate::types::ReasonSavings {
+                trapped: 2,
+                eth_saved: 0.25,
+                gas_saved_wei: 0,
+            },
+        );
+        app.state.stats.skipped.below_threshold = 7;
+        for _ in 0..2 {
+            app.state
+                .rpc_health
+                .record(RpcCall::Fetch, std::time::Instant::now());
+        }
+
+        let buffer = render(&mut app);
+
+        // Header: bordered, title first, centered in the 3-row strip
+        let header = row_text(&buffer, 1);
+        assert!(header.contains("BeesTrap - MAV DEFENSE AGENT | WSS Ethereum: ONLINE"));
+        assert!(header.contains("Block: #19000000"));
+        assert_eq!(buffer[(0, 0)].symbol(), "┌");
+
+        // Mempool table: title on its border, column headings, then rows newest-first
+        assert!(row_text(&buffer, 3).starts_with("┌Live Mempool Activity"));
+        assert!(row_text(&buffer, 4).starts_with("│Age        Hash"));
+        let first = row_text(&buffer, 6);
+        assert!(first.starts_with('│'));
+        assert_eq!(
+            first.chars().skip(12).take(11).collect::<String>(),
+            "0xdead00..."
+        );
+        assert!(first.contains("Router"));
+        assert!(first.contains("1.5000 ETH"));
+        assert!(first.contains("MEV DETECTED 97.0%"));
+        assert!(row_text(&buffer, 7).contains("SAFE 12.0% [TN]"));
+
+        // Economic panel in the bottom 12 rows
+        let bottom: Vec<String> = (28..40).map(|y| row_text(&buffer, y)).collect();
+        assert!(bottom[0].starts_with("┌VANGUARD ECONOMIC IMPACT"));
+        assert!(bottom[1].contains("ETH Saved: 0.2500 ETH"));
+        assert!(bottom[3].contains("Trap Gas Spent: 0.0000 ETH"));
+        assert!(bottom[5].contains("By Reason: Sandwich Pattern 0.2500 ETH (2)"));
+        assert!(bottom[6]
+            .contains("RPC Errors: Mempool fetch 2 (2/min) | Exec estimate 0 | Exec submit 0"));
+        assert!(bottom[7].contains("Skipped: Safe 7 "));
+        assert!(!bottom[7].contains("Low Value"));
+    }
+
+    #[test]
+    fn skip_counts_wrap_between_counters() {
+        let items: Vec<String> = ["Safe 7", "Low Value 0", "Trapped 12", "Mined 0"]
+            .map(String::from)
+            .to_vec();
+
+        assert_eq!(
+            wrap_joined(&items, " | ", 24),
+            vec!["Safe 7 | Low Value 0", "Trapped 12 | Mined 0"]
+        );
+        assert_eq!(wrap_joined(&items, " | ", 8).len(), 4);
+        assert_eq!(wrap_joined(&items, " | ", 200).len(), 1);
+    }
+
+    #[test]
+    fn sparkline_scales_to_the_data_range() {
+        let (dust, max) = sparkline_points(&[0.000_001, 0.000_002, 0.000_004]);
+        assert_eq!(dust, vec![250, 500, 1000]);
+        assert_eq!(max, 0.000_004);
+
+        let (whale, _) = sparkline_points(&[500.0, 2000.0]);
+        assert_eq!(whale, vec![250, 1000]);
+
+        assert_eq!(sparkline_points(&[0.0, 0.0]), (vec![0, 0], 0.0));
+    }
+
+    #[test]
+    fn trap_cost_shows_proof_kb_and_execution_fee() {
+        let cost = TrapCost {
+            proof_bytes: 12_595,
+            gas_used: 400_000,
+            cost_wei: 4_000_000_000_000_000,
+        };
+        let units = Units {
+            execution: crate::chains::Chain::by_id(137),
+            ..Units::default()
+        };
+        assert_eq!(
+            format_trap_cost(&cost, &units),
+            "proof: 12.3KB, gas: 0.0040 POL"
+        );
+    }
+
+    #[test]
+    fn selected_row_shows_insight_panel() {
+        let mut app = App::new();
+        let hash = format!("0xdead{}", "0".repeat(60));
+        app.state.recent_transactions = vec![summary(&hash, Some(0.97), true)];
+
+        let without = render(&mut app);
+        let text: String = (0..40).map(|y| row_text(&without, y)).collect();
+        assert!(text.contains("Select a transaction to view AI analysis"));
+
+        app.table_state.select(Some(0));
+        let with = render(&mut app);
+        let text: String = (0..40).map(|y| row_text(&with, y)).collect();
+        assert!(text.contains("Predator Probability: 97.0%"));
+        assert!(text.contains(&format!("Hash: {}", hash)));
+        // The insight panel starts halfway across the main area
+        assert!(row_text(&with, 3).contains("┐┌AI Deep Insight"));
+    }
+
+    #[test]
+    fn malformed_addresses_and_hashes_render_safely() {
+        let mut app = App::new();
+        let mut tx = summary("0xnot-a-real-hash", Some(0.2), false);
+        tx.from = "0x12".to_string();
+        tx.target_address = Some(String::new());
+        app.state.recent_transactions = vec![tx];
+        app.table_state.select(Some(0));
+
+        let buffer = render(&mut app);
+        let text: String = (0..40).map(|y| row_text(&buffer, y)).collect();
+        assert!(text.contains("From: unknown"));
+        assert!(text.contains("Router unknown"));
+        assert!(!text.contains("Etherscan Link"));
+    }
+}
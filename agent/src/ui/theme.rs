@@ -0,0 +1,200 @@
+//! # UI Theme
+//!
+//! Named colors used by the `render_*` functions. `UI_THEME` selects a
+//! built-in theme or a TOML file; a file may start from a built-in via
+//! `base` and override individual colors:
+//!
+//! ```toml
+//! base = "colorblind-safe"
+//! danger = "#d55e00"
+//! muted = "gray"
+//! ```
+//!
+//! Colors accept names (`light-red`), `#rrggbb` or a 256-color index.
+
+use eyre::{Result, WrapErr};
+use ratatui::style::Color;
+use std::str::FromStr;
+use toml_edit::DocumentMut;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    /// App title and header border
+    pub title: Color,
+    /// Values in the insight panel
+    pub value: Color,
+    /// Table headings, block number, economic metrics and border
+    pub accent: Color,
+    /// Online, safe, armed
+    pub good: Color,
+    /// Reconnecting, warming up, context notes
+    pub warning: Color,
+    /// Offline, MEV detected, low balance
+    pub danger: Color,
+    /// Blob transactions, execution gas, shadow model stats
+    pub special: Color,
+    /// Primary text (hashes, pending status)
+    pub text: Color,
+    /// Table rows and secondary stats
+    pub muted: Color,
+    /// Placeholders for missing values
+    pub faint: Color,
+    pub link: Color,
+    /// Background of the status message overlay
+    pub overlay: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            title: Color::Cyan,
+            value: Color::Cyan,
+            accent: Color::Yellow,
+            good: Color::Green,
+            warning: Color::Yellow,
+            danger: Color::Red,
+            special: Color::Magenta,
+            text: Color::White,
+            muted: Color::Gray,
+            faint: Color::DarkGray,
+            link: Color::Blue,
+            overlay: Color::Blue,
+        }
+    }
+}
+
+impl Theme {
+    /// Bright variants only, readable on dim or washed-out terminals
+    pub fn high_contrast() -> Self {
+        Self {
+            title: Color::LightCyan,
+            value: Color::White,
+            accent: Color::LightYellow,
+            good: Color::LightGreen,
+            warning: Color::LightYellow,
+            danger: Color::LightRed,
+            special: Color::LightMagenta,
+            text: Color::White,
+            muted: Color::White,
+            faint: Color::Gray,
+            link: Color::LightBlue,
+            overlay: Color::Black,
+        }
+    }
+
+    /// Okabe-Ito palette: good/danger stay distinct without red-green contrast
+    pub fn colorblind_safe() -> Self {
+        Self {
+            title: Color::Rgb(86, 180, 233),
+            value: Color::Rgb(86, 180, 233),
+            accent: Color::Rgb(240, 228, 66),
+            good: Color::Rgb(0, 114, 178),
+            warning: Color::Rgb(230, 159, 0),
+            danger: Color::Rgb(213, 94, 0),
+            special: Color::Rgb(204, 121, 167),
+            text: Color::White,
+            muted: Color::Gray,
+            faint: Color::DarkGray,
+            link: Color::Rgb(86, 180, 233),
+            overlay: Color::Rgb(0, 114, 178),
+        }
+    }
+
+    /// Built-in theme by name
+    pub fn builtin(name: &str) -> Option<Self> {
+        match name {
+            "" | "default" => Some(Self::default()),
+            "high-contrast" => Some(Self::high_contrast()),
+            "colorblind-safe" => Some(Self::colorblind_safe()),
+            _ => None,
+        }
+    }
+
+    /// Resolve `UI_THEME`: a built-in name, otherwise a path to a TOML theme
+    pub fn resolve(spec: &str) -> Result<Self> {
+        match Self::builtin(spec) {
+            Some(theme) => Ok(theme),
+            None => {
+                let toml = std::fs::read_to_string(spec)
+                    .wrap_err_with(|| format!("Failed to read UI theme {}", spec))?;
+                Self::from_toml(&toml).wrap_err_with(|| format!("Invalid UI theme {}", spec))
+            }
+        }
+    }
+
+    pub fn from_toml(toml: &str) -> Result<Self> {
+        let doc: DocumentMut = toml.parse()?;
+        let value = |key: &str| {
+            doc[key]
+                .as_str()
+                .ok_or_else(|| eyre::eyre!("`{}` must be a string", key))
+        };
+
+        let mut theme = match doc.get("base") {
+            Some(_) => {
+                let base = value("base")?;
+                Self::builtin(base).ok_or_else(|| eyre::eyre!("Unknown base theme: {}", base))?
+            }
+            None => Self::default(),
+        };
+
+        for (key, _) in doc.iter() {
+            let slot = match key {
+                "base" => continue,
+                "title" => &mut theme.title,
+                "value" => &mut theme.value,
+                "accent" => &mut theme.accent,
+                "good" => &mut theme.good,
+                "warning" => &mut theme.warning,
+                "danger" => &mut theme.danger,
+                "special" => &mut theme.special,
+                "text" => &mut theme.text,
+                "muted" => &mut theme.muted,
+                "faint" => &mut theme.faint,
+                "link" => &mut theme.link,
+                "overlay" => &mut theme.overlay,
+                other => return Err(eyre::eyre!("Unknown theme color: {}", other)),
+            };
+            let raw = value(key)?;
+            *slot = Color::from_str(raw)
+                .map_err(|_| eyre::eyre!("Invalid color for `{}`: {}", key, raw))?;
+        }
+        Ok(theme)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_overrides_colors_on_top_of_a_base() {
+        let theme = Theme::from_toml(
+            r##"
+            base = "high-contrast"
+            danger = "#d55e00"
+            muted = "dark-gray"
+            "##,
+        )
+        .unwrap();
+        assert_eq!(theme.danger, Color::Rgb(213, 94, 0));
+        assert_eq!(theme.muted, Color::DarkGray);
+        assert_eq!(theme.good, Theme::high_contrast().good);
+    }
+
+    #[test]
+    fn rejects_unknown_keys_bases_and_colors() {
+        assert!(Theme::from_toml(r#"dangr = "red""#).is_err());
+        assert!(Theme::from_toml(r#"base = "solarized""#).is_err());
+        assert!(Theme::from_toml(r#"good = "not-a-color""#).is_err());
+        assert!(Theme::from_toml("good = 3").is_err());
+    }
+
+    #[test]
+    fn builtins_keep_good_and_danger_apart() {
+        for name in ["default", "high-contrast", "colorblind-safe"] {
+            let theme = Theme::builtin(name).unwrap();
+            assert_ne!(theme.good, theme.danger, "{}", name);
+        }
+    }
+}
@@ -203,6 +203,30 @@ contract BeeTrapHook is BaseTestHooks {
         emit PredatorStatusChanged(bot, status);
     }
 
+    /**
+     * @notice Records a verified detection without changing the bot's status (flag-only)
+     * @dev Emits ProofVerified and never writes isPredator, so a flag cannot clear a trap
+     * @param bot The detected address
+     * @param proof The ZK proof bytes from EZKL
+     * @param publicInputs The public inputs (normalized features) used in the proof
+     */
+    function recordProof(
+        address bot,
+        bytes calldata proof,
+        uint256[] calldata publicInputs
+    ) external {
+        if (msg.sender != AI_AGENT) {
+            revert OnlyAIAgent();
+        }
+
+        bool isValid = VERIFIER.verifyProof(proof, publicInputs);
+        emit ProofVerified(bot, isValid);
+
+        if (!isValid) {
+            revert InvalidZKProof();
+        }
+    }
+
     // ============ Hook Implementation ============
 
     function beforeSwap(
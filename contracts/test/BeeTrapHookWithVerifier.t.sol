@@ -73,6 +73,7 @@ contract BeeTrapHookWithVerifierTest is Test, Deployers {
 
     // ============ Events ============
     event PredatorStatusChanged(address indexed bot, bool status);
+    event ProofVerified(address indexed bot, bool valid);
     event PredatorTrapped(
         address indexed predator,
         uint24 feeApplied,
@@ -293,6 +294,57 @@ contract BeeTrapHookWithVerifierTest is Test, Deployers {
         );
     }
 
+    /**
+     * @notice Test that a flag-only record never clears a mark
+     */
+    function test_RecordProof_KeepsExistingMark() public {
+        // Arrange: a trap marks the bot
+        bytes memory proof = abi.encodePacked(_generateMockProof());
+        uint256[] memory publicInputs = _generateMockPublicInputs(PREDATOR_BOT);
+
+        vm.prank(AI_AGENT);
+        hookWithSimpleVerifier.markAsPredatorWithProof(
+            PREDATOR_BOT,
+            true,
+            proof,
+            publicInputs
+        );
+
+        // Act: a later, less confident detection is only recorded
+        vm.prank(AI_AGENT);
+        vm.expectEmit(true, false, false, true);
+        emit ProofVerified(PREDATOR_BOT, true);
+
+        hookWithSimpleVerifier.recordProof(PREDATOR_BOT, proof, publicInputs);
+
+        // Assert
+        assertTrue(
+            hookWithSimpleVerifier.isPredator(PREDATOR_BOT),
+            "Flag-only record must not unmark the bot"
+        );
+        assertEq(
+            simpleVerifier.verifyCallCount(),
+            2,
+            "Both submissions should be verified"
+        );
+    }
+
+    /**
+     * @notice Test that a flag-only record does not mark an unmarked bot
+     */
+    function test_RecordProof_DoesNotMark() public {
+        bytes memory proof = abi.encodePacked(_generateMockProof());
+        uint256[] memory publicInputs = _generateMockPublicInputs(PREDATOR_BOT);
+
+        vm.prank(AI_AGENT);
+        hookWithSimpleVerifier.recordProof(PREDATOR_BOT, proof, publicInputs);
+
+        assertFalse(
+            hookWithSimpleVerifier.isPredator(PREDATOR_BOT),
+            "Flag-only record must not mark the bot"
+        );
+    }
+
     // ============ Halo2 Verifier Specific Tests ============
 
     /**
@@ -0,0 +1,47 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.26;
+
+/**
+ * @title MockBeeTrapHook
+ * @notice Minimal stand-in for BeeTrapHook used by the agent's Anvil end-to-end test
+ * @dev Same `isPredator` / `markAsPredatorWithProof` ABI as the real hook, without
+ *      the pool, agent or verifier checks. Any non-empty proof is accepted.
+ */
+contract MockBeeTrapHook {
+    mapping(address => bool) public isPredator;
+
+    event PredatorStatusChanged(address indexed bot, bool status);
+
+    function markAsPredatorWithProof(
+        address bot,
+        bool status,
+        bytes calldata proof,
+        uint256[] calldata publicInputs
+    ) external {
+        require(proof.length > 0, "empty proof");
+        require(publicInputs.length > 0, "no public inputs");
+
+        isPredator[bot] = status;
+        emit PredatorStatusChanged(bot, status);
+    }
+}
+
+/**
+ * @title MockAgentNFT
+ * @notice Minimal stand-in for AgentNFT exposing an inline-JSON `tokenURI`
+ */
+contract MockAgentNFT {
+    function tokenURI(uint256) external pure returns (string memory) {
+        return 'data:application/json,{"name":"BeeTrap Agent","confidence_threshold":0.9}';
+    }
+
+    function markAsPredatorWithProof(
+        uint256,
+        address,
+        bool,
+        bytes calldata,
+        uint256[] calldata
+    ) external pure {
+        revert("use the hook directly");
+    }
+}